@@ -0,0 +1,695 @@
+//! parsing of the annotated struct and generation of the `Bfb` implementation
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    Attribute, Data, DeriveInput, Expr, ExprLit, Fields, GenericArgument, Ident, Lit, LitStr, Meta,
+    PathArguments, Token, Type, parse::ParseStream, punctuated::Punctuated, spanned::Spanned,
+};
+
+#[derive(Clone, Copy, PartialEq)]
+enum Direction {
+    In,
+    Out,
+}
+
+struct EventField {
+    ident: Ident,
+    direction: Direction,
+    with: Vec<Ident>,
+    /// doc comment of the field
+    comment: String,
+}
+
+struct DataField {
+    ident: Ident,
+    direction: Direction,
+    comment: String,
+}
+
+/// how the execution control of the function block is invoked
+enum Ecc {
+    /// `#[ecc(method)]` on the struct: hand-written method
+    Method(Ident),
+    /// `#[ecc]` on an `ExecutionControl<Self>` field: driven by a declarative chart
+    Field(Ident),
+}
+
+/// `#[plug(name, "TYPE")]` or `#[socket(name, "TYPE")]` on the struct
+struct AdapterAttr {
+    name: Ident,
+    type_name: LitStr,
+    plug: bool,
+}
+
+struct FbStruct {
+    ident: Ident,
+    /// doc comment of the struct
+    comment: String,
+    ecc: Ecc,
+    adapters: Vec<AdapterAttr>,
+    /// `#[timer]` on a `Timer` field
+    timer: Option<Ident>,
+    events: Vec<EventField>,
+    data: Vec<DataField>,
+}
+
+pub fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let fb = parse(input)?;
+
+    Ok(generate(&fb))
+}
+
+fn parse(input: DeriveInput) -> syn::Result<FbStruct> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new(
+            input.span(),
+            "Bfb can only be derived for structs",
+        ));
+    };
+
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new(
+            input.span(),
+            "Bfb can only be derived for structs with named fields",
+        ));
+    };
+
+    let mut ecc = None;
+    let mut adapters = vec![];
+
+    for attr in &input.attrs {
+        if attr.path().is_ident("ecc") {
+            ecc = Some(Ecc::Method(attr.parse_args::<Ident>()?));
+        }
+
+        let plug = attr.path().is_ident("plug");
+
+        if plug || attr.path().is_ident("socket") {
+            let (name, type_name) = attr.parse_args_with(|input: ParseStream| {
+                let name = input.parse::<Ident>()?;
+                input.parse::<Token![,]>()?;
+                Ok((name, input.parse::<LitStr>()?))
+            })?;
+
+            if adapters.iter().any(|a: &AdapterAttr| a.name == name) {
+                return Err(syn::Error::new(
+                    name.span(),
+                    format!("the adapter \"{name}\" is already declared"),
+                ));
+            }
+
+            adapters.push(AdapterAttr {
+                name,
+                type_name,
+                plug,
+            });
+        }
+    }
+
+    let mut has_instance_name = false;
+    let mut timer = None;
+    let mut events = vec![];
+    let mut data = vec![];
+
+    for field in &fields.named {
+        let ident = field.ident.clone().unwrap();
+
+        if ident == "instance_name" {
+            has_instance_name = true;
+        }
+
+        let mut with = None;
+
+        for attr in &field.attrs {
+            if attr.path().is_ident("with") {
+                let names =
+                    attr.parse_args_with(Punctuated::<Ident, Token![,]>::parse_terminated)?;
+                with = Some(names.into_iter().collect::<Vec<_>>());
+            }
+
+            if attr.path().is_ident("ecc") {
+                if ecc.is_some() {
+                    return Err(syn::Error::new(
+                        attr.span(),
+                        "the execution control is already declared",
+                    ));
+                }
+
+                ecc = Some(Ecc::Field(ident.clone()));
+            }
+
+            if attr.path().is_ident("timer") {
+                if timer.is_some() {
+                    return Err(syn::Error::new(
+                        attr.span(),
+                        "the timer is already declared",
+                    ));
+                }
+
+                timer = Some(ident.clone());
+            }
+        }
+
+        match port_kind(&field.ty) {
+            Some(("Event", direction)) => events.push(EventField {
+                ident,
+                direction,
+                with: with.unwrap_or_default(),
+                comment: doc_comment(&field.attrs),
+            }),
+            Some(("Data", direction)) => {
+                if with.is_some() {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        "#[with(...)] is only allowed on event fields",
+                    ));
+                }
+
+                data.push(DataField {
+                    ident,
+                    direction,
+                    comment: doc_comment(&field.attrs),
+                });
+            }
+            _ => {
+                if with.is_some() {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        "#[with(...)] is only allowed on event fields",
+                    ));
+                }
+            }
+        }
+    }
+
+    let Some(ecc) = ecc else {
+        return Err(syn::Error::new(
+            input.ident.span(),
+            "missing #[ecc] attribute on an `ExecutionControl<Self>` field \
+             or #[ecc(method)] attribute naming the execution control method",
+        ));
+    };
+
+    if !has_instance_name {
+        return Err(syn::Error::new(
+            input.ident.span(),
+            "Bfb requires a field called `instance_name`",
+        ));
+    }
+
+    // WITH associations need to reference data of the same direction as the event
+    for event in &events {
+        for name in &event.with {
+            match data.iter().find(|d| d.ident == *name) {
+                Some(d) if d.direction == event.direction => {}
+                Some(_) => {
+                    return Err(syn::Error::new(
+                        name.span(),
+                        format!(
+                            "\"{name}\" has a different direction than the event \"{}\"",
+                            event.ident
+                        ),
+                    ));
+                }
+                None => {
+                    return Err(syn::Error::new(
+                        name.span(),
+                        format!("unknown data \"{name}\""),
+                    ));
+                }
+            }
+        }
+    }
+
+    Ok(FbStruct {
+        comment: doc_comment(&input.attrs),
+        ident: input.ident,
+        ecc,
+        adapters,
+        timer,
+        events,
+        data,
+    })
+}
+
+/// returns the port kind (`Event` or `Data`) and direction of a field type like `Data<In, Bool>`
+fn port_kind(ty: &Type) -> Option<(&'static str, Direction)> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+
+    let segment = path.path.segments.last()?;
+
+    let kind = if segment.ident == "Event" {
+        "Event"
+    } else if segment.ident == "Data" {
+        "Data"
+    } else {
+        return None;
+    };
+
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+
+    let Some(GenericArgument::Type(Type::Path(direction))) = args.args.first() else {
+        return None;
+    };
+
+    let direction = direction.path.segments.last()?;
+
+    if direction.ident == "In" {
+        Some((kind, Direction::In))
+    } else if direction.ident == "Out" {
+        Some((kind, Direction::Out))
+    } else {
+        None
+    }
+}
+
+/// joins the lines of the `///` comments into a single line
+fn doc_comment(attrs: &[Attribute]) -> String {
+    let lines: Vec<_> = attrs
+        .iter()
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(meta) if meta.path.is_ident("doc") => match &meta.value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(text),
+                    ..
+                }) => Some(text.value().trim().to_string()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .filter(|line| !line.is_empty())
+        .collect();
+
+    lines.join(" ")
+}
+
+fn names<'a>(idents: impl Iterator<Item = &'a Ident>) -> Vec<String> {
+    idents.map(|i| i.to_string()).collect()
+}
+
+fn generate(fb: &FbStruct) -> TokenStream {
+    let ident = &fb.ident;
+
+    let event_in: Vec<_> = fb
+        .events
+        .iter()
+        .filter(|e| e.direction == Direction::In)
+        .map(|e| &e.ident)
+        .collect();
+    let event_in_names = names(event_in.iter().copied());
+
+    let event_out: Vec<_> = fb
+        .events
+        .iter()
+        .filter(|e| e.direction == Direction::Out)
+        .map(|e| &e.ident)
+        .collect();
+    let event_out_names = names(event_out.iter().copied());
+
+    let event_names = names(fb.events.iter().map(|e| &e.ident));
+    let event_with: Vec<Vec<String>> = fb.events.iter().map(|e| names(e.with.iter())).collect();
+
+    let data: Vec<_> = fb.data.iter().map(|d| &d.ident).collect();
+    let data_names = names(data.iter().copied());
+
+    let data_in: Vec<_> = fb
+        .data
+        .iter()
+        .filter(|d| d.direction == Direction::In)
+        .map(|d| &d.ident)
+        .collect();
+    let data_in_names = names(data_in.iter().copied());
+
+    let data_out: Vec<_> = fb
+        .data
+        .iter()
+        .filter(|d| d.direction == Direction::Out)
+        .map(|d| &d.ident)
+        .collect();
+    let data_out_names = names(data_out.iter().copied());
+
+    let type_name = ident.to_string();
+    let type_comment = &fb.comment;
+
+    let event_desc = |direction: Direction| {
+        let descs = fb
+            .events
+            .iter()
+            .filter(|e| e.direction == direction)
+            .map(|e| {
+                let name = e.ident.to_string();
+                let with = names(e.with.iter());
+                let comment = &e.comment;
+
+                quote! {
+                    ::iec_61499_fb_rs::fb::desc::EventDesc {
+                        name: #name.to_string(),
+                        with: vec![#(#with.to_string()),*],
+                        comment: #comment.to_string(),
+                    }
+                }
+            });
+
+        quote! { vec![#(#descs),*] }
+    };
+
+    let var_desc = |direction: Direction| {
+        let descs = fb
+            .data
+            .iter()
+            .filter(|d| d.direction == direction)
+            .map(|d| {
+                let field = &d.ident;
+                let name = d.ident.to_string();
+                let comment = &d.comment;
+
+                quote! {
+                    ::iec_61499_fb_rs::fb::desc::VarDesc {
+                        name: #name.to_string(),
+                        kind: self.#field.as_kind(),
+                        initial_value: None,
+                        comment: #comment.to_string(),
+                    }
+                }
+            });
+
+        quote! { vec![#(#descs),*] }
+    };
+
+    let event_input_descs = event_desc(Direction::In);
+    let event_output_descs = event_desc(Direction::Out);
+    let input_var_descs = var_desc(Direction::In);
+    let output_var_descs = var_desc(Direction::Out);
+
+    let ecc = match &fb.ecc {
+        Ecc::Method(method) => quote! {
+            fn invoke_execution_control(
+                &mut self,
+            ) -> ::std::result::Result<bool, ::iec_61499_fb_rs::fb::error::FbError> {
+                Ok(self.#method())
+            }
+        },
+        Ecc::Field(field) => quote! {
+            fn invoke_execution_control(
+                &mut self,
+            ) -> ::std::result::Result<bool, ::iec_61499_fb_rs::fb::error::FbError> {
+                ::iec_61499_fb_rs::fb::ecc::invoke(self)
+            }
+
+            fn ecc(&self) -> ::std::option::Option<::iec_61499_fb_rs::fb::ecc::EccDesc> {
+                Some(self.#field.chart().describe())
+            }
+
+            fn ec_state(&self) -> ::std::option::Option<&str> {
+                Some(self.#field.state())
+            }
+        },
+    };
+
+    let timer = match &fb.timer {
+        Some(field) => quote! {
+            fn deadline(&self) -> ::std::option::Option<::std::time::Duration> {
+                self.#field.deadline()
+            }
+
+            fn set_clock(
+                &mut self,
+                clock: ::std::sync::Arc<dyn ::iec_61499_fb_rs::run_time::clock::Clock>,
+            ) {
+                self.#field.set_clock(clock);
+            }
+        },
+        None => quote! {},
+    };
+
+    let adapters = if fb.adapters.is_empty() {
+        quote! {}
+    } else {
+        let decls = fb.adapters.iter().map(|a| {
+            let name = a.name.to_string();
+            let type_name = &a.type_name;
+            let constructor = if a.plug {
+                quote! { plug }
+            } else {
+                quote! { socket }
+            };
+
+            quote! {
+                ::iec_61499_fb_rs::fb::adapter::AdapterDecl::#constructor(#name, #type_name)
+            }
+        });
+
+        quote! {
+            fn adapters(&self) -> ::std::vec::Vec<::iec_61499_fb_rs::fb::adapter::AdapterDecl> {
+                vec![#(#decls),*]
+            }
+        }
+    };
+
+    let ecc_host = match &fb.ecc {
+        Ecc::Method(_) => quote! {},
+        Ecc::Field(field) => quote! {
+            impl ::iec_61499_fb_rs::fb::ecc::EccHost for #ident {
+                fn execution_control(&self) -> &::iec_61499_fb_rs::fb::ecc::ExecutionControl<Self> {
+                    &self.#field
+                }
+
+                fn execution_control_mut(
+                    &mut self,
+                ) -> &mut ::iec_61499_fb_rs::fb::ecc::ExecutionControl<Self> {
+                    &mut self.#field
+                }
+
+                fn clear_event_in(&mut self) {
+                    #(self.#event_in.reset();)*
+                }
+
+                fn send_event_out(
+                    &mut self,
+                    event: &str,
+                ) -> ::std::result::Result<(), ::iec_61499_fb_rs::fb::error::FbError> {
+                    match event {
+                        #(#event_out_names => {
+                            self.#event_out.send();
+                            Ok(())
+                        })*
+                        #(#event_in_names => {
+                            Err(::iec_61499_fb_rs::fb::error::FbError::wrong_direction(event))
+                        })*
+                        _ => Err(::iec_61499_fb_rs::fb::error::FbError::unknown_event(event)),
+                    }
+                }
+            }
+        },
+    };
+
+    let variables = quote! {
+        impl ::iec_61499_fb_rs::st::Variables for #ident {
+            fn read(
+                &self,
+                name: &str,
+            ) -> ::std::option::Option<::iec_61499_fb_rs::fb::data::comm::DataBuffer> {
+                match name {
+                    #(#data_names => Some(self.#data.as_buf()),)*
+                    _ => None,
+                }
+            }
+
+            fn write(
+                &mut self,
+                name: &str,
+                value: &::iec_61499_fb_rs::fb::data::comm::DataBuffer,
+            ) -> ::std::result::Result<(), ::iec_61499_fb_rs::fb::error::FbError> {
+                match name {
+                    #(#data_names => self.#data.set_buf(name, value),)*
+                    _ => Err(::iec_61499_fb_rs::fb::error::FbError::unknown_data(name)),
+                }
+            }
+        }
+    };
+
+    quote! {
+        #ecc_host
+
+        #variables
+
+        impl ::iec_61499_fb_rs::fb::Bfb for #ident {
+            fn as_any(&self) -> &dyn ::std::any::Any {
+                self
+            }
+
+            fn instance_name(&self) -> &str {
+                &self.instance_name
+            }
+
+            fn type_name(&self) -> &str {
+                #type_name
+            }
+
+            fn interface(&self) -> ::iec_61499_fb_rs::fb::desc::TypeInterface {
+                ::iec_61499_fb_rs::fb::desc::TypeInterface {
+                    type_name: #type_name.to_string(),
+                    comment: #type_comment.to_string(),
+                    interface: ::iec_61499_fb_rs::fb::desc::InterfaceDesc {
+                        event_inputs: #event_input_descs,
+                        event_outputs: #event_output_descs,
+                        input_vars: #input_var_descs,
+                        output_vars: #output_var_descs,
+                    },
+                }
+            }
+
+            fn event_inputs(&self) -> ::std::vec::Vec<&str> {
+                vec![#(#event_in_names),*]
+            }
+
+            fn event_outputs(&self) -> ::std::vec::Vec<&str> {
+                vec![#(#event_out_names),*]
+            }
+
+            fn data_inputs(&self) -> ::std::vec::Vec<&str> {
+                vec![#(#data_in_names),*]
+            }
+
+            fn data_outputs(&self) -> ::std::vec::Vec<&str> {
+                vec![#(#data_out_names),*]
+            }
+
+            fn data_kind(
+                &self,
+                data: &str,
+            ) -> ::std::result::Result<
+                ::iec_61499_fb_rs::fb::data::ty::DataKind,
+                ::iec_61499_fb_rs::fb::error::FbError,
+            > {
+                match data {
+                    #(#data_names => Ok(self.#data.as_kind()),)*
+                    _ => Err(::iec_61499_fb_rs::fb::error::FbError::unknown_data(data)),
+                }
+            }
+
+            fn fix_data_kind(
+                &mut self,
+                data: &str,
+                kind: &::iec_61499_fb_rs::fb::data::ty::DataKind,
+            ) -> ::std::result::Result<(), ::iec_61499_fb_rs::fb::error::FbError> {
+                match data {
+                    #(#data_names => self.#data.fix_kind(data, kind),)*
+                    _ => Err(::iec_61499_fb_rs::fb::error::FbError::unknown_data(data)),
+                }
+            }
+
+            fn set_event_in(
+                &mut self,
+                event: &str,
+            ) -> ::std::result::Result<(), ::iec_61499_fb_rs::fb::error::FbError> {
+                match event {
+                    #(#event_in_names => {
+                        if let Some(pending) = ::iec_61499_fb_rs::fb::Bfb::active_event_in(self) {
+                            return Err(
+                                ::iec_61499_fb_rs::fb::error::FbError::event_already_pending(
+                                    event, pending,
+                                ),
+                            );
+                        }
+
+                        self.#event_in.receive();
+                        Ok(())
+                    })*
+                    #(#event_out_names => {
+                        Err(::iec_61499_fb_rs::fb::error::FbError::wrong_direction(event))
+                    })*
+                    _ => Err(::iec_61499_fb_rs::fb::error::FbError::unknown_event(event)),
+                }
+            }
+
+            fn active_event_in(&self) -> ::std::option::Option<&str> {
+                let mut event = None;
+
+                #(if self.#event_in.read() {
+                    event = Some(#event_in_names);
+                })*
+
+                event
+            }
+
+            fn active_event_out(&self) -> ::std::option::Option<&str> {
+                let mut event = None;
+
+                #(if self.#event_out.read() {
+                    event = Some(#event_out_names);
+                })*
+
+                event
+            }
+
+            fn active_events_out(&self) -> ::std::vec::Vec<&str> {
+                let mut events = ::std::vec::Vec::new();
+
+                #(if self.#event_out.read() {
+                    events.push(#event_out_names);
+                })*
+
+                events
+            }
+
+            fn clear_event_out(&mut self) {
+                #(self.#event_out.reset();)*
+            }
+
+            fn with_for_event(
+                &self,
+                event: &str,
+            ) -> ::std::result::Result<
+                ::std::vec::Vec<&str>,
+                ::iec_61499_fb_rs::fb::error::FbError,
+            > {
+                match event {
+                    #(#event_names => Ok(vec![#(#event_with),*]),)*
+                    _ => Err(::iec_61499_fb_rs::fb::error::FbError::unknown_event(event)),
+                }
+            }
+
+            fn read_data_out(
+                &self,
+                data: &str,
+            ) -> ::std::result::Result<
+                ::iec_61499_fb_rs::fb::data::comm::DataBuffer,
+                ::iec_61499_fb_rs::fb::error::FbError,
+            > {
+                match data {
+                    #(#data_out_names => Ok(self.#data_out.as_buf()),)*
+                    #(#data_in_names => {
+                        Err(::iec_61499_fb_rs::fb::error::FbError::wrong_direction(data))
+                    })*
+                    _ => Err(::iec_61499_fb_rs::fb::error::FbError::unknown_data(data)),
+                }
+            }
+
+            fn write_data_in(
+                &mut self,
+                data: &str,
+                buf: &::iec_61499_fb_rs::fb::data::comm::DataBuffer,
+            ) -> ::std::result::Result<(), ::iec_61499_fb_rs::fb::error::FbError> {
+                match data {
+                    #(#data_in_names => self.#data_in.update_buf(data, buf),)*
+                    #(#data_out_names => {
+                        Err(::iec_61499_fb_rs::fb::error::FbError::wrong_direction(data))
+                    })*
+                    _ => Err(::iec_61499_fb_rs::fb::error::FbError::unknown_data(data)),
+                }
+            }
+
+            #ecc
+
+            #timer
+
+            #adapters
+        }
+    }
+}
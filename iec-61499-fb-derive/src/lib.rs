@@ -0,0 +1,48 @@
+//! Derive macros for the `iec-61499-fb-rs` crate.
+//!
+//! `#[derive(Bfb)]` generates the full `Bfb` trait implementation of a basic function block
+//! from its struct declaration:
+//! - `Event<In/Out, Signal>` fields become event in-/outputs named after the field
+//! - `Data<In/Out, T>` fields become data in-/outputs named after the field
+//! - `#[with(...)]` on an event field lists the data fields associated WITH the event
+//! - `#[ecc]` on an `ExecutionControl<Self>` field drives the function block by a declarative chart
+//!   (see `fb::ecc`), which also implements `EccHost`
+//! - alternatively `#[ecc(method)]` on the struct names a hand-written method `fn(&mut self) -> bool`
+//!   which executes a single step of the execution control
+//! - `#[timer]` on a `Timer` field (see `run_time::clock`) exposes its deadline to the runtime
+//!   and lets the runtime set the clock of the timer
+//! - `#[plug(name, "TYPE")]` and `#[socket(name, "TYPE")]` on the struct declare adapters (see `fb::adapter`),
+//!   their ports are ordinary `Event`/`Data` fields called `<name>_<port>`
+//!
+//! `Bfb::interface` reports the struct name as type name, and the `///` comments of the struct and the port fields as comments.
+//!
+//! The `Data` fields are additionally exposed as `st::Variables`, so Structured Text algorithms can operate on them.
+//!
+//! The struct requires a field called `instance_name`.
+//!
+//! ```text
+//! #[derive(Bfb)]
+//! pub struct E_CTU {
+//!     instance_name: String,
+//!     #[ecc]
+//!     ecc: ExecutionControl<Self>,
+//!     #[with(pv)]
+//!     cu: Event<In, Signal>,
+//!     pv: Data<In, UInt>,
+//!     ...
+//! }
+//! ```
+
+use proc_macro::TokenStream;
+use syn::{DeriveInput, parse_macro_input};
+
+mod bfb;
+
+#[proc_macro_derive(Bfb, attributes(with, ecc, timer, plug, socket))]
+pub fn derive_bfb(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    bfb::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -11,6 +11,18 @@ pub enum FunctionBlock {
     Ctu,
     Sr,
     Switch,
+    Split,
+    Merge,
+    Rend,
+    Permit,
+    Select,
+    Demux,
+    Rs,
+    DFf,
+    RTrig,
+    FTrig,
+    Ctd,
+    Ctud,
 }
 
 impl fmt::Display for FunctionBlock {
@@ -26,6 +38,25 @@ pub enum Mode {
     Interactive,
     TestConnectionParallel,
     TestConnectionSequential,
+    ParseTypes,
+    LoadSystem,
+    StructuredText,
+    GenericTypes,
+    Scheduler,
+    WithSemantics,
+    Device,
+    Timers,
+    Composite,
+    Adapters,
+    Validate,
+    Interfaces,
+    Registry,
+    Literals,
+    Calendar,
+    Conversions,
+    Widening,
+    GenericPorts,
+    Arrays,
 }
 
 impl fmt::Display for Mode {
@@ -54,7 +85,7 @@ impl fmt::Display for Sequence {
     version,
     about = "CLI tool to test IEC 61499 implemenations.",
     long_about = "CLI tool to test IEC 61499 implemenations.",
-    after_help = "-f only matters if -m is \"sequence\" or \"interactive\"\n-s only matters if -m is \"sequence\"",
+    after_help = "-f only matters if -m is \"sequence\" or \"interactive\"\n-s only matters if -m is \"sequence\"\n-p only matters if -m is \"load-system\"",
     override_usage = "iec-61499-fb-rs.exe -m [MODE] -f [FUNCTION_BLOCK] -s [SEQUENCE]"
 )]
 pub struct Args {
@@ -74,4 +105,8 @@ pub struct Args {
         default_value_t = Sequence::VotedReset
     )]
     pub sequence: Sequence,
+
+    /// system configuration (`.sys`) to load, defaults to `systems/COUNTER.sys`
+    #[arg(short, long)]
+    pub path: Option<std::path::PathBuf>,
 }
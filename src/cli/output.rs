@@ -1,7 +1,7 @@
 use std::fmt::Write as FmtWrite;
 
-pub struct VoterInformation {
-    pub ecc: &'static str,
+pub struct VoterInformation<'a> {
+    pub ecc: &'a str,
     pub vote: &'static str,
     pub reset: &'static str,
     pub voted: &'static str,
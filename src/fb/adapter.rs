@@ -0,0 +1,122 @@
+//! Adapter interfaces, which bundle events and data exchanged between two function blocks.
+//!
+//! An `AdapterType` is declared from the point of view of the plug:
+//! - its outputs are sent from the plug to the socket
+//! - its inputs are sent from the socket back to the plug
+//!
+//! A function block declares its plugs and sockets by name (see `Bfb::adapters`),
+//! the ports of an adapter are ordinary ports of the function block called `<adapter>_<port>`,
+//! e.g. the plug `srv` of an adapter with the output event `req` has the event output `srv_req`.
+//! On a socket the directions are mirrored, `srv_req` is an event input there.
+//!
+//! `IdConnRuntime::connect_adapter` expands a plug/socket pair into the event and data connections of both directions.
+
+use crate::fb::{desc::InterfaceDesc, error::FbError};
+
+#[derive(Clone, Debug)]
+pub struct AdapterType {
+    pub name: String,
+    /// interface as seen by the plug
+    pub interface: InterfaceDesc,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AdapterRole {
+    Plug,
+    Socket,
+}
+
+/// a plug or socket of a function block
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AdapterDecl {
+    pub name: String,
+    pub type_name: String,
+    pub role: AdapterRole,
+}
+
+impl AdapterDecl {
+    pub fn plug(name: &str, type_name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            type_name: type_name.to_string(),
+            role: AdapterRole::Plug,
+        }
+    }
+
+    pub fn socket(name: &str, type_name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            type_name: type_name.to_string(),
+            role: AdapterRole::Socket,
+        }
+    }
+
+    /// name of the function block port implementing a port of the adapter
+    pub fn port(&self, port: &str) -> String {
+        format!("{}_{}", self.name, port).to_lowercase()
+    }
+}
+
+/// a single connection of an expanded adapter connection: `(from fb, from port, to fb, to port)`
+pub type AdapterConn = (String, String, String, String);
+
+impl AdapterType {
+    pub fn new(name: &str, interface: InterfaceDesc) -> Self {
+        Self {
+            name: name.to_string(),
+            interface,
+        }
+    }
+
+    /// expands a connection between a plug of `plug_fb` and a socket of `socket_fb`
+    /// into its event and data connections, fails if the declarations do not fit this adapter type
+    pub fn expand(
+        &self,
+        (plug_fb, plug): (&str, &AdapterDecl),
+        (socket_fb, socket): (&str, &AdapterDecl),
+    ) -> Result<(Vec<AdapterConn>, Vec<AdapterConn>), FbError> {
+        for (decl, role) in [(plug, AdapterRole::Plug), (socket, AdapterRole::Socket)] {
+            if decl.role != role || !decl.type_name.eq_ignore_ascii_case(&self.name) {
+                return Err(FbError::adapter_mismatch(&decl.name, &self.name));
+            }
+        }
+
+        let downstream = |port: &str| {
+            let port = port.to_lowercase();
+            (
+                plug_fb.to_string(),
+                plug.port(&port),
+                socket_fb.to_string(),
+                socket.port(&port),
+            )
+        };
+
+        let upstream = |port: &str| {
+            let port = port.to_lowercase();
+            (
+                socket_fb.to_string(),
+                socket.port(&port),
+                plug_fb.to_string(),
+                plug.port(&port),
+            )
+        };
+
+        let interface = &self.interface;
+
+        let events = interface
+            .event_outputs
+            .iter()
+            .map(|e| downstream(&e.name))
+            .chain(interface.event_inputs.iter().map(|e| upstream(&e.name)))
+            .collect();
+
+        let data = interface
+            .output_vars
+            .iter()
+            .map(|v| downstream(&v.name))
+            .chain(interface.input_vars.iter().map(|v| upstream(&v.name)))
+            .collect();
+
+        Ok((events, data))
+    }
+}
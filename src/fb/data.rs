@@ -1,11 +1,19 @@
 //! internal data fields in function blocks
 
-use crate::fb::data::{comm::DataBuffer, ty::DataKind};
+use crate::fb::{
+    data::{comm::DataBuffer, ty::DataKind},
+    error::FbError,
+};
 
 use super::direction::{Direction, In, Out};
 
+use array::ArrayType;
 use ty::DataType;
 
+pub mod array;
+pub mod calendar;
+pub mod convert;
+
 /// Represents a data input or output.
 #[derive(Clone, Debug, Default)]
 pub struct Data<D: Direction, T: ty::DataType> {
@@ -25,17 +33,83 @@ impl<D: Direction, T: ty::DataType> Data<D, T> {
     pub fn as_kind(&self) -> DataKind {
         self.value.kind()
     }
+
+    /// sets the value from a communication buffer regardless of the direction (e.g. inside of algorithms),
+    /// fails if the buffer variant does not match the data type (generic data types are fixed by the first value)
+    pub fn set_buf(&mut self, name: &str, buf: &DataBuffer) -> Result<(), FbError> {
+        match (T::from_buf(buf), buf.kind()) {
+            (Some(value), Some(kind)) if self.value.fix_kind(&kind) => {
+                if !self.value.set(value) {
+                    return Err(FbError::type_mismatch(name, self.as_kind(), buf));
+                }
+
+                Ok(())
+            }
+            _ => Err(FbError::type_mismatch(name, self.as_kind(), buf)),
+        }
+    }
+
+    /// fixes the concrete kind of a generic data type (see `ty::GenericKind`),
+    /// fails if the data type does not accept the kind
+    pub fn fix_kind(&mut self, name: &str, kind: &DataKind) -> Result<(), FbError> {
+        if !self.value.fix_kind(kind) {
+            return Err(FbError::type_mismatch(
+                name,
+                self.as_kind(),
+                &DataBuffer::default_for(kind),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl<D: Direction, T: ArrayType> Data<D, T>
+where
+    <T::Element as ty::DataType>::Inner: Clone,
+{
+    /// returns the element at the index (one index per dimension), `None` if the index is out of bounds
+    pub fn element(&self, index: &[i64]) -> Option<<T::Element as ty::DataType>::Inner> {
+        let offset = array::offset(T::BOUNDS, index)?;
+
+        Some(self.value.elements()[offset].clone())
+    }
+
+    /// sets the element at the index regardless of the direction (e.g. inside of algorithms),
+    /// fails if the index is out of bounds
+    pub fn set_element(
+        &mut self,
+        name: &str,
+        index: &[i64],
+        value: <T::Element as ty::DataType>::Inner,
+    ) -> Result<(), FbError> {
+        let Some(offset) = array::offset(T::BOUNDS, index) else {
+            return Err(FbError::index_out_of_bounds(name, index, self.as_kind()));
+        };
+
+        self.value.elements_mut()[offset] = value;
+
+        Ok(())
+    }
 }
 
 impl<T: ty::DataType> Data<In, T> {
-    pub fn update(&mut self, value: <T as ty::DataType>::Inner) {
-        self.value.set(value);
+    /// returns whether the value is accepted (see `DataType::set`)
+    pub fn update(&mut self, value: <T as ty::DataType>::Inner) -> bool {
+        self.value.set(value)
+    }
+
+    /// updates the value from a communication buffer,
+    /// fails if the buffer variant does not match the data type
+    pub fn update_buf(&mut self, name: &str, buf: &DataBuffer) -> Result<(), FbError> {
+        self.set_buf(name, buf)
     }
 }
 
 impl<T: ty::DataType> Data<Out, T> {
-    pub fn write(&mut self, value: <T as ty::DataType>::Inner) {
-        self.value.set(value);
+    /// returns whether the value is accepted (see `DataType::set`)
+    pub fn write(&mut self, value: <T as ty::DataType>::Inner) -> bool {
+        self.value.set(value)
     }
 }
 
@@ -48,8 +122,14 @@ pub fn toggle<D: Direction>(data: &mut Data<D, ty::Bool>) {
 pub mod comm {
     use std::time::Duration;
 
+    use crate::fb::data::{
+        array::{self, ArrayBuffer, ArrayKind},
+        calendar::{Date, DateAndTime, TimeOfDay},
+        ty::DataKind,
+    };
+
     /// enum to enable a type-safe runtime communication of `IEC 61131-3` data types between function blocks
-    #[derive(Default, Clone, Debug)]
+    #[derive(Default, Clone, Debug, PartialEq)]
     pub enum DataBuffer {
         SInt(i8),
         Int(i16),
@@ -62,9 +142,13 @@ pub mod comm {
         Real(f32),
         LReal(f64),
         Time(Duration),
-        Date(Vec<u8>),
-        TimeOfDay(Vec<u8>),
-        DateTime(Vec<u8>),
+        Date(Date),
+        TimeOfDay(TimeOfDay),
+        DateTime(DateAndTime),
+        LTime(Duration),
+        LDate(Date),
+        LTimeOfDay(TimeOfDay),
+        LDateTime(DateAndTime),
         WString(Vec<u16>),
         String(Vec<u8>),
         Bool(bool),
@@ -72,24 +156,534 @@ pub mod comm {
         Word(u16),
         DWord(u32),
         LWord(u64),
+        Array(Box<ArrayBuffer>),
         #[default]
         Unassigned,
     }
 
+    /// formats the value as typed `IEC 61131-3` literal, which `FromStr` parses back to the same value,
+    /// arrays with other bounds than `[0..n-1]` are parsed back by `parse_literal` with their kind
     impl std::fmt::Display for DataBuffer {
         fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            write!(f, "{self:?}")
+            match self {
+                DataBuffer::SInt(v) => write!(f, "SINT#{v}"),
+                DataBuffer::Int(v) => write!(f, "INT#{v}"),
+                DataBuffer::DInt(v) => write!(f, "DINT#{v}"),
+                DataBuffer::LInt(v) => write!(f, "LINT#{v}"),
+                DataBuffer::USInt(v) => write!(f, "USINT#{v}"),
+                DataBuffer::UInt(v) => write!(f, "UINT#{v}"),
+                DataBuffer::UDInt(v) => write!(f, "UDINT#{v}"),
+                DataBuffer::ULInt(v) => write!(f, "ULINT#{v}"),
+                DataBuffer::Real(v) => write!(f, "REAL#{v:?}"),
+                DataBuffer::LReal(v) => write!(f, "LREAL#{v:?}"),
+                DataBuffer::Time(v) => write!(f, "T#{}", format_duration(v)),
+                DataBuffer::Date(v) => write!(f, "D#{v}"),
+                DataBuffer::TimeOfDay(v) => write!(f, "TOD#{v}"),
+                DataBuffer::DateTime(v) => write!(f, "DT#{v}"),
+                DataBuffer::LTime(v) => write!(f, "LT#{}", format_duration(v)),
+                DataBuffer::LDate(v) => write!(f, "LD#{v}"),
+                DataBuffer::LTimeOfDay(v) => write!(f, "LTOD#{v}"),
+                DataBuffer::LDateTime(v) => write!(f, "LDT#{v}"),
+                DataBuffer::String(v) => write!(f, "'{}'", escape_string(v)),
+                DataBuffer::WString(v) => write!(f, "\"{}\"", escape_wstring(v)),
+                DataBuffer::Bool(true) => write!(f, "TRUE"),
+                DataBuffer::Bool(false) => write!(f, "FALSE"),
+                DataBuffer::Byte(v) => write!(f, "BYTE#16#{v:02X}"),
+                DataBuffer::Word(v) => write!(f, "WORD#16#{v:04X}"),
+                DataBuffer::DWord(v) => write!(f, "DWORD#16#{v:08X}"),
+                DataBuffer::LWord(v) => write!(f, "LWORD#16#{v:016X}"),
+                DataBuffer::Array(v) => write!(f, "{v}"),
+                DataBuffer::Unassigned => write!(f, "UNASSIGNED"),
+            }
+        }
+    }
+
+    /// a text which is no valid `IEC 61131-3` literal
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct LiteralError(pub std::string::String);
+
+    impl std::fmt::Display for LiteralError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "invalid literal \"{}\"", self.0)
+        }
+    }
+
+    impl std::error::Error for LiteralError {}
+
+    /// parses an `IEC 61131-3` literal, the kind is taken from the type prefix (e.g. `UINT#3`, `T#1s`)
+    /// or the notation of the literal (`TRUE`, `'string'`, `"wstring"`),
+    /// untyped numbers are parsed as `DINT` (`LINT`/`ULINT` if they do not fit) or `LREAL`,
+    /// array literals are parsed as `ARRAY [0..n-1] OF <kind>` of their elements (see `array_literal_kind`),
+    /// other bounds need the declared kind and `parse_literal`
+    impl std::str::FromStr for DataBuffer {
+        type Err = LiteralError;
+
+        fn from_str(text: &str) -> Result<Self, Self::Err> {
+            let kind = match text.trim() {
+                array if array.starts_with('[') => array_literal_kind(array),
+                literal => literal_kind(literal),
+            };
+
+            kind.and_then(|kind| DataBuffer::parse_literal(&kind, text))
+                .ok_or_else(|| LiteralError(text.to_string()))
+        }
+    }
+
+    impl DataBuffer {
+        /// returns the kind of the buffered value, `None` if unassigned
+        pub fn kind(&self) -> Option<DataKind> {
+            let kind = match self {
+                DataBuffer::SInt(_) => DataKind::SInt,
+                DataBuffer::Int(_) => DataKind::Int,
+                DataBuffer::DInt(_) => DataKind::DInt,
+                DataBuffer::LInt(_) => DataKind::LInt,
+                DataBuffer::USInt(_) => DataKind::USInt,
+                DataBuffer::UInt(_) => DataKind::UInt,
+                DataBuffer::UDInt(_) => DataKind::UDInt,
+                DataBuffer::ULInt(_) => DataKind::ULInt,
+                DataBuffer::Real(_) => DataKind::Real,
+                DataBuffer::LReal(_) => DataKind::LReal,
+                DataBuffer::Time(_) => DataKind::Time,
+                DataBuffer::Date(_) => DataKind::Date,
+                DataBuffer::TimeOfDay(_) => DataKind::TimeOfDay,
+                DataBuffer::DateTime(_) => DataKind::DateTime,
+                DataBuffer::LTime(_) => DataKind::LTime,
+                DataBuffer::LDate(_) => DataKind::LDate,
+                DataBuffer::LTimeOfDay(_) => DataKind::LTimeOfDay,
+                DataBuffer::LDateTime(_) => DataKind::LDateTime,
+                DataBuffer::WString(_) => DataKind::WString,
+                DataBuffer::String(_) => DataKind::String,
+                DataBuffer::Bool(_) => DataKind::Bool,
+                DataBuffer::Byte(_) => DataKind::Byte,
+                DataBuffer::Word(_) => DataKind::Word,
+                DataBuffer::DWord(_) => DataKind::DWord,
+                DataBuffer::LWord(_) => DataKind::LWord,
+                DataBuffer::Array(v) => DataKind::Array(Box::new(v.kind().clone())),
+                DataBuffer::Unassigned => return None,
+            };
+
+            Some(kind)
+        }
+
+        /// the initial value of a variable of the given kind without declared initial value
+        pub fn default_for(kind: &DataKind) -> DataBuffer {
+            match kind {
+                DataKind::SInt => DataBuffer::SInt(0),
+                DataKind::Int => DataBuffer::Int(0),
+                DataKind::DInt => DataBuffer::DInt(0),
+                DataKind::LInt => DataBuffer::LInt(0),
+                DataKind::USInt => DataBuffer::USInt(0),
+                DataKind::UInt => DataBuffer::UInt(0),
+                DataKind::UDInt => DataBuffer::UDInt(0),
+                DataKind::ULInt => DataBuffer::ULInt(0),
+                DataKind::Real => DataBuffer::Real(0.0),
+                DataKind::LReal => DataBuffer::LReal(0.0),
+                DataKind::Time => DataBuffer::Time(Duration::ZERO),
+                DataKind::Date => DataBuffer::Date(Date::default()),
+                DataKind::TimeOfDay => DataBuffer::TimeOfDay(TimeOfDay::default()),
+                DataKind::DateTime => DataBuffer::DateTime(DateAndTime::default()),
+                DataKind::LTime => DataBuffer::LTime(Duration::ZERO),
+                DataKind::LDate => DataBuffer::LDate(Date::default()),
+                DataKind::LTimeOfDay => DataBuffer::LTimeOfDay(TimeOfDay::default()),
+                DataKind::LDateTime => DataBuffer::LDateTime(DateAndTime::default()),
+                DataKind::String => DataBuffer::String(vec![]),
+                DataKind::WString => DataBuffer::WString(vec![]),
+                DataKind::Bool => DataBuffer::Bool(false),
+                DataKind::Byte => DataBuffer::Byte(0),
+                DataKind::Word => DataBuffer::Word(0),
+                DataKind::DWord => DataBuffer::DWord(0),
+                DataKind::LWord => DataBuffer::LWord(0),
+                DataKind::Array(kind) => {
+                    DataBuffer::Array(Box::new(ArrayBuffer::new(*kind.clone())))
+                }
+                // generic data types are unassigned until their kind is fixed
+                DataKind::Generic(_) => DataBuffer::Unassigned,
+            }
+        }
+
+        /// parses an `IEC 61131-3` literal (e.g. `TRUE`, `16#FF`, `UINT#3`, `T#1s500ms`, `D#2024-01-31`, `'text'`)
+        /// as the given kind, returns `None` for malformed literals
+        pub fn parse_literal(kind: &DataKind, text: &str) -> Option<DataBuffer> {
+            let text = text.trim();
+
+            // typed literals like `UINT#3`, the type prefix needs to match the kind
+            let text = match text.split_once('#') {
+                Some((prefix, rest)) if DataKind::from_iec_name(prefix).is_some() => {
+                    if DataKind::from_iec_name(prefix)? != *kind {
+                        return None;
+                    }
+
+                    rest
+                }
+                _ => text,
+            };
+
+            let buf = match kind {
+                DataKind::SInt => DataBuffer::SInt(parse_int(text)?.try_into().ok()?),
+                DataKind::Int => DataBuffer::Int(parse_int(text)?.try_into().ok()?),
+                DataKind::DInt => DataBuffer::DInt(parse_int(text)?.try_into().ok()?),
+                DataKind::LInt => DataBuffer::LInt(parse_int(text)?.try_into().ok()?),
+                DataKind::USInt => DataBuffer::USInt(parse_int(text)?.try_into().ok()?),
+                DataKind::UInt => DataBuffer::UInt(parse_int(text)?.try_into().ok()?),
+                DataKind::UDInt => DataBuffer::UDInt(parse_int(text)?.try_into().ok()?),
+                DataKind::ULInt => DataBuffer::ULInt(parse_int(text)?.try_into().ok()?),
+                DataKind::Byte => DataBuffer::Byte(parse_int(text)?.try_into().ok()?),
+                DataKind::Word => DataBuffer::Word(parse_int(text)?.try_into().ok()?),
+                DataKind::DWord => DataBuffer::DWord(parse_int(text)?.try_into().ok()?),
+                DataKind::LWord => DataBuffer::LWord(parse_int(text)?.try_into().ok()?),
+                // values outside of the range (e.g. `REAL#1e40`) and `inf`/`NaN` are no literals
+                DataKind::Real => DataBuffer::Real(
+                    text.replace('_', "")
+                        .parse()
+                        .ok()
+                        .filter(|v: &f32| v.is_finite())?,
+                ),
+                DataKind::LReal => DataBuffer::LReal(
+                    text.replace('_', "")
+                        .parse()
+                        .ok()
+                        .filter(|v: &f64| v.is_finite())?,
+                ),
+                DataKind::Bool => match text.to_ascii_uppercase().as_str() {
+                    "TRUE" | "1" => DataBuffer::Bool(true),
+                    "FALSE" | "0" => DataBuffer::Bool(false),
+                    _ => return None,
+                },
+                DataKind::Time => DataBuffer::Time(parse_duration(strip_prefix(text, "T"))?),
+                DataKind::LTime => DataBuffer::LTime(parse_duration(strip_prefix(text, "LT"))?),
+                DataKind::Date => DataBuffer::Date(strip_prefix(text, "D").parse().ok()?),
+                DataKind::LDate => DataBuffer::LDate(strip_prefix(text, "LD").parse().ok()?),
+                DataKind::TimeOfDay => DataBuffer::TimeOfDay(text.parse().ok()?),
+                DataKind::LTimeOfDay => DataBuffer::LTimeOfDay(text.parse().ok()?),
+                DataKind::DateTime => DataBuffer::DateTime(text.parse().ok()?),
+                DataKind::LDateTime => DataBuffer::LDateTime(text.parse().ok()?),
+                DataKind::String => DataBuffer::String(unescape_string(
+                    text.strip_prefix('\'')?.strip_suffix('\'')?,
+                )?),
+                DataKind::WString => DataBuffer::WString(unescape_wstring(
+                    text.strip_prefix('"')?.strip_suffix('"')?,
+                )?),
+                DataKind::Array(kind) => {
+                    DataBuffer::Array(Box::new(ArrayBuffer::parse_literal(kind, text)?))
+                }
+                // literals always have a concrete kind
+                DataKind::Generic(_) => return None,
+            };
+
+            Some(buf)
+        }
+    }
+
+    /// parses decimal and based (`2#`, `8#`, `16#`) integer literals, `_` may separate digits,
+    /// the sign precedes the base (`-16#FF`)
+    fn parse_int(text: &str) -> Option<i128> {
+        let text = text.replace('_', "");
+
+        let (negative, text) = match text.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, text.strip_prefix('+').unwrap_or(&text)),
+        };
+
+        let value = match text.split_once('#') {
+            Some((base, digits)) => {
+                let radix = match base {
+                    "2" => 2,
+                    "8" => 8,
+                    "16" => 16,
+                    _ => return None,
+                };
+
+                // `from_str_radix` accepts a sign of its own
+                if digits.starts_with(['+', '-']) {
+                    return None;
+                }
+
+                i128::from_str_radix(digits, radix).ok()?
+            }
+            None => text.parse().ok()?,
+        };
+
+        Some(if negative { -value } else { value })
+    }
+
+    /// parses the value of a duration literal, e.g. `1h30m`, `1.5s` or `100ms` (without `T#` prefix)
+    fn parse_duration(text: &str) -> Option<Duration> {
+        let text = text.replace('_', "").to_ascii_lowercase();
+        let mut rest = text.as_str();
+        let mut nanos: u128 = 0;
+
+        if rest.is_empty() {
+            return None;
+        }
+
+        while !rest.is_empty() {
+            let number_end = rest
+                .find(|c: char| !c.is_ascii_digit() && c != '.')
+                .unwrap_or(rest.len());
+            let (whole, fraction) = rest[..number_end]
+                .split_once('.')
+                .unwrap_or((&rest[..number_end], ""));
+            rest = &rest[number_end..];
+
+            let unit_end = rest
+                .find(|c: char| c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            let unit: u128 = match &rest[..unit_end] {
+                "d" => 86_400_000_000_000,
+                "h" => 3_600_000_000_000,
+                "m" => 60_000_000_000,
+                "s" => 1_000_000_000,
+                "ms" => 1_000_000,
+                "us" => 1_000,
+                "ns" => 1,
+                _ => return None,
+            };
+            rest = &rest[unit_end..];
+
+            if whole.is_empty() && fraction.is_empty() {
+                return None;
+            }
+
+            let whole: u128 = if whole.is_empty() {
+                0
+            } else {
+                whole.parse().ok()?
+            };
+            let fraction = if fraction.is_empty() {
+                0
+            } else {
+                let digits: u32 = fraction.len().try_into().ok()?;
+                let scale = 10u128.checked_pow(digits)?;
+                (fraction.parse::<u128>().ok()? * unit + scale / 2) / scale
+            };
+
+            nanos = nanos.checked_add(whole.checked_mul(unit)?.checked_add(fraction)?)?;
+        }
+
+        Some(Duration::new(
+            (nanos / 1_000_000_000).try_into().ok()?,
+            (nanos % 1_000_000_000) as u32,
+        ))
+    }
+
+    /// formats a duration as value of a duration literal, e.g. `1h30m` or `0s` (without `T#` prefix)
+    fn format_duration(duration: &Duration) -> std::string::String {
+        let mut nanos = duration.as_nanos();
+        let mut text = std::string::String::new();
+
+        for (unit, size) in [
+            ("d", 86_400_000_000_000),
+            ("h", 3_600_000_000_000),
+            ("m", 60_000_000_000),
+            ("s", 1_000_000_000),
+            ("ms", 1_000_000),
+            ("us", 1_000),
+            ("ns", 1),
+        ] {
+            if nanos >= size {
+                text.push_str(&format!("{}{unit}", nanos / size));
+                nanos %= size;
+            }
+        }
+
+        if text.is_empty() {
+            text.push_str("0s");
+        }
+
+        text
+    }
+
+    /// removes a short type prefix like `T#` or `D#`, long prefixes like `TIME#` are removed by `parse_literal`
+    fn strip_prefix<'a>(text: &'a str, prefix: &str) -> &'a str {
+        match text.split_once('#') {
+            Some((p, rest)) if p.eq_ignore_ascii_case(prefix) => rest,
+            _ => text,
+        }
+    }
+
+    /// a character of a string literal after resolving `$` escapes
+    enum Unescaped {
+        Char(char),
+        /// `$hh` in `STRING` and `$hhhh` in `WSTRING` literals
+        Code(u32),
+    }
+
+    /// resolves the `$` escapes of a string literal without its quotes,
+    /// unescaped quotes are rejected
+    fn unescape(text: &str, quote: char, code_digits: usize) -> Option<Vec<Unescaped>> {
+        let mut chars = text.chars();
+        let mut unescaped = vec![];
+
+        while let Some(c) = chars.next() {
+            if c == quote {
+                return None;
+            }
+
+            if c != '$' {
+                unescaped.push(Unescaped::Char(c));
+                continue;
+            }
+
+            let escaped = match chars.next()?.to_ascii_uppercase() {
+                '$' => '$',
+                '\'' => '\'',
+                '"' => '"',
+                'L' | 'N' => '\n',
+                'P' => '\x0c',
+                'R' => '\r',
+                'T' => '\t',
+                first => {
+                    let mut code = std::string::String::from(first);
+
+                    for _ in 1..code_digits {
+                        code.push(chars.next()?);
+                    }
+
+                    unescaped.push(Unescaped::Code(u32::from_str_radix(&code, 16).ok()?));
+                    continue;
+                }
+            };
+
+            unescaped.push(Unescaped::Char(escaped));
+        }
+
+        Some(unescaped)
+    }
+
+    fn unescape_string(text: &str) -> Option<Vec<u8>> {
+        let mut bytes = vec![];
+
+        for u in unescape(text, '\'', 2)? {
+            match u {
+                Unescaped::Char(c) => bytes.extend(c.to_string().as_bytes()),
+                Unescaped::Code(code) => bytes.push(code.try_into().ok()?),
+            }
+        }
+
+        Some(bytes)
+    }
+
+    fn unescape_wstring(text: &str) -> Option<Vec<u16>> {
+        let mut units = vec![];
+
+        for u in unescape(text, '"', 4)? {
+            match u {
+                Unescaped::Char(c) => units.extend(c.encode_utf16(&mut [0; 2]).iter()),
+                Unescaped::Code(code) => units.push(code.try_into().ok()?),
+            }
+        }
+
+        Some(units)
+    }
+
+    /// escapes the bytes of a `STRING`, everything except printable ASCII is written as `$hh`
+    fn escape_string(bytes: &[u8]) -> std::string::String {
+        bytes
+            .iter()
+            .map(|b| match b {
+                b'$' => "$$".to_string(),
+                b'\'' => "$'".to_string(),
+                b'\n' => "$N".to_string(),
+                b'\r' => "$R".to_string(),
+                b'\t' => "$T".to_string(),
+                0x20..=0x7e => (*b as char).to_string(),
+                _ => format!("${b:02X}"),
+            })
+            .collect()
+    }
+
+    /// escapes the code units of a `WSTRING`, control characters and unpaired surrogates are written as `$hhhh`
+    fn escape_wstring(units: &[u16]) -> std::string::String {
+        let mut text = std::string::String::new();
+
+        for c in char::decode_utf16(units.iter().copied()) {
+            match c {
+                Ok('$') => text.push_str("$$"),
+                Ok('"') => text.push_str("$\""),
+                Ok('\n') => text.push_str("$N"),
+                Ok('\r') => text.push_str("$R"),
+                Ok('\t') => text.push_str("$T"),
+                Ok(c) if !c.is_control() => text.push(c),
+                Ok(c) => text.push_str(&format!("${:04X}", c as u32)),
+                Err(e) => text.push_str(&format!("${:04X}", e.unpaired_surrogate())),
+            }
+        }
+
+        text
+    }
+
+    /// infers `ARRAY [0..n-1] OF <kind>` of an array literal like `[INT#1, 2(INT#0)]`,
+    /// the elements need to share one kind
+    fn array_literal_kind(text: &str) -> Option<DataKind> {
+        let items = array::literal_items(text)?;
+        let mut kinds = items.iter().map(|(_, value)| literal_kind(value));
+        let element = kinds.next()??;
+
+        if !kinds.all(|kind| kind.as_ref() == Some(&element)) {
+            return None;
+        }
+
+        let count = items
+            .iter()
+            .try_fold(0usize, |count, (n, _)| count.checked_add(*n))?;
+        let kind = ArrayKind::new(vec![0..=i64::try_from(count).ok()? - 1], element)?;
+
+        Some(DataKind::Array(Box::new(kind)))
+    }
+
+    /// infers the kind of an untyped or typed literal, see `FromStr for DataBuffer`
+    fn literal_kind(text: &str) -> Option<DataKind> {
+        if text.starts_with('\'') {
+            return Some(DataKind::String);
+        }
+
+        if text.starts_with('"') {
+            return Some(DataKind::WString);
+        }
+
+        if text.eq_ignore_ascii_case("TRUE") || text.eq_ignore_ascii_case("FALSE") {
+            return Some(DataKind::Bool);
+        }
+
+        if let Some((prefix, _)) = text.split_once('#') {
+            if let Some(kind) = DataKind::from_iec_name(prefix) {
+                return Some(kind);
+            }
+
+            match prefix.to_ascii_uppercase().as_str() {
+                "T" => return Some(DataKind::Time),
+                "LT" => return Some(DataKind::LTime),
+                "D" => return Some(DataKind::Date),
+                "LD" => return Some(DataKind::LDate),
+                // based integer like `16#FF`
+                _ => {}
+            }
+        } else if text.contains(['.', 'e', 'E']) {
+            return Some(DataKind::LReal);
+        }
+
+        let value = parse_int(text)?;
+
+        if i32::try_from(value).is_ok() {
+            Some(DataKind::DInt)
+        } else if i64::try_from(value).is_ok() {
+            Some(DataKind::LInt)
+        } else {
+            Some(DataKind::ULInt)
         }
     }
 }
 
 /// static inner data type system of function blocks
 pub mod ty {
-    use std::time::Duration;
+    use std::{borrow::Cow, time::Duration};
 
-    use crate::fb::data::comm::DataBuffer;
+    use crate::fb::data::{calendar, comm::DataBuffer};
+
+    pub use crate::fb::data::array::{Array, Array2, Array3, ArrayKind};
 
     /// `IEC 61131-3` data type markers
+    #[derive(Clone, Debug, PartialEq, Eq)]
     pub enum DataKind {
         SInt,
         Int,
@@ -105,6 +699,10 @@ pub mod ty {
         Date,
         TimeOfDay,
         DateTime,
+        LTime,
+        LDate,
+        LTimeOfDay,
+        LDateTime,
         String,
         WString,
         Bool,
@@ -112,10 +710,257 @@ pub mod ty {
         Word,
         DWord,
         LWord,
+        /// `ARRAY [lo..hi, ...] OF <kind>`
+        Array(Box<ArrayKind>),
+        /// generic data type of a port, which accepts several of the kinds above
+        Generic(GenericKind),
     }
 
-    pub fn kind_eq(a: &DataKind, b: &DataKind) -> bool {
-        std::mem::discriminant(a) == std::mem::discriminant(b)
+    impl DataKind {
+        /// all elementary data types
+        pub const ELEMENTARY: [DataKind; 25] = [
+            DataKind::SInt,
+            DataKind::Int,
+            DataKind::DInt,
+            DataKind::LInt,
+            DataKind::USInt,
+            DataKind::UInt,
+            DataKind::UDInt,
+            DataKind::ULInt,
+            DataKind::Real,
+            DataKind::LReal,
+            DataKind::Time,
+            DataKind::Date,
+            DataKind::TimeOfDay,
+            DataKind::DateTime,
+            DataKind::LTime,
+            DataKind::LDate,
+            DataKind::LTimeOfDay,
+            DataKind::LDateTime,
+            DataKind::String,
+            DataKind::WString,
+            DataKind::Bool,
+            DataKind::Byte,
+            DataKind::Word,
+            DataKind::DWord,
+            DataKind::LWord,
+        ];
+
+        /// returns the `IEC 61131-3` name of the data type, the declaration for arrays (e.g. `ARRAY [0..15] OF INT`)
+        pub fn iec_name(&self) -> Cow<'static, str> {
+            let name = match self {
+                DataKind::SInt => "SINT",
+                DataKind::Int => "INT",
+                DataKind::DInt => "DINT",
+                DataKind::LInt => "LINT",
+                DataKind::USInt => "USINT",
+                DataKind::UInt => "UINT",
+                DataKind::UDInt => "UDINT",
+                DataKind::ULInt => "ULINT",
+                DataKind::Real => "REAL",
+                DataKind::LReal => "LREAL",
+                DataKind::Time => "TIME",
+                DataKind::Date => "DATE",
+                DataKind::TimeOfDay => "TIME_OF_DAY",
+                DataKind::DateTime => "DATE_AND_TIME",
+                DataKind::LTime => "LTIME",
+                DataKind::LDate => "LDATE",
+                DataKind::LTimeOfDay => "LTIME_OF_DAY",
+                DataKind::LDateTime => "LDATE_AND_TIME",
+                DataKind::String => "STRING",
+                DataKind::WString => "WSTRING",
+                DataKind::Bool => "BOOL",
+                DataKind::Byte => "BYTE",
+                DataKind::Word => "WORD",
+                DataKind::DWord => "DWORD",
+                DataKind::LWord => "LWORD",
+                DataKind::Array(kind) => return Cow::Owned(kind.to_string()),
+                DataKind::Generic(generic) => generic.iec_name(),
+            };
+
+            Cow::Borrowed(name)
+        }
+
+        /// parses an `IEC 61131-3` data type name (case-insensitive, including short forms like `TOD`)
+        /// or an array declaration (e.g. `ARRAY [0..15] OF INT`)
+        pub fn from_iec_name(name: &str) -> Option<Self> {
+            let kind = match name.to_ascii_uppercase().as_str() {
+                "SINT" => DataKind::SInt,
+                "INT" => DataKind::Int,
+                "DINT" => DataKind::DInt,
+                "LINT" => DataKind::LInt,
+                "USINT" => DataKind::USInt,
+                "UINT" => DataKind::UInt,
+                "UDINT" => DataKind::UDInt,
+                "ULINT" => DataKind::ULInt,
+                "REAL" => DataKind::Real,
+                "LREAL" => DataKind::LReal,
+                "TIME" => DataKind::Time,
+                "DATE" => DataKind::Date,
+                "TIME_OF_DAY" | "TOD" => DataKind::TimeOfDay,
+                "DATE_AND_TIME" | "DT" => DataKind::DateTime,
+                "LTIME" => DataKind::LTime,
+                "LDATE" => DataKind::LDate,
+                "LTIME_OF_DAY" | "LTOD" => DataKind::LTimeOfDay,
+                "LDATE_AND_TIME" | "LDT" => DataKind::LDateTime,
+                "STRING" => DataKind::String,
+                "WSTRING" => DataKind::WString,
+                "BOOL" => DataKind::Bool,
+                "BYTE" => DataKind::Byte,
+                "WORD" => DataKind::Word,
+                "DWORD" => DataKind::DWord,
+                "LWORD" => DataKind::LWord,
+                name if name.starts_with("ARRAY") => {
+                    DataKind::Array(Box::new(ArrayKind::parse(name)?))
+                }
+                name => DataKind::Generic(GenericKind::from_iec_name(name)?),
+            };
+
+            Some(kind)
+        }
+    }
+
+    /// generic data types (`ANY_*`) of ports, which accept every kind they contain,
+    /// the concrete kind of a port is fixed when it is connected or receives its first value
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum GenericKind {
+        Any,
+        AnyElementary,
+        AnyNum,
+        AnyInt,
+        AnyReal,
+        AnyBit,
+        AnyString,
+    }
+
+    impl GenericKind {
+        pub fn iec_name(&self) -> &'static str {
+            match self {
+                GenericKind::Any => "ANY",
+                GenericKind::AnyElementary => "ANY_ELEMENTARY",
+                GenericKind::AnyNum => "ANY_NUM",
+                GenericKind::AnyInt => "ANY_INT",
+                GenericKind::AnyReal => "ANY_REAL",
+                GenericKind::AnyBit => "ANY_BIT",
+                GenericKind::AnyString => "ANY_STRING",
+            }
+        }
+
+        pub fn from_iec_name(name: &str) -> Option<Self> {
+            let generic = match name.to_ascii_uppercase().as_str() {
+                "ANY" => GenericKind::Any,
+                "ANY_ELEMENTARY" => GenericKind::AnyElementary,
+                "ANY_NUM" => GenericKind::AnyNum,
+                "ANY_INT" => GenericKind::AnyInt,
+                "ANY_REAL" => GenericKind::AnyReal,
+                "ANY_BIT" => GenericKind::AnyBit,
+                "ANY_STRING" => GenericKind::AnyString,
+                _ => return None,
+            };
+
+            Some(generic)
+        }
+
+        /// returns whether the concrete kind belongs to the generic kind
+        pub fn contains(&self, kind: &DataKind) -> bool {
+            match self {
+                GenericKind::Any => !matches!(kind, DataKind::Generic(_)),
+                GenericKind::AnyElementary => DataKind::ELEMENTARY.contains(kind),
+                GenericKind::AnyNum => {
+                    GenericKind::AnyInt.contains(kind) || GenericKind::AnyReal.contains(kind)
+                }
+                GenericKind::AnyInt => matches!(
+                    kind,
+                    DataKind::SInt
+                        | DataKind::Int
+                        | DataKind::DInt
+                        | DataKind::LInt
+                        | DataKind::USInt
+                        | DataKind::UInt
+                        | DataKind::UDInt
+                        | DataKind::ULInt
+                ),
+                GenericKind::AnyReal => matches!(kind, DataKind::Real | DataKind::LReal),
+                GenericKind::AnyBit => matches!(
+                    kind,
+                    DataKind::Bool
+                        | DataKind::Byte
+                        | DataKind::Word
+                        | DataKind::DWord
+                        | DataKind::LWord
+                ),
+                GenericKind::AnyString => matches!(kind, DataKind::String | DataKind::WString),
+            }
+        }
+    }
+
+    /// returns whether values of `from` are implicitly widened to `to` without loss,
+    /// following the implicit conversions of `IEC 61131-3`:
+    /// - `SINT -> INT -> DINT -> LINT`, `USINT -> UINT -> UDINT -> ULINT`
+    /// - unsigned integers into the next larger signed integer (e.g. `UINT -> DINT`)
+    /// - integers into reals which represent them exactly (e.g. `INT -> REAL`, `DINT -> LREAL`), `REAL -> LREAL`
+    /// - `BOOL -> BYTE -> WORD -> DWORD -> LWORD`
+    /// - the date and time types into their long variants (e.g. `TIME -> LTIME`)
+    ///
+    /// Narrowing (e.g. `DINT -> INT`) and changing the category (e.g. `INT -> WORD`) are never implicit.
+    pub fn widens_to(from: &DataKind, to: &DataKind) -> bool {
+        let wider: &[DataKind] = match from {
+            DataKind::SInt => &[DataKind::Int, DataKind::Real],
+            DataKind::Int => &[DataKind::DInt, DataKind::Real],
+            DataKind::DInt => &[DataKind::LInt, DataKind::LReal],
+            DataKind::USInt => &[DataKind::UInt, DataKind::Int, DataKind::Real],
+            DataKind::UInt => &[DataKind::UDInt, DataKind::DInt, DataKind::Real],
+            DataKind::UDInt => &[DataKind::ULInt, DataKind::LInt, DataKind::LReal],
+            DataKind::Real => &[DataKind::LReal],
+            DataKind::Bool => &[DataKind::Byte],
+            DataKind::Byte => &[DataKind::Word],
+            DataKind::Word => &[DataKind::DWord],
+            DataKind::DWord => &[DataKind::LWord],
+            DataKind::Time => &[DataKind::LTime],
+            DataKind::Date => &[DataKind::LDate],
+            DataKind::TimeOfDay => &[DataKind::LTimeOfDay],
+            DataKind::DateTime => &[DataKind::LDateTime],
+            _ => &[],
+        };
+
+        wider.contains(to) || wider.iter().any(|kind| widens_to(kind, to))
+    }
+
+    /// returns whether a data output of kind `from` may be connected to a data input of kind `to`,
+    /// either the kinds are equal, `from` is widened to `to`, or a generic kind contains the other kind
+    /// (two generic kinds need to share a concrete kind)
+    pub fn assignable(from: &DataKind, to: &DataKind) -> bool {
+        match (from, to) {
+            (DataKind::Generic(a), DataKind::Generic(b)) => DataKind::ELEMENTARY
+                .iter()
+                .any(|kind| a.contains(kind) && b.contains(kind)),
+            (DataKind::Generic(generic), kind) | (kind, DataKind::Generic(generic)) => {
+                generic.contains(kind)
+            }
+            _ => from == to || widens_to(from, to),
+        }
+    }
+
+    /// the end of a data connection whose generic kind is fixed to the kind of the other end
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum Fixing {
+        Source(DataKind),
+        Destination(DataKind),
+    }
+
+    /// returns which end of a data connection from `from` to `to` is fixed when it is connected,
+    /// `None` if both ends are concrete, both are generic (fixed by the first value) or they are not assignable
+    pub fn fixing(from: &DataKind, to: &DataKind) -> Option<Fixing> {
+        match (from, to) {
+            (DataKind::Generic(_), DataKind::Generic(_)) => None,
+            (DataKind::Generic(generic), kind) if generic.contains(kind) => {
+                Some(Fixing::Source(kind.clone()))
+            }
+            (kind, DataKind::Generic(generic)) if generic.contains(kind) => {
+                Some(Fixing::Destination(kind.clone()))
+            }
+            _ => None,
+        }
     }
 
     /// Enables usage of implementing structs in `Data<Direction, DataType>`
@@ -125,7 +970,19 @@ pub mod ty {
         fn kind(&self) -> DataKind;
         fn get(&self) -> Self::Inner;
         fn as_buf(&self) -> DataBuffer;
-        fn set(&mut self, value: Self::Inner) -> ();
+
+        /// returns whether the value is accepted,
+        /// arrays reject values with the wrong number of elements
+        fn set(&mut self, value: Self::Inner) -> bool;
+
+        /// extracts the inner value of a matching buffer variant
+        fn from_buf(buf: &DataBuffer) -> Option<Self::Inner>;
+
+        /// fixes the concrete kind of a generic data type, returns whether the kind is accepted,
+        /// concrete data types only accept their own kind
+        fn fix_kind(&mut self, kind: &DataKind) -> bool {
+            *kind == self.kind()
+        }
     }
 
     /// Implements the `DataType` trait for a given struct.
@@ -151,8 +1008,16 @@ pub mod ty {
                     DataBuffer::$name(self.data)
                 }
 
-                fn set(&mut self, value: Self::Inner) {
+                fn set(&mut self, value: Self::Inner) -> bool {
                     self.data = value;
+                    true
+                }
+
+                fn from_buf(buf: &DataBuffer) -> Option<Self::Inner> {
+                    match buf {
+                        DataBuffer::$name(v) => Some(*v),
+                        _ => None,
+                    }
                 }
             }
         };
@@ -226,78 +1091,45 @@ pub mod ty {
 
     #[derive(Clone, Debug, Default)]
     pub struct Date {
-        data: Vec<u8>,
-    }
-
-    impl DataType for Date {
-        type Inner = Vec<u8>;
-
-        fn kind(&self) -> DataKind {
-            DataKind::Date
-        }
-
-        fn get(&self) -> Self::Inner {
-            self.data.clone()
-        }
-
-        fn as_buf(&self) -> DataBuffer {
-            DataBuffer::Date(self.data.clone())
-        }
-
-        fn set(&mut self, value: Self::Inner) {
-            self.data = value;
-        }
+        data: calendar::Date,
     }
+    impl_data_type!(Date, calendar::Date);
 
     #[derive(Clone, Debug, Default)]
     pub struct TimeOfDay {
-        data: Vec<u8>,
-    }
-
-    impl DataType for TimeOfDay {
-        type Inner = Vec<u8>;
-
-        fn kind(&self) -> DataKind {
-            DataKind::TimeOfDay
-        }
-
-        fn get(&self) -> Self::Inner {
-            self.data.clone()
-        }
-
-        fn as_buf(&self) -> DataBuffer {
-            DataBuffer::TimeOfDay(self.data.clone())
-        }
-
-        fn set(&mut self, value: Self::Inner) {
-            self.data = value;
-        }
+        data: calendar::TimeOfDay,
     }
+    impl_data_type!(TimeOfDay, calendar::TimeOfDay);
 
     #[derive(Clone, Debug, Default)]
     pub struct DateTime {
-        data: Vec<u8>,
+        data: calendar::DateAndTime,
     }
+    impl_data_type!(DateTime, calendar::DateAndTime);
 
-    impl DataType for DateTime {
-        type Inner = Vec<u8>;
-
-        fn kind(&self) -> DataKind {
-            DataKind::DateTime
-        }
+    #[derive(Clone, Debug, Default)]
+    pub struct LTime {
+        data: Duration,
+    }
+    impl_data_type!(LTime, Duration);
 
-        fn get(&self) -> Self::Inner {
-            self.data.clone()
-        }
+    #[derive(Clone, Debug, Default)]
+    pub struct LDate {
+        data: calendar::Date,
+    }
+    impl_data_type!(LDate, calendar::Date);
 
-        fn as_buf(&self) -> DataBuffer {
-            DataBuffer::DateTime(self.data.clone())
-        }
+    #[derive(Clone, Debug, Default)]
+    pub struct LTimeOfDay {
+        data: calendar::TimeOfDay,
+    }
+    impl_data_type!(LTimeOfDay, calendar::TimeOfDay);
 
-        fn set(&mut self, value: Self::Inner) {
-            self.data = value;
-        }
+    #[derive(Clone, Debug, Default)]
+    pub struct LDateTime {
+        data: calendar::DateAndTime,
     }
+    impl_data_type!(LDateTime, calendar::DateAndTime);
 
     #[derive(Clone, Debug, Default)]
     pub struct String {
@@ -319,8 +1151,16 @@ pub mod ty {
             DataBuffer::String(self.data.clone())
         }
 
-        fn set(&mut self, value: Self::Inner) {
+        fn set(&mut self, value: Self::Inner) -> bool {
             self.data = value;
+            true
+        }
+
+        fn from_buf(buf: &DataBuffer) -> Option<Self::Inner> {
+            match buf {
+                DataBuffer::String(v) => Some(v.clone()),
+                _ => None,
+            }
         }
     }
 
@@ -344,8 +1184,16 @@ pub mod ty {
             DataBuffer::WString(self.data.clone())
         }
 
-        fn set(&mut self, value: Self::Inner) {
+        fn set(&mut self, value: Self::Inner) -> bool {
             self.data = value;
+            true
+        }
+
+        fn from_buf(buf: &DataBuffer) -> Option<Self::Inner> {
+            match buf {
+                DataBuffer::WString(v) => Some(v.clone()),
+                _ => None,
+            }
         }
     }
 
@@ -378,4 +1226,64 @@ pub mod ty {
         data: u64,
     }
     impl_data_type!(LWord, u64);
+
+    /// Implements the `DataType` trait for a generic data type (see `GenericKind`).
+    ///
+    /// The value is stored as `DataBuffer`, which is `Unassigned` until the kind is fixed.
+    macro_rules! impl_generic_type {
+        ($name:ident) => {
+            #[derive(Clone, Debug, Default)]
+            pub struct $name {
+                data: DataBuffer,
+            }
+
+            impl DataType for $name {
+                type Inner = DataBuffer;
+
+                fn kind(&self) -> DataKind {
+                    self.data
+                        .kind()
+                        .unwrap_or(DataKind::Generic(GenericKind::$name))
+                }
+
+                fn get(&self) -> Self::Inner {
+                    self.data.clone()
+                }
+
+                fn as_buf(&self) -> DataBuffer {
+                    self.data.clone()
+                }
+
+                fn set(&mut self, value: Self::Inner) -> bool {
+                    self.data = value;
+                    true
+                }
+
+                fn from_buf(buf: &DataBuffer) -> Option<Self::Inner> {
+                    GenericKind::$name
+                        .contains(&buf.kind()?)
+                        .then(|| buf.clone())
+                }
+
+                fn fix_kind(&mut self, kind: &DataKind) -> bool {
+                    match self.data.kind() {
+                        Some(fixed) => fixed == *kind,
+                        None if GenericKind::$name.contains(kind) => {
+                            self.data = DataBuffer::default_for(kind);
+                            true
+                        }
+                        None => false,
+                    }
+                }
+            }
+        };
+    }
+
+    impl_generic_type!(Any);
+    impl_generic_type!(AnyElementary);
+    impl_generic_type!(AnyNum);
+    impl_generic_type!(AnyInt);
+    impl_generic_type!(AnyReal);
+    impl_generic_type!(AnyBit);
+    impl_generic_type!(AnyString);
 }
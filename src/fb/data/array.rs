@@ -0,0 +1,465 @@
+//! `ARRAY [lo..hi, ...] OF <type>` data types of `IEC 61131-3`.
+//!
+//! An `ArrayKind` consists of the bounds of every dimension and the element kind,
+//! two arrays are only assignable if both match (see `ty::assignable`).
+//! The elements of an `ArrayBuffer` are stored in row-major order, i.e. the last index changes fastest.
+//!
+//! Inside of function blocks arrays are declared with `Array`, `Array2` and `Array3`,
+//! e.g. `Data<In, Array<Int, 0, 15>>` for `ARRAY [0..15] OF INT`.
+
+use std::{fmt::Display, ops::RangeInclusive};
+
+use crate::fb::data::{
+    comm::DataBuffer,
+    ty::{DataKind, DataType},
+};
+
+/// the bounds of every dimension and the element kind of an array
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ArrayKind {
+    bounds: Vec<RangeInclusive<i64>>,
+    element: Box<DataKind>,
+}
+
+impl ArrayKind {
+    /// fails without dimensions, for empty bounds (`hi < lo`) and for generic element kinds
+    pub fn new(bounds: Vec<RangeInclusive<i64>>, element: DataKind) -> Option<Self> {
+        if bounds.is_empty()
+            || bounds.iter().any(RangeInclusive::is_empty)
+            || matches!(element, DataKind::Generic(_))
+        {
+            return None;
+        }
+
+        let kind = Self {
+            bounds,
+            element: Box::new(element),
+        };
+
+        // the number of elements needs to be addressable
+        kind.bounds
+            .iter()
+            .try_fold(1usize, |count, b| count.checked_mul(dimension_len(b)?))?;
+
+        Some(kind)
+    }
+
+    /// parses a declaration like `ARRAY [0..15, 1..2] OF INT` (case-insensitive)
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        let (prefix, rest) = text.split_at_checked(5)?;
+
+        if !prefix.eq_ignore_ascii_case("ARRAY") {
+            return None;
+        }
+
+        let (bounds, element) = rest.trim_start().strip_prefix('[')?.split_once(']')?;
+        let element = element.trim_start();
+        let (of, element) = element.split_at_checked(2)?;
+
+        if !of.eq_ignore_ascii_case("OF") || !element.starts_with(char::is_whitespace) {
+            return None;
+        }
+
+        Self::new(
+            parse_bounds(bounds)?,
+            DataKind::from_iec_name(element.trim())?,
+        )
+    }
+
+    pub fn bounds(&self) -> &[RangeInclusive<i64>] {
+        &self.bounds
+    }
+
+    pub fn element(&self) -> &DataKind {
+        &self.element
+    }
+
+    /// returns the number of elements of all dimensions
+    pub fn element_count(&self) -> usize {
+        self.bounds
+            .iter()
+            .map(|b| dimension_len(b).expect("bounds are checked on creation"))
+            .product()
+    }
+
+    /// returns the position of the element in row-major order, see `offset`
+    pub fn offset(&self, index: &[i64]) -> Option<usize> {
+        offset(&self.bounds, index)
+    }
+}
+
+/// returns the position of the element in row-major order,
+/// `None` if the number of indices does not match the dimensions or an index is out of bounds
+pub fn offset(bounds: &[RangeInclusive<i64>], index: &[i64]) -> Option<usize> {
+    if index.len() != bounds.len() {
+        return None;
+    }
+
+    index.iter().zip(bounds).try_fold(0, |offset, (i, b)| {
+        if !b.contains(i) {
+            return None;
+        }
+
+        let position = usize::try_from(i - b.start()).ok()?;
+
+        Some(offset * dimension_len(b)? + position)
+    })
+}
+
+/// formats the declaration, e.g. `ARRAY [0..15, 1..2] OF INT`
+impl Display for ArrayKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let bounds: Vec<String> = self
+            .bounds
+            .iter()
+            .map(|b| format!("{}..{}", b.start(), b.end()))
+            .collect();
+
+        write!(
+            f,
+            "ARRAY [{}] OF {}",
+            bounds.join(", "),
+            self.element.iec_name()
+        )
+    }
+}
+
+/// parses comma separated bounds like `0..15, 1..2`
+pub fn parse_bounds(text: &str) -> Option<Vec<RangeInclusive<i64>>> {
+    text.split(',')
+        .map(|b| {
+            let (lo, hi) = b.split_once("..")?;
+
+            Some(lo.trim().parse().ok()?..=hi.trim().parse().ok()?)
+        })
+        .collect()
+}
+
+fn dimension_len(bounds: &RangeInclusive<i64>) -> Option<usize> {
+    usize::try_from(bounds.end().checked_sub(*bounds.start())?.checked_add(1)?).ok()
+}
+
+/// an index outside of the bounds or an element of the wrong kind
+#[derive(Clone, Debug, PartialEq)]
+pub enum ArrayError {
+    OutOfBounds { index: Vec<i64>, kind: ArrayKind },
+    ElementMismatch { value: DataBuffer, kind: ArrayKind },
+}
+
+impl Display for ArrayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArrayError::OutOfBounds { index, kind } => {
+                write!(f, "index {index:?} is out of the bounds of {kind}")
+            }
+            ArrayError::ElementMismatch { value, kind } => {
+                write!(f, "{value} is no element of {kind}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ArrayError {}
+
+/// the value of an array, the number and kinds of the elements always match the `ArrayKind`
+#[derive(Clone, Debug, PartialEq)]
+pub struct ArrayBuffer {
+    kind: ArrayKind,
+    elements: Vec<DataBuffer>,
+}
+
+impl ArrayBuffer {
+    /// every element holds the initial value of the element kind
+    pub fn new(kind: ArrayKind) -> Self {
+        let elements = vec![DataBuffer::default_for(kind.element()); kind.element_count()];
+
+        Self { kind, elements }
+    }
+
+    /// fails if the number or the kinds of the elements do not match the array kind
+    pub fn from_elements(kind: ArrayKind, elements: Vec<DataBuffer>) -> Option<Self> {
+        let valid = elements.len() == kind.element_count()
+            && elements
+                .iter()
+                .all(|e| e.kind().as_ref() == Some(kind.element()));
+
+        valid.then_some(Self { kind, elements })
+    }
+
+    pub fn kind(&self) -> &ArrayKind {
+        &self.kind
+    }
+
+    /// returns the elements in row-major order
+    pub fn elements(&self) -> &[DataBuffer] {
+        &self.elements
+    }
+
+    /// returns the element at the index (one index per dimension), `None` if the index is out of bounds
+    pub fn get(&self, index: &[i64]) -> Option<&DataBuffer> {
+        self.elements.get(self.kind.offset(index)?)
+    }
+
+    /// sets the element at the index (one index per dimension),
+    /// fails if the index is out of bounds or the value does not match the element kind
+    pub fn set(&mut self, index: &[i64], value: DataBuffer) -> Result<(), ArrayError> {
+        let Some(offset) = self.kind.offset(index) else {
+            return Err(ArrayError::OutOfBounds {
+                index: index.to_vec(),
+                kind: self.kind.clone(),
+            });
+        };
+
+        if value.kind().as_ref() != Some(self.kind.element()) {
+            return Err(ArrayError::ElementMismatch {
+                value,
+                kind: self.kind.clone(),
+            });
+        }
+
+        self.elements[offset] = value;
+
+        Ok(())
+    }
+
+    /// parses an array literal like `[1, 2, 3]` or `[16(0)]` (`n(value)` repeats the value),
+    /// missing elements keep the initial value of the element kind
+    pub fn parse_literal(kind: &ArrayKind, text: &str) -> Option<Self> {
+        let mut elements = vec![];
+
+        for (count, value) in literal_items(text)? {
+            // checked before repeating, the count is part of the input
+            if count > kind.element_count() - elements.len() {
+                return None;
+            }
+
+            let value = DataBuffer::parse_literal(kind.element(), value)?;
+            elements.extend(std::iter::repeat_n(value, count));
+        }
+
+        let mut array = Self::new(kind.clone());
+        array.elements[..elements.len()].clone_from_slice(&elements);
+
+        Some(array)
+    }
+}
+
+/// formats the elements in row-major order as array literal, e.g. `[INT#1, INT#2]`
+impl Display for ArrayBuffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let elements: Vec<String> = self.elements.iter().map(DataBuffer::to_string).collect();
+
+        write!(f, "[{}]", elements.join(", "))
+    }
+}
+
+/// returns the repeat count and the value of every item of an array literal like `[1, 2(0)]`
+pub(crate) fn literal_items(text: &str) -> Option<Vec<(usize, &str)>> {
+    let text = text.trim().strip_prefix('[')?.strip_suffix(']')?;
+
+    split_items(text)?
+        .into_iter()
+        .map(|item| match item.split_once('(') {
+            Some((count, value)) if !item.starts_with(['\'', '"']) => {
+                Some((count.trim().parse().ok()?, value.strip_suffix(')')?.trim()))
+            }
+            _ => Some((1, item)),
+        })
+        .collect()
+}
+
+/// splits the items of an array literal at the commas outside of strings and parentheses
+fn split_items(text: &str) -> Option<Vec<&str>> {
+    if text.trim().is_empty() {
+        return Some(vec![]);
+    }
+
+    let mut items = vec![];
+    let mut start = 0;
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut escaped = false;
+
+    for (i, c) in text.char_indices() {
+        match (quote, c) {
+            (Some(_), _) if escaped => escaped = false,
+            (Some(_), '$') => escaped = true,
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '(' | '[') => depth += 1,
+            (None, ')' | ']') => depth = depth.checked_sub(1)?,
+            (None, ',') if depth == 0 => {
+                items.push(text[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+
+    items.push(text[start..].trim());
+
+    (quote.is_none() && depth == 0 && items.iter().all(|i| !i.is_empty())).then_some(items)
+}
+
+/// Implemented by the array data types, gives typed access to the elements.
+pub trait ArrayType: DataType {
+    type Element: DataType;
+
+    /// the bounds of every dimension, available without building the `ArrayKind`
+    const BOUNDS: &'static [RangeInclusive<i64>];
+
+    fn array_kind() -> ArrayKind;
+
+    /// returns the elements in row-major order
+    fn elements(&self) -> &[<Self::Element as DataType>::Inner];
+    fn elements_mut(&mut self) -> &mut [<Self::Element as DataType>::Inner];
+}
+
+/// Declares an array data type with const generic bounds `$lo..$hi` for every dimension.
+///
+/// The element type `T` needs to be a concrete data type, its inner value is stored for every element.
+macro_rules! array_type {
+    ($(#[$doc:meta])* $name:ident, $($lo:ident..$hi:ident),+) => {
+        $(#[$doc])*
+        pub struct $name<T: DataType, $(const $lo: i64, const $hi: i64),+> {
+            data: Vec<T::Inner>,
+        }
+
+        impl<T, $(const $lo: i64, const $hi: i64),+> Default for $name<T, $($lo, $hi),+>
+        where
+            T: DataType + Default,
+            T::Inner: Clone,
+        {
+            fn default() -> Self {
+                Self {
+                    data: (0..Self::array_kind().element_count())
+                        .map(|_| T::default().get())
+                        .collect(),
+                }
+            }
+        }
+
+        impl<T, $(const $lo: i64, const $hi: i64),+> Clone for $name<T, $($lo, $hi),+>
+        where
+            T: DataType,
+            T::Inner: Clone,
+        {
+            fn clone(&self) -> Self {
+                Self {
+                    data: self.data.clone(),
+                }
+            }
+        }
+
+        impl<T, $(const $lo: i64, const $hi: i64),+> std::fmt::Debug for $name<T, $($lo, $hi),+>
+        where
+            T: DataType,
+            T::Inner: std::fmt::Debug,
+        {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_struct(stringify!($name))
+                    .field("data", &self.data)
+                    .finish()
+            }
+        }
+
+        impl<T, $(const $lo: i64, const $hi: i64),+> DataType for $name<T, $($lo, $hi),+>
+        where
+            T: DataType + Default,
+            T::Inner: Clone,
+        {
+            type Inner = Vec<T::Inner>;
+
+            fn kind(&self) -> DataKind {
+                DataKind::Array(Box::new(Self::array_kind()))
+            }
+
+            fn get(&self) -> Self::Inner {
+                self.data.clone()
+            }
+
+            fn as_buf(&self) -> DataBuffer {
+                let elements = self
+                    .data
+                    .iter()
+                    .map(|value| {
+                        let mut element = T::default();
+                        element.set(value.clone());
+                        element.as_buf()
+                    })
+                    .collect();
+
+                DataBuffer::Array(Box::new(
+                    ArrayBuffer::from_elements(Self::array_kind(), elements)
+                        .expect("elements match the array kind"),
+                ))
+            }
+
+            /// values with the wrong number of elements are rejected
+            fn set(&mut self, value: Self::Inner) -> bool {
+                if value.len() != self.data.len() {
+                    return false;
+                }
+
+                self.data = value;
+                true
+            }
+
+            fn from_buf(buf: &DataBuffer) -> Option<Self::Inner> {
+                match buf {
+                    DataBuffer::Array(array)
+                        if array.kind().bounds() == Self::BOUNDS
+                            && *array.kind().element() == T::default().kind() =>
+                    {
+                        array.elements().iter().map(T::from_buf).collect()
+                    }
+                    _ => None,
+                }
+            }
+        }
+
+        impl<T, $(const $lo: i64, const $hi: i64),+> ArrayType for $name<T, $($lo, $hi),+>
+        where
+            T: DataType + Default,
+            T::Inner: Clone,
+        {
+            type Element = T;
+
+            const BOUNDS: &'static [RangeInclusive<i64>] = &[$($lo..=$hi),+];
+
+            fn array_kind() -> ArrayKind {
+                ArrayKind::new(Self::BOUNDS.to_vec(), T::default().kind())
+                    .expect("array bounds are valid and the element type is concrete")
+            }
+
+            fn elements(&self) -> &[T::Inner] {
+                &self.data
+            }
+
+            fn elements_mut(&mut self) -> &mut [T::Inner] {
+                &mut self.data
+            }
+        }
+    };
+}
+
+array_type!(
+    /// `ARRAY [LO..HI] OF T`
+    Array,
+    LO..HI
+);
+array_type!(
+    /// `ARRAY [LO1..HI1, LO2..HI2] OF T`
+    Array2,
+    LO1..HI1,
+    LO2..HI2
+);
+array_type!(
+    /// `ARRAY [LO1..HI1, LO2..HI2, LO3..HI3] OF T`
+    Array3,
+    LO1..HI1,
+    LO2..HI2,
+    LO3..HI3
+);
@@ -0,0 +1,415 @@
+//! Calendar value types of the `IEC 61131-3` date and time data types.
+//!
+//! - `Date` counts days since the Unix epoch (`1970-01-01`), years are limited to `0000..=9999`
+//! - `TimeOfDay` counts nanoseconds since midnight
+//! - `DateAndTime` counts nanoseconds since the Unix epoch, which covers the years `1677..=2262`
+//!
+//! All types use the proleptic Gregorian calendar without time zones or leap seconds.
+//! The short (`DATE`, `TOD`, `DT`) and long (`LDATE`, `LTOD`, `LDT`) data types share these value types,
+//! they only differ in their `DataKind`.
+//!
+//! `Display` writes the value of the literal without type prefix (e.g. `2024-01-31-12:00:00.5`),
+//! `FromStr` parses it back.
+
+use std::{
+    fmt::Display,
+    ops::{Add, Sub},
+    str::FromStr,
+    time::{Duration, SystemTime},
+};
+
+use crate::fb::data::comm::LiteralError;
+
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+const NANOS_PER_DAY: u64 = 86_400 * NANOS_PER_SECOND;
+
+/// unix days of `0000-01-01` and `9999-12-31`, the range of `Date`
+const MIN_DAYS: i64 = -719_528;
+const MAX_DAYS: i64 = 2_932_896;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    days: i64,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TimeOfDay {
+    nanos: u64,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DateAndTime {
+    nanos: i64,
+}
+
+impl Date {
+    /// returns `None` for invalid dates like `2023-02-29`
+    pub fn new(year: i64, month: u32, day: u32) -> Option<Self> {
+        if !(0..=9999).contains(&year)
+            || !(1..=12).contains(&month)
+            || day == 0
+            || day > days_in_month(year, month)
+        {
+            return None;
+        }
+
+        Some(Self {
+            days: days_from_civil(year, month, day),
+        })
+    }
+
+    pub fn from_unix_days(days: i64) -> Option<Self> {
+        (MIN_DAYS..=MAX_DAYS)
+            .contains(&days)
+            .then_some(Self { days })
+    }
+
+    pub fn unix_days(&self) -> i64 {
+        self.days
+    }
+
+    pub fn year(&self) -> i64 {
+        civil_from_days(self.days).0
+    }
+
+    pub fn month(&self) -> u32 {
+        civil_from_days(self.days).1
+    }
+
+    pub fn day(&self) -> u32 {
+        civil_from_days(self.days).2
+    }
+
+    /// returns `None` if the result leaves the supported years
+    pub fn checked_add_days(&self, days: i64) -> Option<Self> {
+        Self::from_unix_days(self.days.checked_add(days)?)
+    }
+
+    /// number of days from `earlier` to `self`, negative if `earlier` is later
+    pub fn days_since(&self, earlier: &Date) -> i64 {
+        self.days - earlier.days
+    }
+}
+
+impl TimeOfDay {
+    /// returns `None` for invalid times like `24:00:00`
+    pub fn new(hour: u32, minute: u32, second: u32, nanos: u32) -> Option<Self> {
+        if hour > 23 || minute > 59 || second > 59 || u64::from(nanos) >= NANOS_PER_SECOND {
+            return None;
+        }
+
+        let seconds = u64::from(hour) * 3600 + u64::from(minute) * 60 + u64::from(second);
+
+        Some(Self {
+            nanos: seconds * NANOS_PER_SECOND + u64::from(nanos),
+        })
+    }
+
+    /// the time elapsed since midnight, returns `None` for a day or more
+    pub fn from_duration(since_midnight: Duration) -> Option<Self> {
+        let nanos: u64 = since_midnight.as_nanos().try_into().ok()?;
+
+        (nanos < NANOS_PER_DAY).then_some(Self { nanos })
+    }
+
+    /// the time elapsed since midnight
+    pub fn as_duration(&self) -> Duration {
+        Duration::from_nanos(self.nanos)
+    }
+
+    pub fn hour(&self) -> u32 {
+        (self.nanos / (3600 * NANOS_PER_SECOND)) as u32
+    }
+
+    pub fn minute(&self) -> u32 {
+        (self.nanos / (60 * NANOS_PER_SECOND) % 60) as u32
+    }
+
+    pub fn second(&self) -> u32 {
+        (self.nanos / NANOS_PER_SECOND % 60) as u32
+    }
+
+    pub fn nanosecond(&self) -> u32 {
+        (self.nanos % NANOS_PER_SECOND) as u32
+    }
+
+    /// time from `earlier` to `self`, returns `None` if `earlier` is later
+    pub fn duration_since(&self, earlier: &TimeOfDay) -> Option<Duration> {
+        self.nanos
+            .checked_sub(earlier.nanos)
+            .map(Duration::from_nanos)
+    }
+}
+
+impl DateAndTime {
+    pub fn new(date: Date, time_of_day: TimeOfDay) -> Option<Self> {
+        let nanos =
+            i128::from(date.days) * i128::from(NANOS_PER_DAY) + i128::from(time_of_day.nanos);
+
+        Some(Self {
+            nanos: nanos.try_into().ok()?,
+        })
+    }
+
+    pub fn from_unix_nanos(nanos: i64) -> Self {
+        Self { nanos }
+    }
+
+    pub fn unix_nanos(&self) -> i64 {
+        self.nanos
+    }
+
+    pub fn from_unix_secs(secs: i64) -> Option<Self> {
+        Some(Self {
+            nanos: secs.checked_mul(NANOS_PER_SECOND as i64)?,
+        })
+    }
+
+    /// whole seconds since the epoch, rounded down
+    pub fn unix_secs(&self) -> i64 {
+        self.nanos.div_euclid(NANOS_PER_SECOND as i64)
+    }
+
+    pub fn from_system_time(time: SystemTime) -> Option<Self> {
+        let nanos = match time.duration_since(SystemTime::UNIX_EPOCH) {
+            Ok(after) => i64::try_from(after.as_nanos()).ok()?,
+            Err(e) => -i64::try_from(e.duration().as_nanos()).ok()?,
+        };
+
+        Some(Self { nanos })
+    }
+
+    /// the current system time (UTC)
+    pub fn now() -> Self {
+        Self::from_system_time(SystemTime::now()).expect("system time is within the range of DT")
+    }
+
+    pub fn date(&self) -> Date {
+        Date {
+            days: self.nanos.div_euclid(NANOS_PER_DAY as i64),
+        }
+    }
+
+    pub fn time_of_day(&self) -> TimeOfDay {
+        TimeOfDay {
+            nanos: self.nanos.rem_euclid(NANOS_PER_DAY as i64) as u64,
+        }
+    }
+
+    pub fn checked_add(&self, duration: Duration) -> Option<Self> {
+        let nanos = i64::try_from(duration.as_nanos()).ok()?;
+
+        Some(Self {
+            nanos: self.nanos.checked_add(nanos)?,
+        })
+    }
+
+    pub fn checked_sub(&self, duration: Duration) -> Option<Self> {
+        let nanos = i64::try_from(duration.as_nanos()).ok()?;
+
+        Some(Self {
+            nanos: self.nanos.checked_sub(nanos)?,
+        })
+    }
+
+    /// time from `earlier` to `self`, returns `None` if `earlier` is later
+    pub fn duration_since(&self, earlier: &DateAndTime) -> Option<Duration> {
+        let nanos = self.nanos.checked_sub(earlier.nanos)?;
+
+        u64::try_from(nanos).ok().map(Duration::from_nanos)
+    }
+}
+
+/// panics if the result is out of range, see `checked_add`
+impl Add<Duration> for DateAndTime {
+    type Output = DateAndTime;
+
+    fn add(self, duration: Duration) -> Self::Output {
+        self.checked_add(duration)
+            .expect("overflow when adding a duration to DT")
+    }
+}
+
+/// panics if the result is out of range, see `checked_sub`
+impl Sub<Duration> for DateAndTime {
+    type Output = DateAndTime;
+
+    fn sub(self, duration: Duration) -> Self::Output {
+        self.checked_sub(duration)
+            .expect("overflow when subtracting a duration from DT")
+    }
+}
+
+/// wraps around midnight
+impl Add<Duration> for TimeOfDay {
+    type Output = TimeOfDay;
+
+    fn add(self, duration: Duration) -> Self::Output {
+        let nanos = (u128::from(self.nanos) + duration.as_nanos()) % u128::from(NANOS_PER_DAY);
+
+        TimeOfDay {
+            nanos: nanos as u64,
+        }
+    }
+}
+
+/// wraps around midnight
+impl Sub<Duration> for TimeOfDay {
+    type Output = TimeOfDay;
+
+    fn sub(self, duration: Duration) -> Self::Output {
+        let day = u128::from(NANOS_PER_DAY);
+        let nanos = (u128::from(self.nanos) + day - duration.as_nanos() % day) % day;
+
+        TimeOfDay {
+            nanos: nanos as u64,
+        }
+    }
+}
+
+impl Display for Date {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (year, month, day) = civil_from_days(self.days);
+
+        write!(f, "{year:04}-{month:02}-{day:02}")
+    }
+}
+
+/// fractions of a second are written without trailing zeros
+impl Display for TimeOfDay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:02}:{:02}:{:02}",
+            self.hour(),
+            self.minute(),
+            self.second()
+        )?;
+
+        if self.nanosecond() > 0 {
+            let fraction = format!("{:09}", self.nanosecond());
+            write!(f, ".{}", fraction.trim_end_matches('0'))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for DateAndTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.date(), self.time_of_day())
+    }
+}
+
+/// parses `2024-01-31`
+impl FromStr for Date {
+    type Err = LiteralError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let error = || LiteralError(text.to_string());
+        let mut parts = text.split('-');
+        let mut next = || parts.next().ok_or_else(error);
+
+        let year = next()?.parse().map_err(|_| error())?;
+        let month = next()?.parse().map_err(|_| error())?;
+        let day = next()?.parse().map_err(|_| error())?;
+
+        if parts.next().is_some() {
+            return Err(error());
+        }
+
+        Date::new(year, month, day).ok_or_else(error)
+    }
+}
+
+/// parses `12:00:00` or `08:30:15.25`, fractions have at most nanosecond resolution
+impl FromStr for TimeOfDay {
+    type Err = LiteralError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let error = || LiteralError(text.to_string());
+        let mut parts = text.split(':');
+        let mut next = || parts.next().ok_or_else(error);
+
+        let hour = next()?.parse().map_err(|_| error())?;
+        let minute = next()?.parse().map_err(|_| error())?;
+        let seconds = next()?;
+        let (second, fraction) = seconds.split_once('.').unwrap_or((seconds, ""));
+        let second = second.parse().map_err(|_| error())?;
+
+        if parts.next().is_some()
+            || fraction.len() > 9
+            || !fraction.chars().all(|c| c.is_ascii_digit())
+        {
+            return Err(error());
+        }
+
+        let nanos = format!("{fraction:0<9}").parse().map_err(|_| error())?;
+
+        TimeOfDay::new(hour, minute, second, nanos).ok_or_else(error)
+    }
+}
+
+/// parses `2024-01-31-12:00:00`
+impl FromStr for DateAndTime {
+    type Err = LiteralError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let error = || LiteralError(text.to_string());
+
+        let split = text
+            .match_indices('-')
+            .nth(2)
+            .map(|(index, _)| index)
+            .ok_or_else(error)?;
+
+        let date = text[..split].parse().map_err(|_| error())?;
+        let time_of_day = text[split + 1..].parse().map_err(|_| error())?;
+
+        DateAndTime::new(date, time_of_day).ok_or_else(error)
+    }
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+
+    match month {
+        2 if leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// days since the epoch of a date in the proleptic Gregorian calendar (algorithm by Howard Hinnant)
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month_from_march = i64::from((month + 9) % 12);
+    let day_of_year = (153 * month_from_march + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// inverse of `days_from_civil`
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_from_march + 2) / 5 + 1) as u32;
+    let month = if month_from_march < 10 {
+        month_from_march + 3
+    } else {
+        month_from_march - 9
+    } as u32;
+
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    (year, month, day)
+}
@@ -0,0 +1,366 @@
+//! Type conversion functions of `IEC 61131-3` (`UINT_TO_DINT`, `REAL_TO_INT`, ...) over `DataBuffer`s.
+//!
+//! - integers, bit strings and `BOOL` convert into each other by value, values which do not fit are rejected
+//! - reals are rounded to the nearest integer (halfway cases away from zero), `trunc` rounds towards zero
+//! - bit strings convert into shorter bit strings by keeping the rightmost bits
+//! - `BOOL` is `TRUE` for every value except zero
+//! - `TIME` converts to and from integers in milliseconds, `LTIME` in nanoseconds
+//! - `DT` converts into its `DATE` and `TOD`, the long and short date and time types convert into each other
+//! - every type converts into `STRING`/`WSTRING` as literal (numbers without type prefix, e.g. `5` or `T#1s`),
+//!   strings convert into other types by parsing such a literal
+
+use std::{fmt::Display, time::Duration};
+
+use crate::fb::data::{
+    comm::DataBuffer,
+    ty::{self, DataKind},
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConversionError {
+    /// the value does not fit into the target type (e.g. `UINT_TO_SINT(300)`)
+    OutOfRange { value: DataBuffer, to: DataKind },
+    /// the string is no literal of the target type
+    InvalidLiteral { text: String, to: DataKind },
+    /// there is no conversion between the two types (e.g. `DATE_TO_REAL`)
+    Unsupported { from: DataKind, to: DataKind },
+    /// the name is no conversion function (e.g. `UINT_TO_FOO`)
+    UnknownFunction(String),
+    /// unassigned values can not be converted
+    Unassigned,
+}
+
+impl Display for ConversionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConversionError::OutOfRange { value, to } => {
+                write!(f, "{value} is out of range for {}", to.iec_name())
+            }
+            ConversionError::InvalidLiteral { text, to } => {
+                write!(f, "\"{text}\" is no literal of {}", to.iec_name())
+            }
+            ConversionError::Unsupported { from, to } => write!(
+                f,
+                "{} can not be converted to {}",
+                from.iec_name(),
+                to.iec_name()
+            ),
+            ConversionError::UnknownFunction(name) => {
+                write!(f, "unknown conversion function \"{name}\"")
+            }
+            ConversionError::Unassigned => write!(f, "unassigned value can not be converted"),
+        }
+    }
+}
+
+impl std::error::Error for ConversionError {}
+
+/// returns the name of the conversion function, e.g. `UINT_TO_DINT`
+pub fn function_name(from: &DataKind, to: &DataKind) -> String {
+    format!("{}_TO_{}", from.iec_name(), to.iec_name())
+}
+
+/// returns the source and target type of a conversion function name like `UINT_TO_DINT`,
+/// the names are case-insensitive and may use the short type names (e.g. `DT_TO_TOD`)
+pub fn parse_function_name(name: &str) -> Option<(DataKind, DataKind)> {
+    let (from, to) = name
+        .to_ascii_uppercase()
+        .split_once("_TO_")
+        .map(|(from, to)| (DataKind::from_iec_name(from), DataKind::from_iec_name(to)))?;
+
+    let (from, to) = (from?, to?);
+
+    supports(&from, &to).then_some((from, to))
+}
+
+/// returns whether values of `from` can be converted to `to`
+pub fn supports(from: &DataKind, to: &DataKind) -> bool {
+    use Category::*;
+
+    match (Category::of(from), Category::of(to)) {
+        _ if from == to => false,
+        (Generic | Array, _) | (_, Generic | Array) => false,
+        (String, _) | (_, String) => true,
+        (Number, Number) | (Number, Time) | (Time, Number) | (Time, Time) => true,
+        (Date, Date) | (TimeOfDay, TimeOfDay) | (DateTime, DateTime) => true,
+        (DateTime, Date) | (DateTime, TimeOfDay) => true,
+        _ => false,
+    }
+}
+
+/// calls a conversion function by name, e.g. `call("REAL_TO_INT", &DataBuffer::Real(2.5))`,
+/// the value has to be of the source type
+pub fn call(name: &str, value: &DataBuffer) -> Result<DataBuffer, ConversionError> {
+    let (from, to) =
+        parse_function_name(name).ok_or_else(|| ConversionError::UnknownFunction(name.into()))?;
+
+    match value.kind() {
+        Some(kind) if kind == from => convert(value, &to),
+        Some(kind) => Err(ConversionError::Unsupported { from: kind, to }),
+        None => Err(ConversionError::Unassigned),
+    }
+}
+
+/// converts the value to the given type, see the module documentation for the rules
+pub fn convert(value: &DataBuffer, to: &DataKind) -> Result<DataBuffer, ConversionError> {
+    let from = value.kind().ok_or(ConversionError::Unassigned)?;
+
+    if from == *to {
+        return Ok(value.clone());
+    }
+
+    let unsupported = || ConversionError::Unsupported {
+        from: from.clone(),
+        to: to.clone(),
+    };
+    let out_of_range = || ConversionError::OutOfRange {
+        value: value.clone(),
+        to: to.clone(),
+    };
+
+    if Category::of(&from) == Category::Array {
+        return Err(unsupported());
+    }
+
+    if Category::of(to) == Category::String {
+        let text = to_text(value);
+
+        return Ok(match to {
+            DataKind::String => DataBuffer::String(text.into_bytes()),
+            _ => DataBuffer::WString(text.encode_utf16().collect()),
+        });
+    }
+
+    match value {
+        DataBuffer::String(v) => return from_text(&String::from_utf8_lossy(v), to),
+        DataBuffer::WString(v) => return from_text(&String::from_utf16_lossy(v), to),
+        _ => {}
+    }
+
+    match (value, to) {
+        (DataBuffer::Time(v) | DataBuffer::LTime(v), DataKind::Time) => Ok(DataBuffer::Time(*v)),
+        (DataBuffer::Time(v) | DataBuffer::LTime(v), DataKind::LTime) => Ok(DataBuffer::LTime(*v)),
+        (DataBuffer::Date(v) | DataBuffer::LDate(v), DataKind::Date) => Ok(DataBuffer::Date(*v)),
+        (DataBuffer::Date(v) | DataBuffer::LDate(v), DataKind::LDate) => Ok(DataBuffer::LDate(*v)),
+        (DataBuffer::TimeOfDay(v) | DataBuffer::LTimeOfDay(v), DataKind::TimeOfDay) => {
+            Ok(DataBuffer::TimeOfDay(*v))
+        }
+        (DataBuffer::TimeOfDay(v) | DataBuffer::LTimeOfDay(v), DataKind::LTimeOfDay) => {
+            Ok(DataBuffer::LTimeOfDay(*v))
+        }
+        (DataBuffer::DateTime(v) | DataBuffer::LDateTime(v), DataKind::DateTime) => {
+            Ok(DataBuffer::DateTime(*v))
+        }
+        (DataBuffer::DateTime(v) | DataBuffer::LDateTime(v), DataKind::LDateTime) => {
+            Ok(DataBuffer::LDateTime(*v))
+        }
+        (DataBuffer::DateTime(v) | DataBuffer::LDateTime(v), DataKind::Date) => {
+            Ok(DataBuffer::Date(v.date()))
+        }
+        (DataBuffer::DateTime(v) | DataBuffer::LDateTime(v), DataKind::LDate) => {
+            Ok(DataBuffer::LDate(v.date()))
+        }
+        (DataBuffer::DateTime(v) | DataBuffer::LDateTime(v), DataKind::TimeOfDay) => {
+            Ok(DataBuffer::TimeOfDay(v.time_of_day()))
+        }
+        (DataBuffer::DateTime(v) | DataBuffer::LDateTime(v), DataKind::LTimeOfDay) => {
+            Ok(DataBuffer::LTimeOfDay(v.time_of_day()))
+        }
+        _ => match (Category::of(&from), Category::of(to)) {
+            (Category::Number | Category::Time, Category::Number | Category::Time) => {
+                let number = Number::of(value).ok_or_else(unsupported)?;
+                number.to_buf(to).ok_or_else(out_of_range)
+            }
+            _ => Err(unsupported()),
+        },
+    }
+}
+
+/// widens the value to the given type (see `ty::widens_to`),
+/// returns `None` if the value is already of that type or would be narrowed
+pub fn widen(value: &DataBuffer, to: &DataKind) -> Option<DataBuffer> {
+    if !ty::widens_to(&value.kind()?, to) {
+        return None;
+    }
+
+    convert(value, to).ok()
+}
+
+/// converts a real into an integer by rounding towards zero (`TRUNC`),
+/// other values are converted like `convert`
+pub fn trunc(value: &DataBuffer, to: &DataKind) -> Result<DataBuffer, ConversionError> {
+    match value {
+        DataBuffer::Real(v) => convert(&DataBuffer::LReal(f64::from(*v).trunc()), to),
+        DataBuffer::LReal(v) => convert(&DataBuffer::LReal(v.trunc()), to),
+        _ => convert(value, to),
+    }
+    .map_err(|e| match e {
+        ConversionError::OutOfRange { to, .. } => ConversionError::OutOfRange {
+            value: value.clone(),
+            to,
+        },
+        e => e,
+    })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Category {
+    /// integers, reals, bit strings and `BOOL`
+    Number,
+    Time,
+    Date,
+    TimeOfDay,
+    DateTime,
+    String,
+    /// `ANY_*`, only ports are generic, values never are
+    Generic,
+    /// arrays are not converted as a whole
+    Array,
+}
+
+impl Category {
+    fn of(kind: &DataKind) -> Self {
+        match kind {
+            DataKind::Time | DataKind::LTime => Category::Time,
+            DataKind::Date | DataKind::LDate => Category::Date,
+            DataKind::TimeOfDay | DataKind::LTimeOfDay => Category::TimeOfDay,
+            DataKind::DateTime | DataKind::LDateTime => Category::DateTime,
+            DataKind::String | DataKind::WString => Category::String,
+            DataKind::Generic(_) => Category::Generic,
+            DataKind::Array(_) => Category::Array,
+            _ => Category::Number,
+        }
+    }
+}
+
+/// intermediate value of a numeric conversion
+enum Number {
+    Int(i128),
+    Real(f64),
+    /// bit strings keep their rightmost bits when narrowed
+    Bits(u64),
+}
+
+impl Number {
+    fn of(value: &DataBuffer) -> Option<Self> {
+        let number = match value {
+            DataBuffer::SInt(v) => Number::Int((*v).into()),
+            DataBuffer::Int(v) => Number::Int((*v).into()),
+            DataBuffer::DInt(v) => Number::Int((*v).into()),
+            DataBuffer::LInt(v) => Number::Int((*v).into()),
+            DataBuffer::USInt(v) => Number::Int((*v).into()),
+            DataBuffer::UInt(v) => Number::Int((*v).into()),
+            DataBuffer::UDInt(v) => Number::Int((*v).into()),
+            DataBuffer::ULInt(v) => Number::Int((*v).into()),
+            DataBuffer::Real(v) => Number::Real((*v).into()),
+            DataBuffer::LReal(v) => Number::Real(*v),
+            DataBuffer::Bool(v) => Number::Int((*v).into()),
+            DataBuffer::Byte(v) => Number::Bits((*v).into()),
+            DataBuffer::Word(v) => Number::Bits((*v).into()),
+            DataBuffer::DWord(v) => Number::Bits((*v).into()),
+            DataBuffer::LWord(v) => Number::Bits(*v),
+            DataBuffer::Time(v) => Number::Int(v.as_millis().try_into().ok()?),
+            DataBuffer::LTime(v) => Number::Int(v.as_nanos().try_into().ok()?),
+            _ => return None,
+        };
+
+        Some(number)
+    }
+
+    /// integer value, reals are rounded
+    fn int(&self) -> Option<i128> {
+        match self {
+            Number::Int(v) => Some(*v),
+            Number::Bits(v) => Some((*v).into()),
+            // the range check of `f64 as i128` saturates, so only values well within i128 are accepted
+            Number::Real(v) if v.is_finite() && v.abs() < 1e38 => Some(v.round() as i128),
+            Number::Real(_) => None,
+        }
+    }
+
+    /// returns `None` if the value does not fit into the target type
+    fn to_buf(&self, to: &DataKind) -> Option<DataBuffer> {
+        let buf = match to {
+            DataKind::SInt => DataBuffer::SInt(self.int()?.try_into().ok()?),
+            DataKind::Int => DataBuffer::Int(self.int()?.try_into().ok()?),
+            DataKind::DInt => DataBuffer::DInt(self.int()?.try_into().ok()?),
+            DataKind::LInt => DataBuffer::LInt(self.int()?.try_into().ok()?),
+            DataKind::USInt => DataBuffer::USInt(self.int()?.try_into().ok()?),
+            DataKind::UInt => DataBuffer::UInt(self.int()?.try_into().ok()?),
+            DataKind::UDInt => DataBuffer::UDInt(self.int()?.try_into().ok()?),
+            DataKind::ULInt => DataBuffer::ULInt(self.int()?.try_into().ok()?),
+            DataKind::Real => {
+                let v = self.real() as f32;
+                // finite values beyond the range of REAL become infinite
+                if v.is_infinite() && self.real().is_finite() {
+                    return None;
+                }
+                DataBuffer::Real(v)
+            }
+            DataKind::LReal => DataBuffer::LReal(self.real()),
+            DataKind::Bool => DataBuffer::Bool(match self {
+                Number::Real(v) => *v != 0.0,
+                _ => self.int()? != 0,
+            }),
+            DataKind::Byte => DataBuffer::Byte(self.bits(u8::MAX.into())? as u8),
+            DataKind::Word => DataBuffer::Word(self.bits(u16::MAX.into())? as u16),
+            DataKind::DWord => DataBuffer::DWord(self.bits(u32::MAX.into())? as u32),
+            DataKind::LWord => DataBuffer::LWord(self.bits(u64::MAX)?),
+            DataKind::Time => DataBuffer::Time(Duration::from_millis(self.int()?.try_into().ok()?)),
+            DataKind::LTime => {
+                DataBuffer::LTime(Duration::from_nanos(self.int()?.try_into().ok()?))
+            }
+            _ => return None,
+        };
+
+        Some(buf)
+    }
+
+    fn real(&self) -> f64 {
+        match self {
+            Number::Int(v) => *v as f64,
+            Number::Real(v) => *v,
+            Number::Bits(v) => *v as f64,
+        }
+    }
+
+    /// bit pattern of the value within `mask`, bit strings are cut to the rightmost bits,
+    /// other values have to fit
+    fn bits(&self, mask: u64) -> Option<u64> {
+        match self {
+            Number::Bits(v) => Some(v & mask),
+            _ => u64::try_from(self.int()?).ok().filter(|v| *v <= mask),
+        }
+    }
+}
+
+/// text of the value inside of a `STRING`, numbers are written without type prefix
+fn to_text(value: &DataBuffer) -> String {
+    match value {
+        DataBuffer::SInt(v) => v.to_string(),
+        DataBuffer::Int(v) => v.to_string(),
+        DataBuffer::DInt(v) => v.to_string(),
+        DataBuffer::LInt(v) => v.to_string(),
+        DataBuffer::USInt(v) => v.to_string(),
+        DataBuffer::UInt(v) => v.to_string(),
+        DataBuffer::UDInt(v) => v.to_string(),
+        DataBuffer::ULInt(v) => v.to_string(),
+        DataBuffer::Real(v) => format!("{v:?}"),
+        DataBuffer::LReal(v) => format!("{v:?}"),
+        DataBuffer::Byte(v) => format!("16#{v:02X}"),
+        DataBuffer::Word(v) => format!("16#{v:04X}"),
+        DataBuffer::DWord(v) => format!("16#{v:08X}"),
+        DataBuffer::LWord(v) => format!("16#{v:016X}"),
+        DataBuffer::String(v) => String::from_utf8_lossy(v).into_owned(),
+        DataBuffer::WString(v) => String::from_utf16_lossy(v),
+        // TRUE/FALSE, durations, dates and times of day keep their literal form
+        _ => value.to_string(),
+    }
+}
+
+fn from_text(text: &str, to: &DataKind) -> Result<DataBuffer, ConversionError> {
+    DataBuffer::parse_literal(to, text).ok_or_else(|| ConversionError::InvalidLiteral {
+        text: text.to_string(),
+        to: to.clone(),
+    })
+}
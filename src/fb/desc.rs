@@ -0,0 +1,250 @@
+//! In-memory description of function block types as defined in `IEC 61499-2` type files.
+//!
+//! Names are kept exactly as declared. Since identifiers in `IEC 61499` are case-insensitive,
+//! comparisons against `Bfb` ports (which use lowercase names) ignore the case.
+
+use crate::fb::{Bfb, data::ty::DataKind, ecc::EccDesc, error::FbError};
+
+#[derive(Clone, Debug)]
+pub struct FbTypeDesc {
+    pub name: String,
+    pub comment: String,
+    pub interface: InterfaceDesc,
+    pub body: FbBodyDesc,
+}
+
+/// the static interface of a function block type
+#[derive(Clone, Debug, Default)]
+pub struct InterfaceDesc {
+    pub event_inputs: Vec<EventDesc>,
+    pub event_outputs: Vec<EventDesc>,
+    pub input_vars: Vec<VarDesc>,
+    pub output_vars: Vec<VarDesc>,
+}
+
+impl InterfaceDesc {
+    pub fn event_input(&self, name: &str) -> Option<&EventDesc> {
+        self.event_inputs
+            .iter()
+            .find(|e| e.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn event_output(&self, name: &str) -> Option<&EventDesc> {
+        self.event_outputs
+            .iter()
+            .find(|e| e.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn input_var(&self, name: &str) -> Option<&VarDesc> {
+        self.input_vars
+            .iter()
+            .find(|v| v.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn output_var(&self, name: &str) -> Option<&VarDesc> {
+        self.output_vars
+            .iter()
+            .find(|v| v.name.eq_ignore_ascii_case(name))
+    }
+}
+
+/// the interface of a function block type as reported by `Bfb::interface`
+#[derive(Clone, Debug, Default)]
+pub struct TypeInterface {
+    pub type_name: String,
+    pub comment: String,
+    pub interface: InterfaceDesc,
+}
+
+/// renders the interface in the textual syntax of `IEC 61499-1` (without the body of the type)
+impl std::fmt::Display for TypeInterface {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let comment = |text: &str| {
+            if text.is_empty() {
+                String::new()
+            } else {
+                format!(" (* {text} *)")
+            }
+        };
+
+        writeln!(
+            f,
+            "FUNCTION_BLOCK {}{}",
+            self.type_name,
+            comment(&self.comment)
+        )?;
+
+        for (section, events) in [
+            ("EVENT_INPUT", &self.interface.event_inputs),
+            ("EVENT_OUTPUT", &self.interface.event_outputs),
+        ] {
+            if events.is_empty() {
+                continue;
+            }
+
+            writeln!(f, "{section}")?;
+
+            for event in events {
+                let with = if event.with.is_empty() {
+                    String::new()
+                } else {
+                    format!(" WITH {}", event.with.join(", "))
+                };
+
+                writeln!(f, "    {}{with};{}", event.name, comment(&event.comment))?;
+            }
+
+            writeln!(f, "END_EVENT")?;
+        }
+
+        for (section, vars) in [
+            ("VAR_INPUT", &self.interface.input_vars),
+            ("VAR_OUTPUT", &self.interface.output_vars),
+        ] {
+            if vars.is_empty() {
+                continue;
+            }
+
+            writeln!(f, "{section}")?;
+
+            for var in vars {
+                let initial_value = match &var.initial_value {
+                    Some(value) => format!(" := {value}"),
+                    None => String::new(),
+                };
+
+                writeln!(
+                    f,
+                    "    {} : {}{initial_value};{}",
+                    var.name,
+                    var.kind.iec_name(),
+                    comment(&var.comment)
+                )?;
+            }
+
+            writeln!(f, "END_VAR")?;
+        }
+
+        write!(f, "END_FUNCTION_BLOCK")
+    }
+}
+
+/// an event in- or output including the data associated WITH it
+#[derive(Clone, Debug)]
+pub struct EventDesc {
+    pub name: String,
+    pub with: Vec<String>,
+    pub comment: String,
+}
+
+impl EventDesc {
+    pub fn new(name: &str, with: &[&str]) -> Self {
+        Self {
+            name: name.to_string(),
+            with: with.iter().map(|w| w.to_string()).collect(),
+            comment: String::new(),
+        }
+    }
+}
+
+/// a data in- or output, or an internal variable
+#[derive(Clone, Debug)]
+pub struct VarDesc {
+    pub name: String,
+    pub kind: DataKind,
+    /// initial value in `IEC 61131-3` literal notation
+    pub initial_value: Option<String>,
+    pub comment: String,
+}
+
+impl VarDesc {
+    pub fn new(name: &str, kind: DataKind) -> Self {
+        Self {
+            name: name.to_string(),
+            kind,
+            initial_value: None,
+            comment: String::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum FbBodyDesc {
+    Basic(BasicFbDesc),
+    /// function block types without a basic body (e.g. composite or service interface types)
+    Other(String),
+}
+
+/// the internals of a basic function block type
+#[derive(Clone, Debug, Default)]
+pub struct BasicFbDesc {
+    pub internal_vars: Vec<VarDesc>,
+    pub ecc: EccDesc,
+    pub algorithms: Vec<AlgorithmDesc>,
+}
+
+#[derive(Clone, Debug)]
+pub struct AlgorithmDesc {
+    pub name: String,
+    pub comment: String,
+    /// language of the algorithm body, e.g. `ST`
+    pub language: String,
+    pub text: String,
+}
+
+/// checks the ports of a function block instance against a type description,
+/// returns a description of every deviation
+pub fn check_conformance(desc: &InterfaceDesc, fb: &dyn Bfb) -> Vec<String> {
+    let mut deviations = vec![];
+
+    for event in desc.event_inputs.iter().chain(&desc.event_outputs) {
+        let name = event.name.to_lowercase();
+
+        match fb.with_for_event(&name) {
+            Ok(with) => {
+                let mut expected: Vec<_> = event.with.iter().map(|w| w.to_lowercase()).collect();
+                let mut actual: Vec<_> = with.iter().map(|w| w.to_lowercase()).collect();
+
+                expected.sort();
+                actual.sort();
+
+                if expected != actual {
+                    deviations.push(format!(
+                        "event \"{name}\" is WITH {actual:?}, but the type declares {expected:?}"
+                    ));
+                }
+            }
+            Err(e) => deviations.push(e.to_string()),
+        }
+    }
+
+    for (var, is_output) in desc
+        .input_vars
+        .iter()
+        .map(|v| (v, false))
+        .chain(desc.output_vars.iter().map(|v| (v, true)))
+    {
+        let name = var.name.to_lowercase();
+
+        match fb.data_kind(&name) {
+            Ok(kind) if kind != var.kind => deviations.push(format!(
+                "data \"{name}\" is {}, but the type declares {}",
+                kind.iec_name(),
+                var.kind.iec_name()
+            )),
+            Ok(_) => {}
+            Err(e) => {
+                deviations.push(e.to_string());
+                continue;
+            }
+        }
+
+        // outputs can be read, inputs are rejected as the wrong direction
+        match (fb.read_data_out(&name), is_output) {
+            (Ok(_), true) | (Err(FbError::WrongDirection(_)), false) => {}
+            _ => deviations.push(format!("data \"{name}\" has the wrong direction")),
+        }
+    }
+
+    deviations
+}
@@ -0,0 +1,416 @@
+//! Declarative execution control charts (ECC) of basic function blocks.
+//!
+//! A chart is described as data (states with EC actions and prioritized transitions)
+//! and is executed generically by `invoke`, instead of hand-written `match` blocks.
+//!
+//! ### Execution semantics
+//! - the first declared state is the initial state
+//! - transitions leaving the current state are evaluated in declaration order (priority),
+//!   the first enabled transition fires
+//! - a transition is enabled if its event (if any) is the active input event and its guard (if any) holds
+//! - entering a state executes its EC actions in order (algorithm, then output event)
+//! - the active input event is evaluated in a single step only,
+//!   it is cleared after that step whether a transition fires or not
+//! - an input event received while the chart is unstable waits until the transitions
+//!   without event have brought the chart into a stable state
+//! - a failing output event aborts the step with an error
+
+use std::{fmt::Display, sync::Arc};
+
+use crate::fb::{Bfb, error::FbError};
+
+const DEBUG: bool = true;
+
+/// guard condition of a transition, evaluated on the function block
+pub type Guard<F> = Arc<dyn Fn(&F) -> bool + Send + Sync>;
+
+/// algorithm of an EC action, executed on the function block
+pub type Algorithm<F> = Arc<dyn Fn(&mut F) + Send + Sync>;
+
+/// enables a struct to be driven by an `Ecc`
+pub trait EccHost: Bfb + Sized {
+    fn execution_control(&self) -> &ExecutionControl<Self>;
+
+    fn execution_control_mut(&mut self) -> &mut ExecutionControl<Self>;
+
+    /// resets all input events
+    fn clear_event_in(&mut self);
+
+    /// sets the given output event to active
+    fn send_event_out(&mut self, event: &str) -> Result<(), FbError>;
+}
+
+/// executes a single step of the execution control of the host,
+/// returns a flag whether a transition fired (the state is unstable)
+pub fn invoke<F: EccHost>(host: &mut F) -> Result<bool, FbError> {
+    let chart = Arc::clone(&host.execution_control().chart);
+    let current = host.execution_control().state;
+
+    if chart.states.is_empty() {
+        return Ok(false);
+    }
+
+    let active = host.active_event_in();
+    let enabled = |active| {
+        chart
+            .transitions
+            .iter()
+            .filter(|t| t.source == current)
+            .find(|t| t.condition.enabled(host, active))
+    };
+
+    // the previous steps are completed before the input event is evaluated
+    let pending = host.execution_control().unstable && active.is_some();
+
+    let fired = match pending.then(|| enabled(None)).flatten() {
+        Some(transition) => Some(transition),
+        None => {
+            let fired = enabled(active);
+
+            // the input event was evaluated, whether it triggered a transition or not
+            if active.is_some() {
+                host.clear_event_in();
+            }
+
+            fired
+        }
+    };
+
+    host.execution_control_mut().unstable = fired.is_some();
+
+    let Some(transition) = fired else {
+        return Ok(false);
+    };
+
+    let destination = &chart.states[transition.destination];
+
+    if DEBUG {
+        println!(
+            "{}: {} -> {}",
+            host.instance_name(),
+            chart.states[current].name,
+            destination.name
+        );
+    }
+
+    host.execution_control_mut().state = transition.destination;
+
+    for action in &destination.actions {
+        if let Some((_, algorithm)) = &action.algorithm {
+            algorithm(host);
+        }
+
+        if let Some(event) = &action.output {
+            host.send_event_out(event)?;
+        }
+    }
+
+    Ok(true)
+}
+
+/// current state of a function block within its (shared) chart
+pub struct ExecutionControl<F> {
+    chart: Arc<Ecc<F>>,
+    state: usize,
+    /// whether the last step fired a transition
+    unstable: bool,
+}
+
+impl<F> ExecutionControl<F> {
+    pub fn new(chart: Arc<Ecc<F>>) -> Self {
+        Self {
+            chart,
+            state: 0,
+            unstable: false,
+        }
+    }
+
+    pub fn chart(&self) -> &Ecc<F> {
+        &self.chart
+    }
+
+    /// returns the name of the current EC state
+    pub fn state(&self) -> &str {
+        self.chart
+            .states
+            .get(self.state)
+            .map(|s| s.name.as_str())
+            .unwrap_or("NONE")
+    }
+}
+
+/// an execution control without any states, which never becomes unstable
+impl<F> Default for ExecutionControl<F> {
+    fn default() -> Self {
+        Self::new(Arc::new(Ecc {
+            states: vec![],
+            transitions: vec![],
+        }))
+    }
+}
+
+impl<F> std::fmt::Debug for ExecutionControl<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.state())
+    }
+}
+
+/// an executable execution control chart
+pub struct Ecc<F> {
+    states: Vec<EcState<F>>,
+    transitions: Vec<EcTransition<F>>,
+}
+
+impl<F> Ecc<F> {
+    pub fn builder() -> EccBuilder<F> {
+        EccBuilder {
+            states: vec![],
+            transitions: vec![],
+        }
+    }
+
+    /// returns the chart as plain data
+    pub fn describe(&self) -> EccDesc {
+        EccDesc {
+            states: self
+                .states
+                .iter()
+                .map(|s| EcStateDesc {
+                    name: s.name.clone(),
+                    actions: s
+                        .actions
+                        .iter()
+                        .map(|a| EcActionDesc {
+                            algorithm: a.algorithm.as_ref().map(|(name, _)| name.clone()),
+                            output: a.output.clone(),
+                        })
+                        .collect(),
+                })
+                .collect(),
+            transitions: self
+                .transitions
+                .iter()
+                .map(|t| EcTransitionDesc {
+                    source: self.states[t.source].name.clone(),
+                    destination: self.states[t.destination].name.clone(),
+                    event: t.condition.event.clone(),
+                    guard: t.condition.guard.as_ref().map(|(text, _)| text.clone()),
+                })
+                .collect(),
+        }
+    }
+}
+
+struct EcState<F> {
+    name: String,
+    actions: Vec<EcAction<F>>,
+}
+
+struct EcTransition<F> {
+    source: usize,
+    destination: usize,
+    condition: Condition<F>,
+}
+
+/// an EC action consisting of an optional algorithm and an optional output event
+pub struct EcAction<F> {
+    algorithm: Option<(String, Algorithm<F>)>,
+    output: Option<String>,
+}
+
+impl<F> EcAction<F> {
+    pub fn algorithm(name: &str, algorithm: impl Fn(&mut F) + Send + Sync + 'static) -> Self {
+        Self {
+            algorithm: Some((name.to_string(), Arc::new(algorithm))),
+            output: None,
+        }
+    }
+
+    pub fn output(event: &str) -> Self {
+        Self {
+            algorithm: None,
+            output: Some(event.to_string()),
+        }
+    }
+
+    /// sends the given output event after the algorithm has been executed
+    pub fn then_output(mut self, event: &str) -> Self {
+        self.output = Some(event.to_string());
+        self
+    }
+}
+
+/// transition condition consisting of an optional event and an optional guard
+pub struct Condition<F> {
+    event: Option<String>,
+    guard: Option<(String, Guard<F>)>,
+}
+
+impl<F> Condition<F> {
+    /// condition that is always true (`1`)
+    pub fn always() -> Self {
+        Self {
+            event: None,
+            guard: None,
+        }
+    }
+
+    pub fn event(event: &str) -> Self {
+        Self {
+            event: Some(event.to_string()),
+            guard: None,
+        }
+    }
+
+    /// `text` is the textual representation of the guard used for introspection
+    pub fn guard(text: &str, guard: impl Fn(&F) -> bool + Send + Sync + 'static) -> Self {
+        Self::always().with_guard(text, guard)
+    }
+
+    pub fn with_guard(
+        mut self,
+        text: &str,
+        guard: impl Fn(&F) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.guard = Some((text.to_string(), Arc::new(guard)));
+        self
+    }
+
+    fn enabled(&self, host: &F, active: Option<&str>) -> bool {
+        let event_ok = match &self.event {
+            Some(event) => active == Some(event.as_str()),
+            None => true,
+        };
+
+        event_ok && self.guard.as_ref().is_none_or(|(_, guard)| guard(host))
+    }
+}
+
+pub struct EccBuilder<F> {
+    states: Vec<EcState<F>>,
+    transitions: Vec<(String, String, Condition<F>)>,
+}
+
+impl<F> EccBuilder<F> {
+    /// adds a state, the first added state is the initial state
+    pub fn state(mut self, name: &str, actions: Vec<EcAction<F>>) -> Self {
+        self.states.push(EcState {
+            name: name.to_string(),
+            actions,
+        });
+        self
+    }
+
+    /// adds a transition, transitions are prioritized in the order they are added
+    pub fn transition(mut self, source: &str, destination: &str, condition: Condition<F>) -> Self {
+        self.transitions
+            .push((source.to_string(), destination.to_string(), condition));
+        self
+    }
+
+    pub fn build(self) -> Result<Ecc<F>, EccError> {
+        let EccBuilder {
+            states,
+            transitions: named,
+        } = self;
+
+        let index = |name: &str| {
+            states
+                .iter()
+                .position(|s| s.name == name)
+                .ok_or_else(|| EccError::UnknownState(name.to_string()))
+        };
+
+        let mut transitions = Vec::with_capacity(named.len());
+
+        for (source, destination, condition) in named {
+            transitions.push(EcTransition {
+                source: index(&source)?,
+                destination: index(&destination)?,
+                condition,
+            });
+        }
+
+        Ok(Ecc {
+            states,
+            transitions,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum EccError {
+    /// a transition references a state that does not exist
+    UnknownState(String),
+}
+
+impl Display for EccError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EccError::UnknownState(state) => write!(f, "unknown EC state \"{state}\""),
+        }
+    }
+}
+
+impl std::error::Error for EccError {}
+
+/// plain data description of an execution control chart
+#[derive(Clone, Debug, Default)]
+pub struct EccDesc {
+    pub states: Vec<EcStateDesc>,
+    pub transitions: Vec<EcTransitionDesc>,
+}
+
+#[derive(Clone, Debug)]
+pub struct EcStateDesc {
+    pub name: String,
+    pub actions: Vec<EcActionDesc>,
+}
+
+#[derive(Clone, Debug)]
+pub struct EcActionDesc {
+    pub algorithm: Option<String>,
+    pub output: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct EcTransitionDesc {
+    pub source: String,
+    pub destination: String,
+    pub event: Option<String>,
+    pub guard: Option<String>,
+}
+
+impl EcTransitionDesc {
+    /// returns the condition in the notation of the standard, e.g. `cu[CV < 65535]`
+    pub fn condition(&self) -> String {
+        match (&self.event, &self.guard) {
+            (None, None) => "1".to_string(),
+            (Some(event), None) => event.clone(),
+            (None, Some(guard)) => guard.clone(),
+            (Some(event), Some(guard)) => format!("{event}[{guard}]"),
+        }
+    }
+}
+
+impl Display for EccDesc {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for state in &self.states {
+            write!(f, "{}", state.name)?;
+
+            for action in &state.actions {
+                let algorithm = action.algorithm.as_deref().unwrap_or("-");
+                let output = action.output.as_deref().unwrap_or("-");
+                write!(f, " [{algorithm} / {output}]")?;
+            }
+
+            writeln!(f)?;
+        }
+
+        for t in &self.transitions {
+            writeln!(f, "{} --{}--> {}", t.source, t.condition(), t.destination)?;
+        }
+
+        Ok(())
+    }
+}
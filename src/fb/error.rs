@@ -0,0 +1,193 @@
+//! errors returned by function blocks when they are accessed through the `Bfb` trait
+
+use std::fmt::Display;
+
+use super::data::{comm::DataBuffer, ty::DataKind};
+
+#[derive(Clone, Debug)]
+pub enum FbError {
+    /// the function block has no event with the given name
+    UnknownEvent(String),
+    /// the function block has no data in- or output with the given name
+    UnknownData(String),
+    /// the variant of a `DataBuffer` does not match the `DataKind` of the data field
+    TypeMismatch {
+        data: String,
+        expected: DataKind,
+        found: DataBuffer,
+    },
+    /// the index of an array element is outside of the bounds of the array
+    IndexOutOfBounds {
+        data: String,
+        index: Vec<i64>,
+        kind: DataKind,
+    },
+    /// the port exists, but was accessed in the wrong direction (e.g. writing to a data output)
+    WrongDirection(String),
+    /// the kind of a data output is neither equal to nor widened to the kind of the connected data input
+    KindMismatch {
+        source: String,
+        destination: String,
+        source_kind: DataKind,
+        destination_kind: DataKind,
+    },
+    /// an input event can not be set, since another input event is still pending
+    EventAlreadyPending { event: String, pending: String },
+    /// an input event can not be queued, since the input event queue of the function block is full
+    EventQueueFull { event: String, capacity: usize },
+    /// the function block is still busy after the step limit,
+    /// e.g. its execution control keeps firing transitions or its internal events keep being delivered
+    NotStable { fb_name: String, steps: usize },
+    /// no function block with the given instance name exists
+    UnknownInstance(String),
+    /// no function block type with the given name is registered
+    UnknownType(String),
+    /// the function block has no plug or socket with the given name
+    UnknownAdapter(String),
+    /// the plug or socket does not fit the adapter type of the connection
+    AdapterMismatch { adapter: String, type_name: String },
+}
+
+impl FbError {
+    pub fn unknown_event(event: &str) -> Self {
+        Self::UnknownEvent(event.to_string())
+    }
+
+    pub fn unknown_data(data: &str) -> Self {
+        Self::UnknownData(data.to_string())
+    }
+
+    pub fn type_mismatch(data: &str, expected: DataKind, found: &DataBuffer) -> Self {
+        Self::TypeMismatch {
+            data: data.to_string(),
+            expected,
+            found: found.clone(),
+        }
+    }
+
+    pub fn index_out_of_bounds(data: &str, index: &[i64], kind: DataKind) -> Self {
+        Self::IndexOutOfBounds {
+            data: data.to_string(),
+            index: index.to_vec(),
+            kind,
+        }
+    }
+
+    pub fn wrong_direction(port: &str) -> Self {
+        Self::WrongDirection(port.to_string())
+    }
+
+    pub fn kind_mismatch(
+        source: &str,
+        destination: &str,
+        source_kind: DataKind,
+        destination_kind: DataKind,
+    ) -> Self {
+        Self::KindMismatch {
+            source: source.to_string(),
+            destination: destination.to_string(),
+            source_kind,
+            destination_kind,
+        }
+    }
+
+    pub fn event_already_pending(event: &str, pending: &str) -> Self {
+        Self::EventAlreadyPending {
+            event: event.to_string(),
+            pending: pending.to_string(),
+        }
+    }
+
+    pub fn event_queue_full(event: &str, capacity: usize) -> Self {
+        Self::EventQueueFull {
+            event: event.to_string(),
+            capacity,
+        }
+    }
+
+    pub fn not_stable(fb_name: &str, steps: usize) -> Self {
+        Self::NotStable {
+            fb_name: fb_name.to_string(),
+            steps,
+        }
+    }
+
+    pub fn unknown_instance(instance_name: &str) -> Self {
+        Self::UnknownInstance(instance_name.to_string())
+    }
+
+    pub fn unknown_type(type_name: &str) -> Self {
+        Self::UnknownType(type_name.to_string())
+    }
+
+    pub fn unknown_adapter(adapter: &str) -> Self {
+        Self::UnknownAdapter(adapter.to_string())
+    }
+
+    pub fn adapter_mismatch(adapter: &str, type_name: &str) -> Self {
+        Self::AdapterMismatch {
+            adapter: adapter.to_string(),
+            type_name: type_name.to_string(),
+        }
+    }
+}
+
+impl Display for FbError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FbError::UnknownEvent(event) => write!(f, "unknown event \"{event}\""),
+            FbError::UnknownData(data) => write!(f, "unknown data \"{data}\""),
+            FbError::TypeMismatch {
+                data,
+                expected,
+                found,
+            } => write!(
+                f,
+                "data \"{data}\" expects {}, but received {found}",
+                expected.iec_name()
+            ),
+            FbError::IndexOutOfBounds { data, index, kind } => write!(
+                f,
+                "index {index:?} of data \"{data}\" is out of the bounds of {}",
+                kind.iec_name()
+            ),
+            FbError::WrongDirection(port) => {
+                write!(f, "port \"{port}\" was accessed in the wrong direction")
+            }
+            FbError::KindMismatch {
+                source,
+                destination,
+                source_kind,
+                destination_kind,
+            } => write!(
+                f,
+                "data \"{source}\" ({}) can not be connected to \"{destination}\" ({})",
+                source_kind.iec_name(),
+                destination_kind.iec_name()
+            ),
+            FbError::EventAlreadyPending { event, pending } => write!(
+                f,
+                "event \"{event}\" can not be set, since \"{pending}\" is still pending"
+            ),
+            FbError::EventQueueFull { event, capacity } => write!(
+                f,
+                "event \"{event}\" can not be queued, since the queue is full (capacity {capacity})"
+            ),
+            FbError::NotStable { fb_name, steps } => write!(
+                f,
+                "function block \"{fb_name}\" not stable after {steps} steps"
+            ),
+            FbError::UnknownInstance(instance_name) => {
+                write!(f, "no fb with name=\"{instance_name}\" exists")
+            }
+            FbError::UnknownType(type_name) => write!(f, "unknown type \"{type_name}\""),
+            FbError::UnknownAdapter(adapter) => write!(f, "unknown plug or socket \"{adapter}\""),
+            FbError::AdapterMismatch { adapter, type_name } => write!(
+                f,
+                "plug or socket \"{adapter}\" does not fit the adapter type \"{type_name}\""
+            ),
+        }
+    }
+}
+
+impl std::error::Error for FbError {}
@@ -1,50 +1,160 @@
 use std::{
     any::Any,
     fmt::{Debug, Display},
+    sync::Arc,
+    time::Duration,
 };
 
 use data::comm::DataBuffer;
 
-use crate::fb::data::ty::DataKind;
+use crate::{
+    fb::{
+        adapter::AdapterDecl,
+        data::ty::DataKind,
+        desc::{EventDesc, InterfaceDesc, TypeInterface, VarDesc},
+        ecc::EccDesc,
+        error::FbError,
+    },
+    run_time::clock::Clock,
+};
 
+pub mod adapter;
 pub mod data;
+pub mod desc;
 pub mod direction;
+pub mod ecc;
+pub mod error;
 pub mod event;
+pub mod registry;
+
+/// derives the `Bfb` trait from `Event`/`Data` fields, see `iec_61499_fb_derive`
+pub use iec_61499_fb_derive::Bfb;
 
-/// trait to enable structs to be handled as basic function blocks by the run time
-pub trait Bfb: Any + Debug + Display {
+/// trait to enable structs to be handled as basic function blocks by the run time,
+/// function blocks are `Send`, so every resource of a `Device` can run on its own thread
+pub trait Bfb: Any + Debug + Display + Send {
     /// enables dynamic dispatch downcasting (e.g. dyn Fb -> Voter)
     fn as_any(&self) -> &dyn Any;
 
     /// returns the instance name of the function block
-    fn instance_name(&self) -> &'static str;
+    fn instance_name(&self) -> &str;
+
+    /// returns the name of the function block type
+    fn type_name(&self) -> &str;
+
+    /// returns the names of the event inputs
+    fn event_inputs(&self) -> Vec<&str>;
+
+    /// returns the names of the event outputs
+    fn event_outputs(&self) -> Vec<&str>;
+
+    /// returns the names of the data inputs
+    fn data_inputs(&self) -> Vec<&str>;
+
+    /// returns the names of the data outputs
+    fn data_outputs(&self) -> Vec<&str>;
+
+    /// returns the full interface of the function block type,
+    /// by default assembled from the port names, `with_for_event` and `data_kind` (without comments or initial values)
+    fn interface(&self) -> TypeInterface {
+        let events = |names: Vec<&str>| {
+            names
+                .into_iter()
+                .map(|name| EventDesc::new(name, &self.with_for_event(name).unwrap_or_default()))
+                .collect()
+        };
+
+        let vars = |names: Vec<&str>| {
+            names
+                .into_iter()
+                .filter_map(|name| Some(VarDesc::new(name, self.data_kind(name).ok()?)))
+                .collect()
+        };
+
+        TypeInterface {
+            type_name: self.type_name().to_string(),
+            comment: String::new(),
+            interface: InterfaceDesc {
+                event_inputs: events(self.event_inputs()),
+                event_outputs: events(self.event_outputs()),
+                input_vars: vars(self.data_inputs()),
+                output_vars: vars(self.data_outputs()),
+            },
+        }
+    }
 
     /// returns the `DataKind` variant of the queried data field
-    fn data_kind(&self, data: &str) -> DataKind;
+    fn data_kind(&self, data: &str) -> Result<DataKind, FbError>;
 
-    /// set an input event to active
-    fn set_event_in(&mut self, event: &str);
+    /// fixes the concrete kind of a generic (`ANY_*`) data port, called by the runtime when the port is connected,
+    /// fails if the port does not accept the kind (ports of a concrete kind only accept their own kind)
+    fn fix_data_kind(&mut self, data: &str, kind: &DataKind) -> Result<(), FbError> {
+        let declared = self.data_kind(data)?;
+
+        if declared != *kind {
+            return Err(FbError::type_mismatch(
+                data,
+                declared,
+                &DataBuffer::default_for(kind),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// set an input event to active,
+    /// fails if a different input event is still pending
+    fn set_event_in(&mut self, event: &str) -> Result<(), FbError>;
 
     /// returns the currently active in event, if there is any
-    fn active_event_in(&self) -> Option<&'static str>;
+    fn active_event_in(&self) -> Option<&str>;
 
     /// returns the currently active out event, if there is any
-    fn active_event_out(&self) -> Option<&'static str>;
+    fn active_event_out(&self) -> Option<&str>;
+
+    /// returns all currently active out events,
+    /// a single EC state may emit several of them at once
+    fn active_events_out(&self) -> Vec<&str> {
+        self.active_event_out().into_iter().collect()
+    }
 
     /// clears the current active out event
     fn clear_event_out(&mut self);
 
     /// returns the field names of data in- or output
     /// associated WITH the given event
-    fn with_for_event(&self, event: &str) -> Vec<&'static str>;
+    fn with_for_event(&self, event: &str) -> Result<Vec<&str>, FbError>;
 
     /// gets the current value of output data as a buffer value
-    fn read_data_out(&self, data: &str) -> DataBuffer;
+    fn read_data_out(&self, data: &str) -> Result<DataBuffer, FbError>;
 
     /// sets the value of an input data to the value inside given buffer
-    fn write_data_in(&mut self, data: &str, buf: &DataBuffer);
+    fn write_data_in(&mut self, data: &str, buf: &DataBuffer) -> Result<(), FbError>;
 
     /// executes a single step of the function block execution control,
     /// returns a flag whether the state after the step is unstable
-    fn invoke_execution_control(&mut self) -> bool;
+    fn invoke_execution_control(&mut self) -> Result<bool, FbError>;
+
+    /// returns the execution control chart as plain data, if the function block exposes it
+    fn ecc(&self) -> Option<EccDesc> {
+        None
+    }
+
+    /// returns the name of the current EC state, if the function block exposes it
+    fn ec_state(&self) -> Option<&str> {
+        None
+    }
+
+    /// returns the clock time at which a timed function block has to be invoked, if it waits for one
+    fn deadline(&self) -> Option<Duration> {
+        None
+    }
+
+    /// sets the clock of a timed function block, other function blocks ignore it
+    fn set_clock(&mut self, _clock: Arc<dyn Clock>) {}
+
+    /// returns the plugs and sockets of the function block
+    fn adapters(&self) -> Vec<AdapterDecl> {
+        vec![]
+    }
 }
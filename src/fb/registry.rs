@@ -0,0 +1,72 @@
+//! Registry of function block types, which creates instances by type name.
+//!
+//! Type names are case-insensitive like all `IEC 61499` identifiers,
+//! they are reported in the case they were registered with.
+
+use std::collections::HashMap;
+
+use crate::fb::{Bfb, desc::TypeInterface};
+
+/// creates a function block instance with the given instance name
+pub type Factory = Box<dyn Fn(&str) -> Box<dyn Bfb> + Send + Sync>;
+
+#[derive(Default)]
+pub struct TypeRegistry {
+    /// keyed by the uppercase type name, together with the registered type name
+    factories: HashMap<String, (String, Factory)>,
+}
+
+impl TypeRegistry {
+    /// registers a function block type, replaces existing types with the same name
+    pub fn register(
+        &mut self,
+        type_name: &str,
+        factory: impl Fn(&str) -> Box<dyn Bfb> + Send + Sync + 'static,
+    ) {
+        self.factories.insert(
+            type_name.to_ascii_uppercase(),
+            (type_name.to_string(), Box::new(factory)),
+        );
+    }
+
+    /// removes a function block type, returns whether it was registered
+    pub fn unregister(&mut self, type_name: &str) -> bool {
+        self.factories
+            .remove(&type_name.to_ascii_uppercase())
+            .is_some()
+    }
+
+    pub fn contains(&self, type_name: &str) -> bool {
+        self.factories.contains_key(&type_name.to_ascii_uppercase())
+    }
+
+    /// returns the names of all registered types in alphabetical order
+    pub fn type_names(&self) -> Vec<&str> {
+        let mut names: Vec<_> = self
+            .factories
+            .values()
+            .map(|(name, _)| name.as_str())
+            .collect();
+
+        names.sort_by_key(|name| name.to_ascii_uppercase());
+        names
+    }
+
+    /// creates a new instance of the given type, if the type is registered
+    pub fn create(&self, type_name: &str, instance_name: &str) -> Option<Box<dyn Bfb>> {
+        self.factories
+            .get(&type_name.to_ascii_uppercase())
+            .map(|(_, factory)| factory(instance_name))
+    }
+
+    /// returns the interface of the given type, read from a temporary instance
+    pub fn interface(&self, type_name: &str) -> Option<TypeInterface> {
+        self.create(type_name, "probe").map(|fb| fb.interface())
+    }
+}
+
+impl std::fmt::Debug for TypeRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.type_names()).finish()
+    }
+}
@@ -0,0 +1,113 @@
+//! Polymorphic arithmetic function blocks with generic data ports (see `ty::GenericKind`).
+//!
+//! `F_ADD` adds two `ANY_NUM` values, the narrower input is widened to the kind of the other one.
+//! The concrete kinds of the ports are fixed when they are connected (see `Bfb::fix_data_kind`)
+//! or by the first value written to them.
+
+use std::sync::Arc;
+
+use crate::fb::{
+    Bfb,
+    data::{Data, comm::DataBuffer, convert, ty::AnyNum},
+    direction::{In, Out},
+    ecc::{Condition, EcAction, Ecc, ExecutionControl},
+    event::{Event, ty::Signal},
+};
+
+#[allow(non_camel_case_types)]
+#[derive(Default, Debug, Bfb)]
+pub struct F_ADD {
+    instance_name: String,
+    #[ecc]
+    ecc: ExecutionControl<Self>,
+    #[with(in1, in2)]
+    req: Event<In, Signal>,
+    #[with(out)]
+    cnf: Event<Out, Signal>,
+    in1: Data<In, AnyNum>,
+    in2: Data<In, AnyNum>,
+    out: Data<Out, AnyNum>,
+}
+
+impl F_ADD {
+    pub fn new(instance_name: &str) -> Self {
+        Self {
+            instance_name: instance_name.to_string(),
+            ecc: ExecutionControl::new(Arc::new(Self::chart())),
+            ..Default::default()
+        }
+    }
+
+    fn chart() -> Ecc<Self> {
+        Ecc::builder()
+            .state("START", vec![])
+            .state(
+                "REQ",
+                vec![EcAction::algorithm("REQ", Self::req_algorithm).then_output("cnf")],
+            )
+            .transition("START", "REQ", Condition::event("req"))
+            .transition("REQ", "START", Condition::always())
+            .build()
+            .expect("F_ADD chart is valid")
+    }
+
+    fn req_algorithm(&mut self) {
+        let (in1, in2) = (self.in1.read(), self.in2.read());
+
+        let Some(sum) = add(&in1, &in2) else {
+            println!(
+                "[error F_ADD]: {}: cannot add {in1} and {in2}",
+                self.instance_name
+            );
+            return;
+        };
+
+        let sum = convert::widen(&sum, &self.out.as_kind()).unwrap_or(sum);
+
+        if let Err(e) = self.out.set_buf("out", &sum) {
+            println!("[error F_ADD]: {}: {e}", self.instance_name);
+        }
+    }
+}
+
+/// adds two numbers, the narrower one is widened to the kind of the other one,
+/// returns `None` on overflow or if the kinds have no common kind
+fn add(a: &DataBuffer, b: &DataBuffer) -> Option<DataBuffer> {
+    let (a, b) = match (a.kind()?, b.kind()?) {
+        (x, y) if x == y => (a.clone(), b.clone()),
+        (_, y) if convert::widen(a, &y).is_some() => (convert::widen(a, &y)?, b.clone()),
+        (x, _) => (a.clone(), convert::widen(b, &x)?),
+    };
+
+    let sum = match (a, b) {
+        (DataBuffer::SInt(a), DataBuffer::SInt(b)) => DataBuffer::SInt(a.checked_add(b)?),
+        (DataBuffer::Int(a), DataBuffer::Int(b)) => DataBuffer::Int(a.checked_add(b)?),
+        (DataBuffer::DInt(a), DataBuffer::DInt(b)) => DataBuffer::DInt(a.checked_add(b)?),
+        (DataBuffer::LInt(a), DataBuffer::LInt(b)) => DataBuffer::LInt(a.checked_add(b)?),
+        (DataBuffer::USInt(a), DataBuffer::USInt(b)) => DataBuffer::USInt(a.checked_add(b)?),
+        (DataBuffer::UInt(a), DataBuffer::UInt(b)) => DataBuffer::UInt(a.checked_add(b)?),
+        (DataBuffer::UDInt(a), DataBuffer::UDInt(b)) => DataBuffer::UDInt(a.checked_add(b)?),
+        (DataBuffer::ULInt(a), DataBuffer::ULInt(b)) => DataBuffer::ULInt(a.checked_add(b)?),
+        (DataBuffer::Real(a), DataBuffer::Real(b)) => DataBuffer::Real(a + b),
+        (DataBuffer::LReal(a), DataBuffer::LReal(b)) => DataBuffer::LReal(a + b),
+        _ => return None,
+    };
+
+    Some(sum)
+}
+
+impl std::fmt::Display for F_ADD {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}={{req={}, cnf={}, in1={}, in2={}, out={}, state={}}}",
+            self.instance_name,
+            self.req.read(),
+            self.cnf.read(),
+            self.in1.as_buf(),
+            self.in2.as_buf(),
+            self.out.as_buf(),
+            self.ecc.state(),
+        )
+    }
+}
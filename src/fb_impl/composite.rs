@@ -0,0 +1,628 @@
+//! Composite function blocks, which encapsulate a network of other function blocks.
+//!
+//! A `Cfb` owns an `IdConnRuntime` with its component function blocks and their connections.
+//! The ports of its interface are bound to ports of the components:
+//! - an interface input event is delivered to every bound component input event,
+//!   the resulting event chains run to completion inside of the composite (see `run_time::scheduler`)
+//! - component output events bound to interface output events are emitted by the composite,
+//!   every emission is forwarded on its own, one per invocation of the execution control
+//! - interface data inputs are latched for the bound component inputs,
+//!   which sample them WITH their own input events like any other data connection
+//! - while an emission is active, interface data outputs carry the values of the bound component outputs
+//!   at the time of the emission, otherwise they read the component outputs
+//! - bound ports need assignable kinds, generic component ports are fixed to the kind of the interface
+//!
+//! Since the composite implements `Bfb`, it is added to a runtime (or another composite) like a basic block.
+//!
+//! ```text
+//! let mut cfb = Cfb::new("counter0", "COUNTER", interface);
+//! cfb.add_fb(E_CTU::new("ctu"));
+//! cfb.bind_event_in("cu", ("ctu", "cu"))?;
+//! cfb.bind_data_in("pv", ("ctu", "pv"))?;
+//! cfb.bind_event_out(("ctu", "cuo"), "cuo")?;
+//! cfb.bind_data_out(("ctu", "cv"), "cv")?;
+//! rt.add_fb(cfb);
+//! ```
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Display,
+    sync::Arc,
+    time::Duration,
+};
+
+use crate::{
+    fb::{
+        Bfb,
+        data::{
+            comm::DataBuffer,
+            convert,
+            ty::{self, DataKind},
+        },
+        desc::{EventDesc, InterfaceDesc, TypeInterface, VarDesc},
+        error::FbError,
+        registry::TypeRegistry,
+    },
+    run_time::{
+        clock::{Clock, RealClock},
+        id_conn::IdConnRuntime,
+        scheduler::{Emission, Scheduler},
+    },
+};
+
+/// upper bound of deliveries inside of the composite for a single input event
+const MAX_DELIVERIES: usize = 1000;
+
+/// an interface port bound to a port of a component
+#[derive(Debug)]
+struct Binding {
+    port: String,
+    fb_name: String,
+    fb_port: String,
+}
+
+/// interface output events emitted together, with the interface data outputs at the time of the emission
+#[derive(Debug)]
+struct PendingOutput {
+    events: Vec<String>,
+    data: Vec<(String, DataBuffer)>,
+}
+
+#[derive(Debug)]
+pub struct Cfb {
+    instance_name: String,
+    type_name: String,
+    interface: InterfaceDesc,
+    network: IdConnRuntime,
+    scheduler: Scheduler,
+    clock: Arc<dyn Clock>,
+    event_in: Option<String>,
+    events_out: Vec<String>,
+    pending_out: VecDeque<PendingOutput>,
+    inputs: HashMap<String, DataBuffer>,
+    /// interface data outputs latched with the active emission
+    outputs: HashMap<String, DataBuffer>,
+    event_inputs: Vec<Binding>,
+    event_outputs: Vec<Binding>,
+    data_inputs: Vec<Binding>,
+    data_outputs: Vec<Binding>,
+}
+
+impl Cfb {
+    /// creates an empty composite, port names of the interface are lowercased
+    pub fn new(instance_name: &str, type_name: &str, interface: InterfaceDesc) -> Self {
+        let events = |events: Vec<EventDesc>| {
+            events
+                .into_iter()
+                .map(|e| EventDesc {
+                    name: e.name.to_lowercase(),
+                    with: e.with.iter().map(|w| w.to_lowercase()).collect(),
+                    ..e
+                })
+                .collect()
+        };
+
+        let vars = |vars: Vec<VarDesc>| {
+            vars.into_iter()
+                .map(|v| VarDesc {
+                    name: v.name.to_lowercase(),
+                    ..v
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let interface = InterfaceDesc {
+            event_inputs: events(interface.event_inputs),
+            event_outputs: events(interface.event_outputs),
+            input_vars: vars(interface.input_vars),
+            output_vars: vars(interface.output_vars),
+        };
+
+        let inputs = interface
+            .input_vars
+            .iter()
+            .map(|v| (v.name.clone(), initial_value(instance_name, v)))
+            .collect();
+
+        Self {
+            instance_name: instance_name.to_string(),
+            type_name: type_name.to_string(),
+            interface,
+            network: IdConnRuntime::default(),
+            scheduler: Scheduler::default(),
+            clock: Arc::new(RealClock),
+            event_in: None,
+            events_out: vec![],
+            pending_out: VecDeque::new(),
+            inputs,
+            outputs: HashMap::new(),
+            event_inputs: vec![],
+            event_outputs: vec![],
+            data_inputs: vec![],
+            data_outputs: vec![],
+        }
+    }
+
+    /// the network of the component function blocks
+    pub fn network(&self) -> &IdConnRuntime {
+        &self.network
+    }
+
+    pub fn add_fb<T: Bfb + 'static>(&mut self, fb: T) {
+        self.network.add_fb(fb);
+    }
+
+    pub fn add_boxed_fb(&mut self, fb: Box<dyn Bfb>) {
+        self.network.add_boxed_fb(fb);
+    }
+
+    /// creates a component of a registered type, see `IdConnRuntime::create_fb`
+    pub fn create_fb(
+        &mut self,
+        registry: &TypeRegistry,
+        type_name: &str,
+        instance_name: &str,
+    ) -> Result<(), FbError> {
+        self.network.create_fb(registry, type_name, instance_name)
+    }
+
+    /// connects two components, see `IdConnRuntime::connect_event`
+    pub fn connect_event(&mut self, from: (&str, &str), to: (&str, &str)) {
+        self.network.connect_event(from, to);
+    }
+
+    /// connects two components, see `IdConnRuntime::connect_data`
+    pub fn connect_data(&mut self, from: (&str, &str), to: (&str, &str)) -> Result<(), FbError> {
+        self.network.connect_data(from, to)
+    }
+
+    /// delivers the interface input event to the input event of a component
+    pub fn bind_event_in(&mut self, event: &str, to: (&str, &str)) -> Result<(), FbError> {
+        let event = event.to_lowercase();
+
+        if self.interface.event_input(&event).is_none() {
+            return Err(self.interface_error(&event, true));
+        }
+
+        self.component_event(to, true)?;
+        self.event_inputs.push(binding(&event, to));
+
+        Ok(())
+    }
+
+    /// emits the interface output event whenever the component emits its output event
+    pub fn bind_event_out(&mut self, from: (&str, &str), event: &str) -> Result<(), FbError> {
+        let event = event.to_lowercase();
+
+        if self.interface.event_output(&event).is_none() {
+            return Err(self.interface_error(&event, true));
+        }
+
+        self.component_event(from, false)?;
+        self.event_outputs.push(binding(&event, from));
+        self.scheduler.watch(from.0);
+
+        Ok(())
+    }
+
+    /// latches the interface data input for the data input of a component
+    pub fn bind_data_in(&mut self, data: &str, to: (&str, &str)) -> Result<(), FbError> {
+        let data = data.to_lowercase();
+
+        let Some(var) = self.interface.input_var(&data) else {
+            return Err(self.interface_error(&data, false));
+        };
+
+        let kind = var.kind.clone();
+        bind_kind(&mut self.network, &kind, to, true)?;
+
+        self.network.write_remote_input(to, &self.inputs[&data]);
+        self.data_inputs.push(binding(&data, to));
+
+        Ok(())
+    }
+
+    /// reads the interface data output from the data output of a component
+    pub fn bind_data_out(&mut self, from: (&str, &str), data: &str) -> Result<(), FbError> {
+        let data = data.to_lowercase();
+
+        let Some(var) = self.interface.output_var(&data) else {
+            return Err(self.interface_error(&data, false));
+        };
+
+        let kind = var.kind.clone();
+        bind_kind(&mut self.network, &kind, from, false)?;
+        self.component(from)?.read_data_out(from.1)?;
+
+        self.data_outputs.push(binding(&data, from));
+
+        Ok(())
+    }
+}
+
+impl Cfb {
+    fn component(&self, port: (&str, &str)) -> Result<&dyn Bfb, FbError> {
+        self.network
+            .fbs()
+            .get(port.0)
+            .map(|fb| fb.as_ref())
+            .ok_or_else(|| FbError::unknown_data(&format!("{}.{}", port.0, port.1)))
+    }
+
+    /// checks that the component has the event in the expected direction
+    fn component_event(&self, port: (&str, &str), is_input: bool) -> Result<(), FbError> {
+        let fb = self.component(port)?;
+        let (expected, other) = if is_input {
+            (fb.event_inputs(), fb.event_outputs())
+        } else {
+            (fb.event_outputs(), fb.event_inputs())
+        };
+        let has = |events: Vec<&str>| events.iter().any(|e| e.eq_ignore_ascii_case(port.1));
+
+        match (has(expected), has(other)) {
+            (true, _) => Ok(()),
+            (false, true) => Err(FbError::wrong_direction(port.1)),
+            (false, false) => Err(FbError::unknown_event(port.1)),
+        }
+    }
+
+    /// error for a port that is not part of the interface in the expected direction
+    fn interface_error(&self, port: &str, is_event: bool) -> FbError {
+        let exists = if is_event {
+            self.interface.event_input(port).is_some()
+                || self.interface.event_output(port).is_some()
+        } else {
+            self.interface.input_var(port).is_some() || self.interface.output_var(port).is_some()
+        };
+
+        match (exists, is_event) {
+            (true, _) => FbError::wrong_direction(port),
+            (false, true) => FbError::unknown_event(port),
+            (false, false) => FbError::unknown_data(port),
+        }
+    }
+
+    /// whether a timer of a component expired on the clock of the composite
+    fn timer_expired(&self) -> bool {
+        self.deadline().is_some_and(|d| d <= self.clock.now())
+    }
+
+    /// queues the interface output events bound to the output event of a component
+    fn queue_emission(&mut self, emission: Emission) {
+        let bound = |b: &&Binding| b.fb_name == emission.fb_name;
+
+        let events: Vec<String> = self
+            .event_outputs
+            .iter()
+            .filter(bound)
+            .filter(|b| b.fb_port == emission.event)
+            .map(|b| b.port.clone())
+            .collect();
+
+        if events.is_empty() {
+            return;
+        }
+
+        let data = self
+            .data_outputs
+            .iter()
+            .filter(bound)
+            .filter_map(|b| {
+                let (_, buf) = emission.data.iter().find(|(port, _)| *port == b.fb_port)?;
+                Some((b.port.clone(), buf.clone()))
+            })
+            .collect();
+
+        self.pending_out.push_back(PendingOutput { events, data });
+    }
+
+    /// emits the next queued interface output events, if the previous ones were taken
+    fn emit_pending(&mut self) -> bool {
+        if !self.events_out.is_empty() {
+            return false;
+        }
+
+        let Some(pending) = self.pending_out.pop_front() else {
+            return false;
+        };
+
+        self.events_out = pending.events;
+        self.outputs = pending.data.into_iter().collect();
+
+        true
+    }
+}
+
+fn binding(port: &str, fb_port: (&str, &str)) -> Binding {
+    Binding {
+        port: port.to_string(),
+        fb_name: fb_port.0.to_string(),
+        fb_port: fb_port.1.to_string(),
+    }
+}
+
+/// checks that the interface port and the component port are assignable like a data connection,
+/// a generic component port is fixed to the kind of the interface port
+fn bind_kind(
+    network: &mut IdConnRuntime,
+    kind: &DataKind,
+    port: (&str, &str),
+    input: bool,
+) -> Result<(), FbError> {
+    let name = format!("{}.{}", port.0, port.1);
+    let Some(fb) = network.fbs_mut().get_mut(port.0) else {
+        return Err(FbError::unknown_data(&name));
+    };
+
+    let fb_kind = fb.data_kind(port.1)?;
+    let (source, destination) = if input {
+        (kind, &fb_kind)
+    } else {
+        (&fb_kind, kind)
+    };
+
+    if !ty::assignable(source, destination) {
+        return Err(FbError::type_mismatch(
+            port.1,
+            fb_kind,
+            &DataBuffer::default_for(kind),
+        ));
+    }
+
+    // the kinds of the interface are declared by the composite type
+    match ty::fixing(source, destination) {
+        Some(ty::Fixing::Destination(kind)) if input => fb.fix_data_kind(port.1, &kind),
+        Some(ty::Fixing::Source(kind)) if !input => fb.fix_data_kind(port.1, &kind),
+        _ => Ok(()),
+    }
+}
+
+fn initial_value(instance_name: &str, var: &VarDesc) -> DataBuffer {
+    let Some(value) = &var.initial_value else {
+        return DataBuffer::default_for(&var.kind);
+    };
+
+    DataBuffer::parse_literal(&var.kind, value).unwrap_or_else(|| {
+        println!(
+            "[error cfb]: {instance_name}: invalid initial value \"{value}\" of \"{}\"",
+            var.name
+        );
+        DataBuffer::default_for(&var.kind)
+    })
+}
+
+impl Bfb for Cfb {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn instance_name(&self) -> &str {
+        &self.instance_name
+    }
+
+    fn type_name(&self) -> &str {
+        &self.type_name
+    }
+
+    fn interface(&self) -> TypeInterface {
+        TypeInterface {
+            type_name: self.type_name.clone(),
+            comment: String::new(),
+            interface: self.interface.clone(),
+        }
+    }
+
+    fn event_inputs(&self) -> Vec<&str> {
+        self.interface
+            .event_inputs
+            .iter()
+            .map(|e| e.name.as_str())
+            .collect()
+    }
+
+    fn event_outputs(&self) -> Vec<&str> {
+        self.interface
+            .event_outputs
+            .iter()
+            .map(|e| e.name.as_str())
+            .collect()
+    }
+
+    fn data_inputs(&self) -> Vec<&str> {
+        self.interface
+            .input_vars
+            .iter()
+            .map(|v| v.name.as_str())
+            .collect()
+    }
+
+    fn data_outputs(&self) -> Vec<&str> {
+        self.interface
+            .output_vars
+            .iter()
+            .map(|v| v.name.as_str())
+            .collect()
+    }
+
+    fn data_kind(&self, data: &str) -> Result<DataKind, FbError> {
+        self.interface
+            .input_var(data)
+            .or_else(|| self.interface.output_var(data))
+            .map(|v| v.kind.clone())
+            .ok_or_else(|| FbError::unknown_data(data))
+    }
+
+    fn set_event_in(&mut self, event: &str) -> Result<(), FbError> {
+        let Some(e) = self.interface.event_input(event) else {
+            return Err(self.interface_error(event, true));
+        };
+
+        if let Some(pending) = self.active_event_in() {
+            return Err(FbError::event_already_pending(event, pending));
+        }
+
+        self.event_in = Some(e.name.clone());
+
+        Ok(())
+    }
+
+    fn active_event_in(&self) -> Option<&str> {
+        self.event_in.as_deref()
+    }
+
+    fn active_event_out(&self) -> Option<&str> {
+        self.events_out.last().map(String::as_str)
+    }
+
+    fn active_events_out(&self) -> Vec<&str> {
+        self.events_out.iter().map(String::as_str).collect()
+    }
+
+    fn clear_event_out(&mut self) {
+        self.events_out.clear();
+        self.outputs.clear();
+    }
+
+    fn with_for_event(&self, event: &str) -> Result<Vec<&str>, FbError> {
+        self.interface
+            .event_input(event)
+            .or_else(|| self.interface.event_output(event))
+            .map(|e| e.with.iter().map(String::as_str).collect())
+            .ok_or_else(|| FbError::unknown_event(event))
+    }
+
+    fn read_data_out(&self, data: &str) -> Result<DataBuffer, FbError> {
+        let Some(var) = self.interface.output_var(data) else {
+            return Err(self.interface_error(data, false));
+        };
+
+        let buf = match self.outputs.get(&var.name) {
+            Some(buf) => buf.clone(),
+            None => match self.data_outputs.iter().find(|b| b.port == var.name) {
+                Some(b) => self.network.fbs()[&b.fb_name].read_data_out(&b.fb_port)?,
+                None => return Ok(DataBuffer::default_for(&var.kind)),
+            },
+        };
+
+        Ok(convert::widen(&buf, &var.kind).unwrap_or(buf))
+    }
+
+    fn write_data_in(&mut self, data: &str, buf: &DataBuffer) -> Result<(), FbError> {
+        let Some(var) = self.interface.input_var(data) else {
+            return Err(self.interface_error(data, false));
+        };
+
+        if buf.kind().as_ref() != Some(&var.kind) {
+            return Err(FbError::type_mismatch(data, var.kind.clone(), buf));
+        }
+
+        for b in self.data_inputs.iter().filter(|b| b.port == var.name) {
+            self.network
+                .write_remote_input((&b.fb_name, &b.fb_port), buf);
+        }
+
+        self.inputs.insert(var.name.clone(), buf.clone());
+
+        Ok(())
+    }
+
+    /// emits the next queued interface output events, otherwise delivers the pending input event,
+    /// or the expired timers of the components, and runs the internal event chains to completion
+    fn invoke_execution_control(&mut self) -> Result<bool, FbError> {
+        if !self.pending_out.is_empty() {
+            self.emit_pending();
+            return Ok(true);
+        }
+
+        let result = match self.event_in.take() {
+            Some(event) => {
+                for b in self.event_inputs.iter().filter(|b| b.port == event) {
+                    self.scheduler.post(&b.fb_name, &b.fb_port);
+                }
+
+                self.scheduler.run(&mut self.network, MAX_DELIVERIES)
+            }
+            None if self.timer_expired() => {
+                let now = self.clock.now();
+                self.scheduler
+                    .poll_timers(&mut self.network, now, MAX_DELIVERIES)
+            }
+            None => return Ok(false),
+        };
+
+        // the emissions before a failure are still forwarded
+        for emission in self.scheduler.take_emissions() {
+            self.queue_emission(emission);
+        }
+
+        self.emit_pending();
+
+        if !result? {
+            return Err(FbError::not_stable(&self.instance_name, MAX_DELIVERIES));
+        }
+
+        Ok(true)
+    }
+
+    fn deadline(&self) -> Option<Duration> {
+        self.network
+            .fbs()
+            .values()
+            .filter_map(|fb| fb.deadline())
+            .min()
+    }
+
+    fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.network.set_clock(Arc::clone(&clock));
+        self.clock = clock;
+    }
+}
+
+impl Display for Cfb {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let events = self
+            .interface
+            .event_inputs
+            .iter()
+            .map(|e| (&e.name, self.event_in.as_ref() == Some(&e.name)))
+            .chain(
+                self.interface
+                    .event_outputs
+                    .iter()
+                    .map(|e| (&e.name, self.events_out.contains(&e.name))),
+            )
+            .map(|(name, active)| format!("{name}={active}"));
+
+        let inputs = self
+            .interface
+            .input_vars
+            .iter()
+            .map(|v| format!("{}={}", v.name, self.inputs[&v.name]));
+
+        let outputs = self.interface.output_vars.iter().map(|v| {
+            let value = self
+                .read_data_out(&v.name)
+                .unwrap_or(DataBuffer::Unassigned);
+
+            format!("{}={value}", v.name)
+        });
+
+        let mut components: Vec<String> = self
+            .network
+            .fbs()
+            .values()
+            .map(|fb| fb.to_string())
+            .collect();
+        components.sort();
+
+        let fields: Vec<String> = events.chain(inputs).chain(outputs).collect();
+
+        write!(
+            f,
+            "{}={{{}, network=[{}]}}",
+            self.instance_name,
+            fields.join(", "),
+            components.join(", ")
+        )
+    }
+}
@@ -0,0 +1,200 @@
+//! Conversion function blocks (`F_UINT_TO_DINT`, `F_REAL_TO_INT`, ...) bridging data connections of different types.
+//!
+//! `REQ` converts `IN` with `fb::data::convert::convert` and confirms with `CNF`,
+//! a value which can not be converted keeps the previous `OUT` and sends no `CNF`.
+
+use std::fmt::Display;
+
+use crate::fb::{
+    Bfb,
+    data::{
+        comm::DataBuffer,
+        convert::{self, ConversionError},
+        ty::DataKind,
+    },
+    error::FbError,
+    registry::TypeRegistry,
+};
+
+const EVENT_INPUTS: [&str; 1] = ["req"];
+const EVENT_OUTPUTS: [&str; 1] = ["cnf"];
+
+#[derive(Debug)]
+pub struct Conversion {
+    instance_name: String,
+    type_name: String,
+    to: DataKind,
+    req: bool,
+    cnf: bool,
+    input: DataBuffer,
+    output: DataBuffer,
+}
+
+impl Conversion {
+    /// fails if there is no conversion between the two types
+    pub fn new(
+        instance_name: &str,
+        from: &DataKind,
+        to: &DataKind,
+    ) -> Result<Self, ConversionError> {
+        if !convert::supports(from, to) {
+            return Err(ConversionError::Unsupported {
+                from: from.clone(),
+                to: to.clone(),
+            });
+        }
+
+        Ok(Self {
+            instance_name: instance_name.to_string(),
+            type_name: type_name(from, to),
+            to: to.clone(),
+            req: false,
+            cnf: false,
+            input: DataBuffer::default_for(from),
+            output: DataBuffer::default_for(to),
+        })
+    }
+
+    fn from(&self) -> DataKind {
+        self.input.kind().expect("IN is always assigned")
+    }
+}
+
+/// returns the type name of the conversion function block, e.g. `F_UINT_TO_DINT`
+pub fn type_name(from: &DataKind, to: &DataKind) -> String {
+    format!("F_{}", convert::function_name(from, to))
+}
+
+/// registers a conversion function block for every supported pair of elementary types
+pub fn register(registry: &mut TypeRegistry) {
+    for from in &DataKind::ELEMENTARY {
+        for to in DataKind::ELEMENTARY
+            .iter()
+            .filter(|to| convert::supports(from, to))
+        {
+            let (from, to) = (from.clone(), to.clone());
+
+            registry.register(&type_name(&from, &to), move |name| {
+                Box::new(Conversion::new(name, &from, &to).expect("conversion is supported"))
+            });
+        }
+    }
+}
+
+impl Bfb for Conversion {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn instance_name(&self) -> &str {
+        &self.instance_name
+    }
+
+    fn type_name(&self) -> &str {
+        &self.type_name
+    }
+
+    fn event_inputs(&self) -> Vec<&str> {
+        EVENT_INPUTS.to_vec()
+    }
+
+    fn event_outputs(&self) -> Vec<&str> {
+        EVENT_OUTPUTS.to_vec()
+    }
+
+    fn data_inputs(&self) -> Vec<&str> {
+        vec!["in"]
+    }
+
+    fn data_outputs(&self) -> Vec<&str> {
+        vec!["out"]
+    }
+
+    fn data_kind(&self, data: &str) -> Result<DataKind, FbError> {
+        match data {
+            "in" => Ok(self.from()),
+            "out" => Ok(self.to.clone()),
+            _ => Err(FbError::unknown_data(data)),
+        }
+    }
+
+    fn set_event_in(&mut self, event: &str) -> Result<(), FbError> {
+        match event {
+            "req" if self.req => Err(FbError::event_already_pending(event, "req")),
+            "req" => {
+                self.req = true;
+                Ok(())
+            }
+            "cnf" => Err(FbError::wrong_direction(event)),
+            _ => Err(FbError::unknown_event(event)),
+        }
+    }
+
+    fn active_event_in(&self) -> Option<&str> {
+        self.req.then_some("req")
+    }
+
+    fn active_event_out(&self) -> Option<&str> {
+        self.cnf.then_some("cnf")
+    }
+
+    fn clear_event_out(&mut self) {
+        self.cnf = false;
+    }
+
+    fn with_for_event(&self, event: &str) -> Result<Vec<&str>, FbError> {
+        match event {
+            "req" => Ok(vec!["in"]),
+            "cnf" => Ok(vec!["out"]),
+            _ => Err(FbError::unknown_event(event)),
+        }
+    }
+
+    fn read_data_out(&self, data: &str) -> Result<DataBuffer, FbError> {
+        match data {
+            "out" => Ok(self.output.clone()),
+            "in" => Err(FbError::wrong_direction(data)),
+            _ => Err(FbError::unknown_data(data)),
+        }
+    }
+
+    fn write_data_in(&mut self, data: &str, buf: &DataBuffer) -> Result<(), FbError> {
+        match data {
+            "in" if buf.kind() == Some(self.from()) => {
+                self.input = buf.clone();
+                Ok(())
+            }
+            "in" => Err(FbError::type_mismatch(data, self.from(), buf)),
+            "out" => Err(FbError::wrong_direction(data)),
+            _ => Err(FbError::unknown_data(data)),
+        }
+    }
+
+    fn invoke_execution_control(&mut self) -> Result<bool, FbError> {
+        if !self.req {
+            return Ok(false);
+        }
+
+        self.req = false;
+
+        match convert::convert(&self.input, &self.to) {
+            Ok(output) => {
+                self.output = output;
+                self.cnf = true;
+            }
+            Err(e) => println!("[error convert]: {}: {e}", self.instance_name),
+        }
+
+        Ok(true)
+    }
+}
+
+impl Display for Conversion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}={{req={}, cnf={}, in={}, out={}}}",
+            self.instance_name, self.req, self.cnf, self.input, self.output,
+        )
+    }
+}
@@ -0,0 +1,93 @@
+use std::sync::Arc;
+
+use crate::fb::{
+    Bfb,
+    data::{
+        Data,
+        ty::{Bool, UInt},
+    },
+    direction::{In, Out},
+    ecc::{Condition, EcAction, Ecc, ExecutionControl},
+    event::{Event, ty::Signal},
+};
+
+#[allow(non_camel_case_types)]
+#[derive(Default, Debug, Bfb)]
+pub struct E_CTD {
+    instance_name: String,
+    #[ecc]
+    ecc: ExecutionControl<Self>,
+    cd: Event<In, Signal>,
+    #[with(pv)]
+    ld: Event<In, Signal>,
+    #[with(q, cv)]
+    cdo: Event<Out, Signal>,
+    #[with(q, cv)]
+    ldo: Event<Out, Signal>,
+    pv: Data<In, UInt>,
+    q: Data<Out, Bool>,
+    cv: Data<Out, UInt>,
+}
+
+impl E_CTD {
+    pub fn new(instance_name: &str) -> Self {
+        Self {
+            instance_name: instance_name.to_string(),
+            ecc: ExecutionControl::new(Arc::new(Self::chart())),
+            ..Default::default()
+        }
+    }
+
+    fn chart() -> Ecc<Self> {
+        Ecc::builder()
+            .state("START", vec![])
+            .state(
+                "CD",
+                vec![EcAction::algorithm("CD", Self::cd_algorithm).then_output("cdo")],
+            )
+            .state(
+                "LD",
+                vec![EcAction::algorithm("LD", Self::ld_algorithm).then_output("ldo")],
+            )
+            .transition(
+                "START",
+                "CD",
+                Condition::event("cd").with_guard("CV > 0", |fb: &Self| fb.cv.read() > 0),
+            )
+            .transition("START", "LD", Condition::event("ld"))
+            .transition("CD", "START", Condition::always())
+            .transition("LD", "START", Condition::always())
+            .build()
+            .expect("E_CTD chart is valid")
+    }
+}
+
+impl E_CTD {
+    fn cd_algorithm(&mut self) {
+        self.cv.write(self.cv.read() - 1);
+        self.q.write(self.cv.read() == 0);
+    }
+
+    fn ld_algorithm(&mut self) {
+        self.cv.write(self.pv.read());
+        self.q.write(self.cv.read() == 0);
+    }
+}
+
+impl std::fmt::Display for E_CTD {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}={{cd={}, ld={}, cdo={}, ldo={}, pv={}, q={}, cv={}, state={}}}",
+            self.instance_name,
+            self.cd.read(),
+            self.ld.read(),
+            self.cdo.read(),
+            self.ldo.read(),
+            self.pv.as_buf(),
+            self.q.as_buf(),
+            self.cv.as_buf(),
+            self.ecc.state(),
+        )
+    }
+}
@@ -1,33 +1,28 @@
-use crate::{
-    fb::{
-        Bfb,
-        data::{
-            Data,
-            comm::DataBuffer,
-            ty::{Bool, DataKind, UInt},
-        },
-        direction::{In, Out},
-        event::{Event, ty::Signal},
+use std::sync::Arc;
+
+use crate::fb::{
+    Bfb,
+    data::{
+        Data,
+        ty::{Bool, UInt},
     },
-    fb_impl::event::dbg_state_print,
+    direction::{In, Out},
+    ecc::{Condition, EcAction, Ecc, ExecutionControl},
+    event::{Event, ty::Signal},
 };
 
-#[derive(Clone, Debug, Default)]
-enum CtuState {
-    #[default]
-    Start,
-    Cu,
-    R,
-}
-
 #[allow(non_camel_case_types)]
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Bfb)]
 pub struct E_CTU {
-    instance_name: &'static str,
-    ec_state: CtuState,
+    instance_name: String,
+    #[ecc]
+    ecc: ExecutionControl<Self>,
+    #[with(pv)]
     cu: Event<In, Signal>,
     r: Event<In, Signal>,
+    #[with(q, cv)]
     cuo: Event<Out, Signal>,
+    #[with(q, cv)]
     ro: Event<Out, Signal>,
     pv: Data<In, UInt>,
     q: Data<Out, Bool>,
@@ -35,127 +30,35 @@ pub struct E_CTU {
 }
 
 impl E_CTU {
-    pub fn new(instance_name: &'static str) -> Self {
+    pub fn new(instance_name: &str) -> Self {
         Self {
-            instance_name,
+            instance_name: instance_name.to_string(),
+            ecc: ExecutionControl::new(Arc::new(Self::chart())),
             ..Default::default()
         }
     }
-}
-
-impl Bfb for E_CTU {
-    fn as_any(&self) -> &dyn std::any::Any {
-        self
-    }
-
-    fn instance_name(&self) -> &'static str {
-        self.instance_name
-    }
-
-    fn data_kind(&self, data: &str) -> DataKind {
-        match data {
-            "pv" => self.pv.as_kind(),
-            "q" => self.q.as_kind(),
-            "cv" => self.cv.as_kind(),
-            _ => panic!("unknown data {data}"),
-        }
-    }
-
-    fn set_event_in(&mut self, event: &str) {
-        match event {
-            "cu" => self.cu.receive(),
-            "r" => self.r.receive(),
-            _ => panic!("unknown event {event}"),
-        }
-    }
-
-    fn active_event_in(&self) -> Option<&'static str> {
-        let mut event = None;
-
-        if self.cu.read() {
-            event = Some("cu");
-        }
-
-        if self.r.read() {
-            event = Some("r");
-        }
-
-        event
-    }
-
-    fn active_event_out(&self) -> Option<&'static str> {
-        let mut event = None;
-
-        if self.cuo.read() {
-            event = Some("cuo");
-        }
-
-        if self.ro.read() {
-            event = Some("ro");
-        }
-
-        event
-    }
-
-    fn clear_event_out(&mut self) {
-        self.cuo.reset();
-        self.ro.reset();
-    }
-
-    fn with_for_event(&self, event: &str) -> Vec<&'static str> {
-        match event {
-            "cu" => vec!["pv"],
-            "cuo" | "ro" => vec!["q", "cv"],
-            "r" => vec![],
-            _ => panic!("unknown event {event}"),
-        }
-    }
-
-    fn read_data_out(&self, data: &str) -> DataBuffer {
-        match data {
-            "q" => self.q.as_buf(),
-            "cv" => self.cv.as_buf(),
-            _ => panic!("unknown data {data}"),
-        }
-    }
 
-    fn write_data_in(&mut self, data: &str, buf: &DataBuffer) {
-        match (data, buf) {
-            ("pv", DataBuffer::UInt(v)) => {
-                self.pv.update(*v);
-            }
-            _ => panic!("unknown data {data} or invalid communication data variant {buf:?}"),
-        }
-    }
-
-    fn invoke_execution_control(&mut self) -> bool {
-        let mut unstable = false;
-
-        match self.ec_state {
-            CtuState::Start => {
-                if self.cu.read_and_reset() && self.cv.read() < 65535 {
-                    dbg_state_print(self.instance_name, "START -> CU");
-                    self.enter(CtuState::Cu);
-                    unstable = true;
-                } else if self.r.read_and_reset() {
-                    dbg_state_print(self.instance_name, "START -> RESET");
-                    self.enter(CtuState::R);
-                    unstable = true;
-                }
-            }
-            CtuState::Cu => {
-                dbg_state_print(self.instance_name, "CU -> START");
-                self.enter(CtuState::Start);
-                unstable = true;
-            }
-            CtuState::R => {
-                dbg_state_print(self.instance_name, "RESET -> START");
-                self.enter(CtuState::Start);
-                unstable = true;
-            }
-        }
-
-        unstable
+    fn chart() -> Ecc<Self> {
+        Ecc::builder()
+            .state("START", vec![])
+            .state(
+                "CU",
+                vec![EcAction::algorithm("CU", Self::cu_algorithm).then_output("cuo")],
+            )
+            .state(
+                "R",
+                vec![EcAction::algorithm("R", Self::r_algorithm).then_output("ro")],
+            )
+            .transition(
+                "START",
+                "CU",
+                Condition::event("cu").with_guard("CV < 65535", |fb: &Self| fb.cv.read() < 65535),
+            )
+            .transition("START", "R", Condition::event("r"))
+            .transition("CU", "START", Condition::always())
+            .transition("R", "START", Condition::always())
+            .build()
+            .expect("E_CTU chart is valid")
     }
 }
 
@@ -171,29 +74,11 @@ impl E_CTU {
     }
 }
 
-impl E_CTU {
-    fn enter(&mut self, state: CtuState) {
-        match state {
-            CtuState::Start => {}
-            CtuState::Cu => {
-                self.cu_algorithm();
-                self.cuo.send();
-            }
-            CtuState::R => {
-                self.r_algorithm();
-                self.ro.send();
-            }
-        }
-
-        self.ec_state = state;
-    }
-}
-
 impl std::fmt::Display for E_CTU {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "{}={{cu={}, r={}, cuo={}, ro={}, pv={}, q={}, cv={}, state={:?}}}",
+            "{}={{cu={}, r={}, cuo={}, ro={}, pv={}, q={}, cv={}, state={}}}",
             self.instance_name,
             self.cu.read(),
             self.r.read(),
@@ -202,7 +87,7 @@ impl std::fmt::Display for E_CTU {
             self.pv.as_buf(),
             self.q.as_buf(),
             self.cv.as_buf(),
-            self.ec_state,
+            self.ecc.state(),
         )
     }
 }
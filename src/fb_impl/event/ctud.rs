@@ -0,0 +1,136 @@
+use std::sync::Arc;
+
+use crate::fb::{
+    Bfb,
+    data::{
+        Data,
+        ty::{Bool, UInt},
+    },
+    direction::{In, Out},
+    ecc::{Condition, EcAction, Ecc, ExecutionControl},
+    event::{Event, ty::Signal},
+};
+
+/// up/down counter, `qu` signals `cv >= pv` and `qd` signals `cv = 0`
+#[allow(non_camel_case_types)]
+#[derive(Default, Debug, Bfb)]
+pub struct E_CTUD {
+    instance_name: String,
+    #[ecc]
+    ecc: ExecutionControl<Self>,
+    #[with(pv)]
+    cu: Event<In, Signal>,
+    #[with(pv)]
+    cd: Event<In, Signal>,
+    r: Event<In, Signal>,
+    #[with(pv)]
+    ld: Event<In, Signal>,
+    #[with(qu, qd, cv)]
+    co: Event<Out, Signal>,
+    #[with(qu, qd, cv)]
+    ro: Event<Out, Signal>,
+    #[with(qu, qd, cv)]
+    ldo: Event<Out, Signal>,
+    pv: Data<In, UInt>,
+    qu: Data<Out, Bool>,
+    qd: Data<Out, Bool>,
+    cv: Data<Out, UInt>,
+}
+
+impl E_CTUD {
+    pub fn new(instance_name: &str) -> Self {
+        Self {
+            instance_name: instance_name.to_string(),
+            ecc: ExecutionControl::new(Arc::new(Self::chart())),
+            ..Default::default()
+        }
+    }
+
+    fn chart() -> Ecc<Self> {
+        Ecc::builder()
+            .state("START", vec![])
+            .state(
+                "CU",
+                vec![EcAction::algorithm("CU", Self::cu_algorithm).then_output("co")],
+            )
+            .state(
+                "CD",
+                vec![EcAction::algorithm("CD", Self::cd_algorithm).then_output("co")],
+            )
+            .state(
+                "R",
+                vec![EcAction::algorithm("R", Self::r_algorithm).then_output("ro")],
+            )
+            .state(
+                "LD",
+                vec![EcAction::algorithm("LD", Self::ld_algorithm).then_output("ldo")],
+            )
+            .transition(
+                "START",
+                "CU",
+                Condition::event("cu").with_guard("CV < 65535", |fb: &Self| fb.cv.read() < 65535),
+            )
+            .transition(
+                "START",
+                "CD",
+                Condition::event("cd").with_guard("CV > 0", |fb: &Self| fb.cv.read() > 0),
+            )
+            .transition("START", "R", Condition::event("r"))
+            .transition("START", "LD", Condition::event("ld"))
+            .transition("CU", "START", Condition::always())
+            .transition("CD", "START", Condition::always())
+            .transition("R", "START", Condition::always())
+            .transition("LD", "START", Condition::always())
+            .build()
+            .expect("E_CTUD chart is valid")
+    }
+}
+
+impl E_CTUD {
+    fn cu_algorithm(&mut self) {
+        self.cv.write(self.cv.read() + 1);
+        self.update_outputs();
+    }
+
+    fn cd_algorithm(&mut self) {
+        self.cv.write(self.cv.read() - 1);
+        self.update_outputs();
+    }
+
+    fn r_algorithm(&mut self) {
+        self.cv.write(0);
+        self.update_outputs();
+    }
+
+    fn ld_algorithm(&mut self) {
+        self.cv.write(self.pv.read());
+        self.update_outputs();
+    }
+
+    fn update_outputs(&mut self) {
+        self.qu.write(self.cv.read() >= self.pv.read());
+        self.qd.write(self.cv.read() == 0);
+    }
+}
+
+impl std::fmt::Display for E_CTUD {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}={{cu={}, cd={}, r={}, ld={}, co={}, ro={}, ldo={}, pv={}, qu={}, qd={}, cv={}, state={}}}",
+            self.instance_name,
+            self.cu.read(),
+            self.cd.read(),
+            self.r.read(),
+            self.ld.read(),
+            self.co.read(),
+            self.ro.read(),
+            self.ldo.read(),
+            self.pv.as_buf(),
+            self.qu.as_buf(),
+            self.qd.as_buf(),
+            self.cv.as_buf(),
+            self.ecc.state(),
+        )
+    }
+}
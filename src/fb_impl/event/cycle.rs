@@ -0,0 +1,99 @@
+use std::sync::Arc;
+
+use crate::{
+    fb::{
+        Bfb,
+        data::{Data, ty::Time},
+        direction::{In, Out},
+        ecc::{Condition, EcAction, Ecc, ExecutionControl},
+        event::{Event, ty::Signal},
+    },
+    run_time::clock::Timer,
+};
+
+/// emits `eo` every `dt` after `start` until `stop`, `start` is ignored while the cycle is running
+#[allow(non_camel_case_types)]
+#[derive(Default, Debug, Bfb)]
+pub struct E_CYCLE {
+    instance_name: String,
+    #[ecc]
+    ecc: ExecutionControl<Self>,
+    #[timer]
+    timer: Timer,
+    #[with(dt)]
+    start: Event<In, Signal>,
+    stop: Event<In, Signal>,
+    eo: Event<Out, Signal>,
+    dt: Data<In, Time>,
+}
+
+impl E_CYCLE {
+    pub fn new(instance_name: &str) -> Self {
+        Self {
+            instance_name: instance_name.to_string(),
+            ecc: ExecutionControl::new(Arc::new(Self::chart())),
+            ..Default::default()
+        }
+    }
+
+    fn chart() -> Ecc<Self> {
+        Ecc::builder()
+            .state("START", vec![])
+            .state(
+                "ARM",
+                vec![EcAction::algorithm("START", Self::start_algorithm)],
+            )
+            .state("ACTIVE", vec![])
+            .state(
+                "EO",
+                vec![EcAction::algorithm("NEXT", Self::next_algorithm).then_output("eo")],
+            )
+            .state(
+                "STOP",
+                vec![EcAction::algorithm("STOP", Self::stop_algorithm)],
+            )
+            .transition("START", "ARM", Condition::event("start"))
+            .transition("ARM", "ACTIVE", Condition::always())
+            .transition("ACTIVE", "STOP", Condition::event("stop"))
+            .transition(
+                "ACTIVE",
+                "EO",
+                Condition::guard("TIMEOUT", |fb: &Self| fb.timer.expired()),
+            )
+            .transition("EO", "ACTIVE", Condition::always())
+            .transition("STOP", "START", Condition::always())
+            .build()
+            .expect("E_CYCLE chart is valid")
+    }
+}
+
+impl E_CYCLE {
+    fn start_algorithm(&mut self) {
+        self.timer.start(self.dt.read());
+    }
+
+    /// the next period starts at the deadline of the previous one, not when it was noticed
+    fn next_algorithm(&mut self) {
+        self.timer.restart(self.dt.read());
+    }
+
+    fn stop_algorithm(&mut self) {
+        self.timer.stop();
+    }
+}
+
+impl std::fmt::Display for E_CYCLE {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}={{start={}, stop={}, eo={}, dt={}, deadline={}, state={}}}",
+            self.instance_name,
+            self.start.read(),
+            self.stop.read(),
+            self.eo.read(),
+            self.dt.as_buf(),
+            self.timer,
+            self.ecc.state(),
+        )
+    }
+}
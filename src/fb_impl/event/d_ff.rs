@@ -0,0 +1,85 @@
+use std::sync::Arc;
+
+use crate::fb::{
+    Bfb,
+    data::{Data, ty::Bool},
+    direction::{In, Out},
+    ecc::{Condition, EcAction, Ecc, ExecutionControl},
+    event::{Event, ty::Signal},
+};
+
+/// data latch, `clk` stores `d` in `q` and emits `eo` only if `q` changed
+#[allow(non_camel_case_types)]
+#[derive(Default, Debug, Bfb)]
+pub struct E_D_FF {
+    instance_name: String,
+    #[ecc]
+    ecc: ExecutionControl<Self>,
+    #[with(d)]
+    clk: Event<In, Signal>,
+    #[with(q)]
+    eo: Event<Out, Signal>,
+    d: Data<In, Bool>,
+    q: Data<Out, Bool>,
+}
+
+impl E_D_FF {
+    pub fn new(instance_name: &str) -> Self {
+        Self {
+            instance_name: instance_name.to_string(),
+            ecc: ExecutionControl::new(Arc::new(Self::chart())),
+            ..Default::default()
+        }
+    }
+
+    fn chart() -> Ecc<Self> {
+        Ecc::builder()
+            .state("Q0", vec![])
+            .state(
+                "SET",
+                vec![EcAction::algorithm("LATCH", Self::latch_algorithm).then_output("eo")],
+            )
+            .state(
+                "RESET",
+                vec![EcAction::algorithm("LATCH", Self::latch_algorithm).then_output("eo")],
+            )
+            .transition(
+                "Q0",
+                "SET",
+                Condition::event("clk").with_guard("D", |fb: &Self| fb.d.read()),
+            )
+            .transition(
+                "SET",
+                "RESET",
+                Condition::event("clk").with_guard("NOT D", |fb: &Self| !fb.d.read()),
+            )
+            .transition(
+                "RESET",
+                "SET",
+                Condition::event("clk").with_guard("D", |fb: &Self| fb.d.read()),
+            )
+            .build()
+            .expect("E_D_FF chart is valid")
+    }
+}
+
+impl E_D_FF {
+    fn latch_algorithm(&mut self) {
+        self.q.write(self.d.read());
+    }
+}
+
+impl std::fmt::Display for E_D_FF {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}={{clk={}, d={}, eo={}, q={}, state={}}}",
+            self.instance_name,
+            self.clk.read(),
+            self.d.as_buf(),
+            self.eo.read(),
+            self.q.as_buf(),
+            self.ecc.state(),
+        )
+    }
+}
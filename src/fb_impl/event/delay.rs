@@ -0,0 +1,92 @@
+use std::sync::Arc;
+
+use crate::{
+    fb::{
+        Bfb,
+        data::{Data, ty::Time},
+        direction::{In, Out},
+        ecc::{Condition, EcAction, Ecc, ExecutionControl},
+        event::{Event, ty::Signal},
+    },
+    run_time::clock::Timer,
+};
+
+/// emits `eo` once `dt` elapsed after `start`, `start` is ignored while the delay is running
+#[allow(non_camel_case_types)]
+#[derive(Default, Debug, Bfb)]
+pub struct E_DELAY {
+    instance_name: String,
+    #[ecc]
+    ecc: ExecutionControl<Self>,
+    #[timer]
+    timer: Timer,
+    #[with(dt)]
+    start: Event<In, Signal>,
+    stop: Event<In, Signal>,
+    eo: Event<Out, Signal>,
+    dt: Data<In, Time>,
+}
+
+impl E_DELAY {
+    pub fn new(instance_name: &str) -> Self {
+        Self {
+            instance_name: instance_name.to_string(),
+            ecc: ExecutionControl::new(Arc::new(Self::chart())),
+            ..Default::default()
+        }
+    }
+
+    fn chart() -> Ecc<Self> {
+        Ecc::builder()
+            .state("START", vec![])
+            .state(
+                "DELAY",
+                vec![EcAction::algorithm("START", Self::start_algorithm)],
+            )
+            .state(
+                "STOP",
+                vec![EcAction::algorithm("STOP", Self::stop_algorithm)],
+            )
+            .state(
+                "EO",
+                vec![EcAction::algorithm("STOP", Self::stop_algorithm).then_output("eo")],
+            )
+            .transition("START", "DELAY", Condition::event("start"))
+            .transition("DELAY", "STOP", Condition::event("stop"))
+            .transition(
+                "DELAY",
+                "EO",
+                Condition::guard("TIMEOUT", |fb: &Self| fb.timer.expired()),
+            )
+            .transition("STOP", "START", Condition::always())
+            .transition("EO", "START", Condition::always())
+            .build()
+            .expect("E_DELAY chart is valid")
+    }
+}
+
+impl E_DELAY {
+    fn start_algorithm(&mut self) {
+        self.timer.start(self.dt.read());
+    }
+
+    fn stop_algorithm(&mut self) {
+        self.timer.stop();
+    }
+}
+
+impl std::fmt::Display for E_DELAY {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}={{start={}, stop={}, eo={}, dt={}, deadline={}, state={}}}",
+            self.instance_name,
+            self.start.read(),
+            self.stop.read(),
+            self.eo.read(),
+            self.dt.as_buf(),
+            self.timer,
+            self.ecc.state(),
+        )
+    }
+}
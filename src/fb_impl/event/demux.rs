@@ -0,0 +1,87 @@
+use std::sync::Arc;
+
+use crate::fb::{
+    Bfb,
+    data::{Data, ty::UInt},
+    direction::{In, Out},
+    ecc::{Condition, EcAction, Ecc, ExecutionControl},
+    event::{Event, ty::Signal},
+};
+
+/// forwards `ei` to the output `eo<k>`, events with `k > 3` are discarded
+#[allow(non_camel_case_types)]
+#[derive(Default, Debug, Bfb)]
+pub struct E_DEMUX {
+    instance_name: String,
+    #[ecc]
+    ecc: ExecutionControl<Self>,
+    #[with(k)]
+    ei: Event<In, Signal>,
+    eo0: Event<Out, Signal>,
+    eo1: Event<Out, Signal>,
+    eo2: Event<Out, Signal>,
+    eo3: Event<Out, Signal>,
+    k: Data<In, UInt>,
+}
+
+impl E_DEMUX {
+    pub fn new(instance_name: &str) -> Self {
+        Self {
+            instance_name: instance_name.to_string(),
+            ecc: ExecutionControl::new(Arc::new(Self::chart())),
+            ..Default::default()
+        }
+    }
+
+    fn chart() -> Ecc<Self> {
+        Ecc::builder()
+            .state("START", vec![])
+            .state("EO0", vec![EcAction::output("eo0")])
+            .state("EO1", vec![EcAction::output("eo1")])
+            .state("EO2", vec![EcAction::output("eo2")])
+            .state("EO3", vec![EcAction::output("eo3")])
+            .transition(
+                "START",
+                "EO0",
+                Condition::event("ei").with_guard("K = 0", |fb: &Self| fb.k.read() == 0),
+            )
+            .transition(
+                "START",
+                "EO1",
+                Condition::event("ei").with_guard("K = 1", |fb: &Self| fb.k.read() == 1),
+            )
+            .transition(
+                "START",
+                "EO2",
+                Condition::event("ei").with_guard("K = 2", |fb: &Self| fb.k.read() == 2),
+            )
+            .transition(
+                "START",
+                "EO3",
+                Condition::event("ei").with_guard("K = 3", |fb: &Self| fb.k.read() == 3),
+            )
+            .transition("EO0", "START", Condition::always())
+            .transition("EO1", "START", Condition::always())
+            .transition("EO2", "START", Condition::always())
+            .transition("EO3", "START", Condition::always())
+            .build()
+            .expect("E_DEMUX chart is valid")
+    }
+}
+
+impl std::fmt::Display for E_DEMUX {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}={{ei={}, k={}, eo0={}, eo1={}, eo2={}, eo3={}, state={}}}",
+            self.instance_name,
+            self.ei.read(),
+            self.k.as_buf(),
+            self.eo0.read(),
+            self.eo1.read(),
+            self.eo2.read(),
+            self.eo3.read(),
+            self.ecc.state(),
+        )
+    }
+}
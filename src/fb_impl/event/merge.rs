@@ -0,0 +1,54 @@
+use std::sync::Arc;
+
+use crate::fb::{
+    Bfb,
+    direction::{In, Out},
+    ecc::{Condition, EcAction, Ecc, ExecutionControl},
+    event::{Event, ty::Signal},
+};
+
+#[allow(non_camel_case_types)]
+#[derive(Default, Debug, Bfb)]
+pub struct E_MERGE {
+    instance_name: String,
+    #[ecc]
+    ecc: ExecutionControl<Self>,
+    ei1: Event<In, Signal>,
+    ei2: Event<In, Signal>,
+    eo: Event<Out, Signal>,
+}
+
+impl E_MERGE {
+    pub fn new(instance_name: &str) -> Self {
+        Self {
+            instance_name: instance_name.to_string(),
+            ecc: ExecutionControl::new(Arc::new(Self::chart())),
+            ..Default::default()
+        }
+    }
+
+    fn chart() -> Ecc<Self> {
+        Ecc::builder()
+            .state("START", vec![])
+            .state("EO", vec![EcAction::output("eo")])
+            .transition("START", "EO", Condition::event("ei1"))
+            .transition("START", "EO", Condition::event("ei2"))
+            .transition("EO", "START", Condition::always())
+            .build()
+            .expect("E_MERGE chart is valid")
+    }
+}
+
+impl std::fmt::Display for E_MERGE {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}={{ei1={}, ei2={}, eo={}, state={}}}",
+            self.instance_name,
+            self.ei1.read(),
+            self.ei2.read(),
+            self.eo.read(),
+            self.ecc.state(),
+        )
+    }
+}
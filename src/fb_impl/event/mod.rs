@@ -1,11 +1,19 @@
+pub mod ctd;
 pub mod ctu;
+pub mod ctud;
+pub mod cycle;
+pub mod d_ff;
+pub mod delay;
+pub mod demux;
+pub mod f_trig;
+pub mod merge;
+pub mod permit;
+pub mod r_trig;
+pub mod rend;
+pub mod restart;
+pub mod rs;
+pub mod select;
+pub mod split;
 pub mod sr;
 pub mod switch;
-
-const DEBUG: bool = true;
-
-pub fn dbg_state_print(name: &'static str, state: &'static str) {
-    if DEBUG {
-        println!("{name}: {state}");
-    }
-}
+pub mod train;
@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use crate::fb::{
+    Bfb,
+    data::{Data, ty::Bool},
+    direction::{In, Out},
+    ecc::{Condition, EcAction, Ecc, ExecutionControl},
+    event::{Event, ty::Signal},
+};
+
+#[allow(non_camel_case_types)]
+#[derive(Default, Debug, Bfb)]
+pub struct E_PERMIT {
+    instance_name: String,
+    #[ecc]
+    ecc: ExecutionControl<Self>,
+    #[with(permit)]
+    ei: Event<In, Signal>,
+    eo: Event<Out, Signal>,
+    permit: Data<In, Bool>,
+}
+
+impl E_PERMIT {
+    pub fn new(instance_name: &str) -> Self {
+        Self {
+            instance_name: instance_name.to_string(),
+            ecc: ExecutionControl::new(Arc::new(Self::chart())),
+            ..Default::default()
+        }
+    }
+
+    fn chart() -> Ecc<Self> {
+        Ecc::builder()
+            .state("START", vec![])
+            .state("EO", vec![EcAction::output("eo")])
+            .transition(
+                "START",
+                "EO",
+                Condition::event("ei").with_guard("PERMIT", |fb: &Self| fb.permit.read()),
+            )
+            .transition("EO", "START", Condition::always())
+            .build()
+            .expect("E_PERMIT chart is valid")
+    }
+}
+
+impl std::fmt::Display for E_PERMIT {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}={{ei={}, permit={}, eo={}, state={}}}",
+            self.instance_name,
+            self.ei.read(),
+            self.permit.as_buf(),
+            self.eo.read(),
+            self.ecc.state(),
+        )
+    }
+}
@@ -0,0 +1,66 @@
+use std::sync::Arc;
+
+use crate::fb::{
+    Bfb,
+    data::{Data, ty::Bool},
+    direction::{In, Out},
+    ecc::{Condition, EcAction, Ecc, ExecutionControl},
+    event::{Event, ty::Signal},
+};
+
+/// emits `eo` when `ei` samples a rising edge of `qi`
+#[allow(non_camel_case_types)]
+#[derive(Default, Debug, Bfb)]
+pub struct E_R_TRIG {
+    instance_name: String,
+    #[ecc]
+    ecc: ExecutionControl<Self>,
+    #[with(qi)]
+    ei: Event<In, Signal>,
+    eo: Event<Out, Signal>,
+    qi: Data<In, Bool>,
+}
+
+impl E_R_TRIG {
+    pub fn new(instance_name: &str) -> Self {
+        Self {
+            instance_name: instance_name.to_string(),
+            ecc: ExecutionControl::new(Arc::new(Self::chart())),
+            ..Default::default()
+        }
+    }
+
+    fn chart() -> Ecc<Self> {
+        Ecc::builder()
+            .state("Q0", vec![])
+            .state("EO", vec![EcAction::output("eo")])
+            .state("Q1", vec![])
+            .transition(
+                "Q0",
+                "EO",
+                Condition::event("ei").with_guard("QI", |fb: &Self| fb.qi.read()),
+            )
+            .transition("EO", "Q1", Condition::always())
+            .transition(
+                "Q1",
+                "Q0",
+                Condition::event("ei").with_guard("NOT QI", |fb: &Self| !fb.qi.read()),
+            )
+            .build()
+            .expect("E_R_TRIG chart is valid")
+    }
+}
+
+impl std::fmt::Display for E_R_TRIG {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}={{ei={}, qi={}, eo={}, state={}}}",
+            self.instance_name,
+            self.ei.read(),
+            self.qi.as_buf(),
+            self.eo.read(),
+            self.ecc.state(),
+        )
+    }
+}
@@ -0,0 +1,63 @@
+use std::sync::Arc;
+
+use crate::fb::{
+    Bfb,
+    direction::{In, Out},
+    ecc::{Condition, EcAction, Ecc, ExecutionControl},
+    event::{Event, ty::Signal},
+};
+
+/// rendezvous of `ei1` and `ei2`: emits `eo` once both arrived, in any order, `r` forgets an arrived event
+#[allow(non_camel_case_types)]
+#[derive(Default, Debug, Bfb)]
+pub struct E_REND {
+    instance_name: String,
+    #[ecc]
+    ecc: ExecutionControl<Self>,
+    ei1: Event<In, Signal>,
+    ei2: Event<In, Signal>,
+    r: Event<In, Signal>,
+    eo: Event<Out, Signal>,
+}
+
+impl E_REND {
+    pub fn new(instance_name: &str) -> Self {
+        Self {
+            instance_name: instance_name.to_string(),
+            ecc: ExecutionControl::new(Arc::new(Self::chart())),
+            ..Default::default()
+        }
+    }
+
+    fn chart() -> Ecc<Self> {
+        Ecc::builder()
+            .state("START", vec![])
+            .state("EI1", vec![])
+            .state("EI2", vec![])
+            .state("EO", vec![EcAction::output("eo")])
+            .transition("START", "EI1", Condition::event("ei1"))
+            .transition("START", "EI2", Condition::event("ei2"))
+            .transition("EI1", "EO", Condition::event("ei2"))
+            .transition("EI1", "START", Condition::event("r"))
+            .transition("EI2", "EO", Condition::event("ei1"))
+            .transition("EI2", "START", Condition::event("r"))
+            .transition("EO", "START", Condition::always())
+            .build()
+            .expect("E_REND chart is valid")
+    }
+}
+
+impl std::fmt::Display for E_REND {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}={{ei1={}, ei2={}, r={}, eo={}, state={}}}",
+            self.instance_name,
+            self.ei1.read(),
+            self.ei2.read(),
+            self.r.read(),
+            self.eo.read(),
+            self.ecc.state(),
+        )
+    }
+}
@@ -0,0 +1,78 @@
+use std::{sync::Arc, time::Duration};
+
+use crate::{
+    fb::{
+        Bfb,
+        direction::Out,
+        ecc::{Condition, EcAction, Ecc, ExecutionControl},
+        event::{Event, ty::Signal},
+    },
+    run_time::clock::Timer,
+};
+
+/// emits `cold` the first time the runtime polls its timers after the function block was created,
+/// the runtimes neither perform warm restarts nor shut down resources yet, so `warm` and `stop` are never emitted
+#[allow(non_camel_case_types)]
+#[derive(Default, Debug, Bfb)]
+pub struct E_RESTART {
+    instance_name: String,
+    #[ecc]
+    ecc: ExecutionControl<Self>,
+    #[timer]
+    timer: Timer,
+    cold: Event<Out, Signal>,
+    warm: Event<Out, Signal>,
+    stop: Event<Out, Signal>,
+}
+
+impl E_RESTART {
+    pub fn new(instance_name: &str) -> Self {
+        let mut timer = Timer::default();
+        timer.start(Duration::ZERO);
+
+        Self {
+            instance_name: instance_name.to_string(),
+            ecc: ExecutionControl::new(Arc::new(Self::chart())),
+            timer,
+            ..Default::default()
+        }
+    }
+
+    fn chart() -> Ecc<Self> {
+        Ecc::builder()
+            .state("START", vec![])
+            .state(
+                "COLD",
+                vec![EcAction::algorithm("STARTED", Self::started_algorithm).then_output("cold")],
+            )
+            .state("RUN", vec![])
+            .transition(
+                "START",
+                "COLD",
+                Condition::guard("STARTUP", |fb: &Self| fb.timer.expired()),
+            )
+            .transition("COLD", "RUN", Condition::always())
+            .build()
+            .expect("E_RESTART chart is valid")
+    }
+}
+
+impl E_RESTART {
+    fn started_algorithm(&mut self) {
+        self.timer.stop();
+    }
+}
+
+impl std::fmt::Display for E_RESTART {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}={{cold={}, warm={}, stop={}, state={}}}",
+            self.instance_name,
+            self.cold.read(),
+            self.warm.read(),
+            self.stop.read(),
+            self.ecc.state(),
+        )
+    }
+}
@@ -0,0 +1,77 @@
+use std::sync::Arc;
+
+use crate::fb::{
+    Bfb,
+    data::{Data, ty::Bool},
+    direction::{In, Out},
+    ecc::{Condition, EcAction, Ecc, ExecutionControl},
+    event::{Event, ty::Signal},
+};
+
+/// reset dominant counterpart of `E_SR`, a reset in the initial state also emits `eo`
+#[allow(non_camel_case_types)]
+#[derive(Default, Debug, Bfb)]
+pub struct E_RS {
+    instance_name: String,
+    #[ecc]
+    ecc: ExecutionControl<Self>,
+    r: Event<In, Signal>,
+    s: Event<In, Signal>,
+    #[with(q)]
+    eo: Event<Out, Signal>,
+    q: Data<Out, Bool>,
+}
+
+impl E_RS {
+    pub fn new(instance_name: &str) -> Self {
+        Self {
+            instance_name: instance_name.to_string(),
+            ecc: ExecutionControl::new(Arc::new(Self::chart())),
+            ..Default::default()
+        }
+    }
+
+    fn chart() -> Ecc<Self> {
+        Ecc::builder()
+            .state("Q0", vec![])
+            .state(
+                "RESET",
+                vec![EcAction::algorithm("RESET", Self::reset_algorithm).then_output("eo")],
+            )
+            .state(
+                "SET",
+                vec![EcAction::algorithm("SET", Self::set_algorithm).then_output("eo")],
+            )
+            .transition("Q0", "RESET", Condition::event("r"))
+            .transition("Q0", "SET", Condition::event("s"))
+            .transition("SET", "RESET", Condition::event("r"))
+            .transition("RESET", "SET", Condition::event("s"))
+            .build()
+            .expect("E_RS chart is valid")
+    }
+}
+
+impl E_RS {
+    fn set_algorithm(&mut self) {
+        self.q.write(true);
+    }
+
+    fn reset_algorithm(&mut self) {
+        self.q.write(false);
+    }
+}
+
+impl std::fmt::Display for E_RS {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}={{r={}, s={}, eo={}, q={}, state={}}}",
+            self.instance_name,
+            self.r.read(),
+            self.s.read(),
+            self.eo.read(),
+            self.q.as_buf(),
+            self.ecc.state(),
+        )
+    }
+}
@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use crate::fb::{
+    Bfb,
+    data::{Data, ty::Bool},
+    direction::{In, Out},
+    ecc::{Condition, EcAction, Ecc, ExecutionControl},
+    event::{Event, ty::Signal},
+};
+
+/// forwards `ei0` if `g` is FALSE and `ei1` if `g` is TRUE
+#[allow(non_camel_case_types)]
+#[derive(Default, Debug, Bfb)]
+pub struct E_SELECT {
+    instance_name: String,
+    #[ecc]
+    ecc: ExecutionControl<Self>,
+    #[with(g)]
+    ei0: Event<In, Signal>,
+    #[with(g)]
+    ei1: Event<In, Signal>,
+    eo: Event<Out, Signal>,
+    g: Data<In, Bool>,
+}
+
+impl E_SELECT {
+    pub fn new(instance_name: &str) -> Self {
+        Self {
+            instance_name: instance_name.to_string(),
+            ecc: ExecutionControl::new(Arc::new(Self::chart())),
+            ..Default::default()
+        }
+    }
+
+    fn chart() -> Ecc<Self> {
+        Ecc::builder()
+            .state("START", vec![])
+            .state("EO", vec![EcAction::output("eo")])
+            .transition(
+                "START",
+                "EO",
+                Condition::event("ei0").with_guard("NOT G", |fb: &Self| !fb.g.read()),
+            )
+            .transition(
+                "START",
+                "EO",
+                Condition::event("ei1").with_guard("G", |fb: &Self| fb.g.read()),
+            )
+            .transition("EO", "START", Condition::always())
+            .build()
+            .expect("E_SELECT chart is valid")
+    }
+}
+
+impl std::fmt::Display for E_SELECT {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}={{ei0={}, ei1={}, g={}, eo={}, state={}}}",
+            self.instance_name,
+            self.ei0.read(),
+            self.ei1.read(),
+            self.g.as_buf(),
+            self.eo.read(),
+            self.ecc.state(),
+        )
+    }
+}
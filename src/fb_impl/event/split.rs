@@ -0,0 +1,53 @@
+use std::sync::Arc;
+
+use crate::fb::{
+    Bfb,
+    direction::{In, Out},
+    ecc::{Condition, EcAction, Ecc, ExecutionControl},
+    event::{Event, ty::Signal},
+};
+
+#[allow(non_camel_case_types)]
+#[derive(Default, Debug, Bfb)]
+pub struct E_SPLIT {
+    instance_name: String,
+    #[ecc]
+    ecc: ExecutionControl<Self>,
+    ei: Event<In, Signal>,
+    eo1: Event<Out, Signal>,
+    eo2: Event<Out, Signal>,
+}
+
+impl E_SPLIT {
+    pub fn new(instance_name: &str) -> Self {
+        Self {
+            instance_name: instance_name.to_string(),
+            ecc: ExecutionControl::new(Arc::new(Self::chart())),
+            ..Default::default()
+        }
+    }
+
+    fn chart() -> Ecc<Self> {
+        Ecc::builder()
+            .state("START", vec![])
+            .state("EO", vec![EcAction::output("eo1"), EcAction::output("eo2")])
+            .transition("START", "EO", Condition::event("ei"))
+            .transition("EO", "START", Condition::always())
+            .build()
+            .expect("E_SPLIT chart is valid")
+    }
+}
+
+impl std::fmt::Display for E_SPLIT {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}={{ei={}, eo1={}, eo2={}, state={}}}",
+            self.instance_name,
+            self.ei.read(),
+            self.eo1.read(),
+            self.eo2.read(),
+            self.ecc.state(),
+        )
+    }
+}
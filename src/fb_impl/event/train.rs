@@ -0,0 +1,124 @@
+use std::sync::Arc;
+
+use crate::{
+    fb::{
+        Bfb,
+        data::{
+            Data,
+            ty::{Time, UInt},
+        },
+        direction::{In, Out},
+        ecc::{Condition, EcAction, Ecc, ExecutionControl},
+        event::{Event, ty::Signal},
+    },
+    run_time::clock::Timer,
+};
+
+/// emits `eo` `n` times every `dt` after `start`, `cv` counts the emitted events
+#[allow(non_camel_case_types)]
+#[derive(Default, Debug, Bfb)]
+pub struct E_TRAIN {
+    instance_name: String,
+    #[ecc]
+    ecc: ExecutionControl<Self>,
+    #[timer]
+    timer: Timer,
+    #[with(dt, n)]
+    start: Event<In, Signal>,
+    stop: Event<In, Signal>,
+    #[with(cv)]
+    eo: Event<Out, Signal>,
+    dt: Data<In, Time>,
+    n: Data<In, UInt>,
+    cv: Data<Out, UInt>,
+}
+
+impl E_TRAIN {
+    pub fn new(instance_name: &str) -> Self {
+        Self {
+            instance_name: instance_name.to_string(),
+            ecc: ExecutionControl::new(Arc::new(Self::chart())),
+            ..Default::default()
+        }
+    }
+
+    fn chart() -> Ecc<Self> {
+        Ecc::builder()
+            .state("START", vec![])
+            .state(
+                "ARM",
+                vec![EcAction::algorithm("START", Self::start_algorithm)],
+            )
+            .state("ACTIVE", vec![])
+            .state(
+                "EO",
+                vec![EcAction::algorithm("COUNT", Self::count_algorithm).then_output("eo")],
+            )
+            .state(
+                "STOP",
+                vec![EcAction::algorithm("STOP", Self::stop_algorithm)],
+            )
+            .transition("START", "ARM", Condition::event("start"))
+            .transition(
+                "ARM",
+                "STOP",
+                Condition::guard("N = 0", |fb: &Self| fb.n.read() == 0),
+            )
+            .transition("ARM", "ACTIVE", Condition::always())
+            .transition("ACTIVE", "STOP", Condition::event("stop"))
+            .transition(
+                "ACTIVE",
+                "EO",
+                Condition::guard("TIMEOUT", |fb: &Self| fb.timer.expired()),
+            )
+            .transition(
+                "EO",
+                "ACTIVE",
+                Condition::guard("CV < N", |fb: &Self| fb.cv.read() < fb.n.read()),
+            )
+            .transition("EO", "START", Condition::always())
+            .transition("STOP", "START", Condition::always())
+            .build()
+            .expect("E_TRAIN chart is valid")
+    }
+}
+
+impl E_TRAIN {
+    fn start_algorithm(&mut self) {
+        self.cv.write(0);
+        self.timer.start(self.dt.read());
+    }
+
+    /// the next event is scheduled relative to the deadline of the previous one
+    fn count_algorithm(&mut self) {
+        self.cv.write(self.cv.read() + 1);
+
+        if self.cv.read() < self.n.read() {
+            self.timer.restart(self.dt.read());
+        } else {
+            self.timer.stop();
+        }
+    }
+
+    fn stop_algorithm(&mut self) {
+        self.timer.stop();
+    }
+}
+
+impl std::fmt::Display for E_TRAIN {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}={{start={}, stop={}, eo={}, dt={}, n={}, cv={}, deadline={}, state={}}}",
+            self.instance_name,
+            self.start.read(),
+            self.stop.read(),
+            self.eo.read(),
+            self.dt.as_buf(),
+            self.n.as_buf(),
+            self.cv.as_buf(),
+            self.timer,
+            self.ecc.state(),
+        )
+    }
+}
@@ -0,0 +1,595 @@
+//! Basic function block whose type is only known at runtime.
+//!
+//! A `GenericType` is prepared once from a type description (e.g. parsed from a `.fbt` file):
+//! port names are lowercased, the ST algorithms and guards are parsed,
+//! and the ECC is built as a chart shared by all instances.
+//! Each `GenericBfb` instance stores its in-, output and internal variables as `DataBuffer`s.
+
+use std::{collections::HashMap, fmt::Display, sync::Arc};
+
+use crate::{
+    fb::{
+        Bfb,
+        data::{comm::DataBuffer, ty::DataKind},
+        desc::{FbBodyDesc, FbTypeDesc, TypeInterface, VarDesc},
+        ecc::{self, Condition, EcAction, Ecc, EccDesc, EccError, EccHost, ExecutionControl},
+        error::FbError,
+        registry::TypeRegistry,
+    },
+    st::{Expression, Program, StError, Variables},
+};
+
+#[derive(Clone, Debug)]
+pub enum GenericTypeError {
+    /// only basic function block types can be interpreted
+    NotBasic(String),
+    UnsupportedLanguage {
+        algorithm: String,
+        language: String,
+    },
+    Algorithm {
+        algorithm: String,
+        error: StError,
+    },
+    /// an EC action references an algorithm that does not exist
+    UnknownAlgorithm(String),
+    Guard {
+        guard: String,
+        error: StError,
+    },
+    InitialValue {
+        var: String,
+        value: String,
+        kind: DataKind,
+    },
+    Ecc(EccError),
+}
+
+impl Display for GenericTypeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GenericTypeError::NotBasic(body) => {
+                write!(f, "{body} function block types can not be interpreted")
+            }
+            GenericTypeError::UnsupportedLanguage {
+                algorithm,
+                language,
+            } => write!(
+                f,
+                "algorithm \"{algorithm}\" is written in unsupported language \"{language}\""
+            ),
+            GenericTypeError::Algorithm { algorithm, error } => {
+                write!(f, "algorithm \"{algorithm}\": {error}")
+            }
+            GenericTypeError::UnknownAlgorithm(name) => write!(f, "unknown algorithm \"{name}\""),
+            GenericTypeError::Guard { guard, error } => write!(f, "guard \"{guard}\": {error}"),
+            GenericTypeError::InitialValue { var, value, kind } => write!(
+                f,
+                "invalid initial value \"{value}\" of \"{var}\" ({})",
+                kind.iec_name()
+            ),
+            GenericTypeError::Ecc(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for GenericTypeError {}
+
+/// an event port with its lowercase name and WITH associations
+#[derive(Debug)]
+struct EventPort {
+    name: String,
+    with: Vec<String>,
+}
+
+/// a variable with its lowercase name, kind and initial value
+#[derive(Debug)]
+struct Var {
+    name: String,
+    kind: DataKind,
+    initial: DataBuffer,
+}
+
+/// a basic function block type prepared for instantiation
+pub struct GenericType {
+    desc: FbTypeDesc,
+    event_inputs: Vec<EventPort>,
+    event_outputs: Vec<EventPort>,
+    input_vars: Vec<Var>,
+    output_vars: Vec<Var>,
+    internal_vars: Vec<Var>,
+    chart: Arc<Ecc<GenericBfb>>,
+}
+
+impl GenericType {
+    pub fn new(desc: FbTypeDesc) -> Result<Arc<Self>, GenericTypeError> {
+        let basic = match &desc.body {
+            FbBodyDesc::Basic(basic) => basic,
+            FbBodyDesc::Other(body) => return Err(GenericTypeError::NotBasic(body.clone())),
+        };
+
+        let events = |events: &[crate::fb::desc::EventDesc]| {
+            events
+                .iter()
+                .map(|e| EventPort {
+                    name: e.name.to_lowercase(),
+                    with: e.with.iter().map(|w| w.to_lowercase()).collect(),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let mut algorithms = HashMap::new();
+
+        for alg in &basic.algorithms {
+            if !alg.language.eq_ignore_ascii_case("ST") {
+                return Err(GenericTypeError::UnsupportedLanguage {
+                    algorithm: alg.name.clone(),
+                    language: alg.language.clone(),
+                });
+            }
+
+            let program =
+                Program::parse(&alg.text).map_err(|error| GenericTypeError::Algorithm {
+                    algorithm: alg.name.clone(),
+                    error,
+                })?;
+
+            algorithms.insert(alg.name.clone(), Arc::new(program));
+        }
+
+        Ok(Arc::new(Self {
+            event_inputs: events(&desc.interface.event_inputs),
+            event_outputs: events(&desc.interface.event_outputs),
+            input_vars: vars(&desc.interface.input_vars)?,
+            output_vars: vars(&desc.interface.output_vars)?,
+            internal_vars: vars(&basic.internal_vars)?,
+            chart: Arc::new(chart(&basic.ecc, &algorithms)?),
+            desc,
+        }))
+    }
+
+    pub fn desc(&self) -> &FbTypeDesc {
+        &self.desc
+    }
+
+    pub fn instantiate(self: &Arc<Self>, instance_name: &str) -> GenericBfb {
+        let vars = self
+            .input_vars
+            .iter()
+            .chain(&self.output_vars)
+            .chain(&self.internal_vars)
+            .map(|v| (v.name.clone(), v.initial.clone()))
+            .collect();
+
+        GenericBfb {
+            instance_name: instance_name.to_string(),
+            ty: Arc::clone(self),
+            ecc: ExecutionControl::new(Arc::clone(&self.chart)),
+            event_in: None,
+            events_out: vec![false; self.event_outputs.len()],
+            vars,
+        }
+    }
+
+    /// registers the type under its name, so instances can be created by the runtime
+    pub fn register(self: &Arc<Self>, registry: &mut TypeRegistry) {
+        let ty = Arc::clone(self);
+
+        registry.register(&self.desc.name, move |name| Box::new(ty.instantiate(name)));
+    }
+
+    fn var(&self, name: &str) -> Option<&Var> {
+        self.input_vars
+            .iter()
+            .chain(&self.output_vars)
+            .chain(&self.internal_vars)
+            .find(|v| v.name == name)
+    }
+}
+
+impl std::fmt::Debug for GenericType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "GenericType({})", self.desc.name)
+    }
+}
+
+fn vars(vars: &[VarDesc]) -> Result<Vec<Var>, GenericTypeError> {
+    vars.iter()
+        .map(|v| {
+            let initial = match &v.initial_value {
+                Some(value) => DataBuffer::parse_literal(&v.kind, value).ok_or_else(|| {
+                    GenericTypeError::InitialValue {
+                        var: v.name.clone(),
+                        value: value.clone(),
+                        kind: v.kind.clone(),
+                    }
+                })?,
+                None => DataBuffer::default_for(&v.kind),
+            };
+
+            Ok(Var {
+                name: v.name.to_lowercase(),
+                kind: v.kind.clone(),
+                initial,
+            })
+        })
+        .collect()
+}
+
+/// builds the chart from its description, algorithms and guards are interpreted
+fn chart(
+    desc: &EccDesc,
+    algorithms: &HashMap<String, Arc<Program>>,
+) -> Result<Ecc<GenericBfb>, GenericTypeError> {
+    let mut builder = Ecc::builder();
+
+    for state in &desc.states {
+        let mut actions = vec![];
+
+        for action in &state.actions {
+            let ec_action = match &action.algorithm {
+                Some(name) => {
+                    let Some(program) = algorithms.get(name).cloned() else {
+                        return Err(GenericTypeError::UnknownAlgorithm(name.clone()));
+                    };
+                    let alg_name = name.clone();
+
+                    let ec_action = EcAction::algorithm(name, move |fb: &mut GenericBfb| {
+                        if let Err(e) = program.execute(fb) {
+                            println!("[error st]: {}: {alg_name}: {e}", fb.instance_name);
+                        }
+                    });
+
+                    match &action.output {
+                        Some(output) => ec_action.then_output(&output.to_lowercase()),
+                        None => ec_action,
+                    }
+                }
+                None => match &action.output {
+                    Some(output) => EcAction::output(&output.to_lowercase()),
+                    None => continue,
+                },
+            };
+
+            actions.push(ec_action);
+        }
+
+        builder = builder.state(&state.name, actions);
+    }
+
+    for transition in &desc.transitions {
+        let condition = match &transition.event {
+            Some(event) => Condition::event(&event.to_lowercase()),
+            None => Condition::always(),
+        };
+
+        let condition = match &transition.guard {
+            Some(text) => {
+                let guard = Expression::parse(text).map_err(|error| GenericTypeError::Guard {
+                    guard: text.clone(),
+                    error,
+                })?;
+
+                condition.with_guard(text, move |fb: &GenericBfb| {
+                    guard.evaluate_bool(fb).unwrap_or_else(|e| {
+                        println!("[error st]: {}: guard: {e}", fb.instance_name);
+                        false
+                    })
+                })
+            }
+            None => condition,
+        };
+
+        builder = builder.transition(&transition.source, &transition.destination, condition);
+    }
+
+    builder.build().map_err(GenericTypeError::Ecc)
+}
+
+/// basic function block interpreting a `GenericType`
+#[derive(Debug)]
+pub struct GenericBfb {
+    instance_name: String,
+    ty: Arc<GenericType>,
+    ecc: ExecutionControl<Self>,
+    /// index of the pending input event
+    event_in: Option<usize>,
+    events_out: Vec<bool>,
+    vars: HashMap<String, DataBuffer>,
+}
+
+impl GenericBfb {
+    pub fn ty(&self) -> &Arc<GenericType> {
+        &self.ty
+    }
+
+    /// returns the declared kind of a variable, generic variables (`ANY_NUM`, ...) return the kind they are fixed to
+    fn kind(&self, var: &Var) -> DataKind {
+        match (
+            &var.kind,
+            self.vars.get(&var.name).and_then(DataBuffer::kind),
+        ) {
+            (DataKind::Generic(_), Some(fixed)) => fixed,
+            (kind, _) => kind.clone(),
+        }
+    }
+
+    fn event(&self, event: &str) -> Option<&EventPort> {
+        let event = event.to_lowercase();
+
+        self.ty
+            .event_inputs
+            .iter()
+            .chain(&self.ty.event_outputs)
+            .find(|e| e.name == event)
+    }
+}
+
+impl Bfb for GenericBfb {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn instance_name(&self) -> &str {
+        &self.instance_name
+    }
+
+    fn type_name(&self) -> &str {
+        &self.ty.desc.name
+    }
+
+    fn interface(&self) -> TypeInterface {
+        TypeInterface {
+            type_name: self.ty.desc.name.clone(),
+            comment: self.ty.desc.comment.clone(),
+            interface: self.ty.desc.interface.clone(),
+        }
+    }
+
+    fn event_inputs(&self) -> Vec<&str> {
+        self.ty
+            .event_inputs
+            .iter()
+            .map(|e| e.name.as_str())
+            .collect()
+    }
+
+    fn event_outputs(&self) -> Vec<&str> {
+        self.ty
+            .event_outputs
+            .iter()
+            .map(|e| e.name.as_str())
+            .collect()
+    }
+
+    fn data_inputs(&self) -> Vec<&str> {
+        self.ty.input_vars.iter().map(|v| v.name.as_str()).collect()
+    }
+
+    fn data_outputs(&self) -> Vec<&str> {
+        self.ty
+            .output_vars
+            .iter()
+            .map(|v| v.name.as_str())
+            .collect()
+    }
+
+    fn data_kind(&self, data: &str) -> Result<DataKind, FbError> {
+        let name = data.to_lowercase();
+
+        self.ty
+            .input_vars
+            .iter()
+            .chain(&self.ty.output_vars)
+            .find(|v| v.name == name)
+            .map(|v| self.kind(v))
+            .ok_or_else(|| FbError::unknown_data(data))
+    }
+
+    fn fix_data_kind(&mut self, data: &str, kind: &DataKind) -> Result<(), FbError> {
+        let name = data.to_lowercase();
+        let Some(var) = self
+            .ty
+            .input_vars
+            .iter()
+            .chain(&self.ty.output_vars)
+            .find(|v| v.name == name)
+        else {
+            return Err(FbError::unknown_data(data));
+        };
+
+        match self.kind(var) {
+            DataKind::Generic(generic) if generic.contains(kind) => {
+                self.vars
+                    .insert(var.name.clone(), DataBuffer::default_for(kind));
+                Ok(())
+            }
+            fixed if fixed == *kind => Ok(()),
+            fixed => Err(FbError::type_mismatch(
+                data,
+                fixed,
+                &DataBuffer::default_for(kind),
+            )),
+        }
+    }
+
+    fn set_event_in(&mut self, event: &str) -> Result<(), FbError> {
+        let name = event.to_lowercase();
+
+        if let Some(index) = self.ty.event_inputs.iter().position(|e| e.name == name) {
+            if let Some(pending) = self.active_event_in() {
+                return Err(FbError::event_already_pending(event, pending));
+            }
+
+            self.event_in = Some(index);
+            return Ok(());
+        }
+
+        if self.ty.event_outputs.iter().any(|e| e.name == name) {
+            return Err(FbError::wrong_direction(event));
+        }
+
+        Err(FbError::unknown_event(event))
+    }
+
+    fn active_event_in(&self) -> Option<&str> {
+        self.event_in.map(|i| self.ty.event_inputs[i].name.as_str())
+    }
+
+    fn active_event_out(&self) -> Option<&str> {
+        self.events_out
+            .iter()
+            .rposition(|active| *active)
+            .map(|i| self.ty.event_outputs[i].name.as_str())
+    }
+
+    fn active_events_out(&self) -> Vec<&str> {
+        self.ty
+            .event_outputs
+            .iter()
+            .zip(&self.events_out)
+            .filter(|(_, active)| **active)
+            .map(|(e, _)| e.name.as_str())
+            .collect()
+    }
+
+    fn clear_event_out(&mut self) {
+        self.events_out.fill(false);
+    }
+
+    fn with_for_event(&self, event: &str) -> Result<Vec<&str>, FbError> {
+        self.event(event)
+            .map(|e| e.with.iter().map(String::as_str).collect())
+            .ok_or_else(|| FbError::unknown_event(event))
+    }
+
+    fn read_data_out(&self, data: &str) -> Result<DataBuffer, FbError> {
+        let name = data.to_lowercase();
+
+        if self.ty.output_vars.iter().any(|v| v.name == name) {
+            return Ok(self.vars[name.as_str()].clone());
+        }
+
+        if self.ty.input_vars.iter().any(|v| v.name == name) {
+            return Err(FbError::wrong_direction(data));
+        }
+
+        Err(FbError::unknown_data(data))
+    }
+
+    fn write_data_in(&mut self, data: &str, buf: &DataBuffer) -> Result<(), FbError> {
+        let name = data.to_lowercase();
+
+        if self.ty.input_vars.iter().any(|v| v.name == name) {
+            return self.write(&name, buf);
+        }
+
+        if self.ty.output_vars.iter().any(|v| v.name == name) {
+            return Err(FbError::wrong_direction(data));
+        }
+
+        Err(FbError::unknown_data(data))
+    }
+
+    fn invoke_execution_control(&mut self) -> Result<bool, FbError> {
+        ecc::invoke(self)
+    }
+
+    fn ecc(&self) -> Option<EccDesc> {
+        Some(self.ecc.chart().describe())
+    }
+
+    fn ec_state(&self) -> Option<&str> {
+        Some(self.ecc.state())
+    }
+}
+
+impl EccHost for GenericBfb {
+    fn execution_control(&self) -> &ExecutionControl<Self> {
+        &self.ecc
+    }
+
+    fn execution_control_mut(&mut self) -> &mut ExecutionControl<Self> {
+        &mut self.ecc
+    }
+
+    fn clear_event_in(&mut self) {
+        self.event_in = None;
+    }
+
+    fn send_event_out(&mut self, event: &str) -> Result<(), FbError> {
+        if let Some(index) = self.ty.event_outputs.iter().position(|e| e.name == event) {
+            self.events_out[index] = true;
+            return Ok(());
+        }
+
+        if self.ty.event_inputs.iter().any(|e| e.name == event) {
+            return Err(FbError::wrong_direction(event));
+        }
+
+        Err(FbError::unknown_event(event))
+    }
+}
+
+/// in-, output and internal variables, accessible to algorithms and guards
+impl Variables for GenericBfb {
+    fn read(&self, name: &str) -> Option<DataBuffer> {
+        self.vars.get(name).cloned()
+    }
+
+    fn write(&mut self, name: &str, value: &DataBuffer) -> Result<(), FbError> {
+        let Some(var) = self.ty.var(name) else {
+            return Err(FbError::unknown_data(name));
+        };
+
+        let accepted = match (self.kind(var), value.kind()) {
+            (DataKind::Generic(generic), Some(kind)) => generic.contains(&kind),
+            (kind, value) => value == Some(kind),
+        };
+
+        if !accepted {
+            return Err(FbError::type_mismatch(name, self.kind(var), value));
+        }
+
+        self.vars.insert(var.name.clone(), value.clone());
+
+        Ok(())
+    }
+}
+
+impl Display for GenericBfb {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let events = self
+            .ty
+            .event_inputs
+            .iter()
+            .enumerate()
+            .map(|(i, e)| (&e.name, self.event_in == Some(i)))
+            .chain(
+                self.ty
+                    .event_outputs
+                    .iter()
+                    .zip(&self.events_out)
+                    .map(|(e, active)| (&e.name, *active)),
+            )
+            .map(|(name, active)| format!("{name}={active}"));
+
+        let vars = self
+            .ty
+            .input_vars
+            .iter()
+            .chain(&self.ty.output_vars)
+            .chain(&self.ty.internal_vars)
+            .map(|v| format!("{}={}", v.name, self.vars[&v.name]));
+
+        let fields: Vec<String> = events.chain(vars).collect();
+
+        write!(
+            f,
+            "{}={{{}, state={}}}",
+            self.instance_name,
+            fields.join(", "),
+            self.ecc.state()
+        )
+    }
+}
@@ -1,5 +1,63 @@
 //! Includes implementations of different function blocks:
 //! - `Voter`
+//! - event function blocks (`E_CTU`, `E_CTD`, `E_CTUD`, `E_SR`, `E_RS`, `E_D_FF`, `E_SWITCH`, `E_SELECT`, `E_DEMUX`,
+//!   `E_PERMIT`, `E_SPLIT`, `E_MERGE`, `E_REND`, `E_R_TRIG`, `E_F_TRIG`)
+//! - timed event function blocks (`E_DELAY`, `E_CYCLE`, `E_RESTART`, `E_TRAIN`)
+//! - `GenericBfb`, which interprets basic function block types loaded at runtime
+//! - `Cfb`, which encapsulates a network of other function blocks
+//! - conversion function blocks (`F_UINT_TO_DINT`, `F_REAL_TO_INT`, ...)
+//! - polymorphic arithmetic function blocks with generic data ports (`F_ADD`)
 
+use crate::fb::registry::TypeRegistry;
+
+pub mod arith;
+pub mod composite;
+pub mod convert;
 pub mod event;
+pub mod generic;
 pub mod voter;
+
+/// returns a registry containing all function block types implemented in this crate
+pub fn builtin_registry() -> TypeRegistry {
+    let mut registry = TypeRegistry::default();
+
+    registry.register("E_CTD", |name| Box::new(event::ctd::E_CTD::new(name)));
+    registry.register("E_CTU", |name| Box::new(event::ctu::E_CTU::new(name)));
+    registry.register("E_CTUD", |name| Box::new(event::ctud::E_CTUD::new(name)));
+    registry.register("E_CYCLE", |name| Box::new(event::cycle::E_CYCLE::new(name)));
+    registry.register("E_D_FF", |name| Box::new(event::d_ff::E_D_FF::new(name)));
+    registry.register("E_DELAY", |name| Box::new(event::delay::E_DELAY::new(name)));
+    registry.register("E_DEMUX", |name| Box::new(event::demux::E_DEMUX::new(name)));
+    registry.register("E_F_TRIG", |name| {
+        Box::new(event::f_trig::E_F_TRIG::new(name))
+    });
+    registry.register("E_MERGE", |name| Box::new(event::merge::E_MERGE::new(name)));
+    registry.register("E_PERMIT", |name| {
+        Box::new(event::permit::E_PERMIT::new(name))
+    });
+    registry.register("E_R_TRIG", |name| {
+        Box::new(event::r_trig::E_R_TRIG::new(name))
+    });
+    registry.register("E_REND", |name| Box::new(event::rend::E_REND::new(name)));
+    registry.register("E_RESTART", |name| {
+        Box::new(event::restart::E_RESTART::new(name))
+    });
+    registry.register("E_RS", |name| Box::new(event::rs::E_RS::new(name)));
+    registry.register("E_SELECT", |name| {
+        Box::new(event::select::E_SELECT::new(name))
+    });
+    registry.register("E_SPLIT", |name| Box::new(event::split::E_SPLIT::new(name)));
+    registry.register("E_SR", |name| Box::new(event::sr::E_SR::new(name)));
+    registry.register("E_SWITCH", |name| {
+        Box::new(event::switch::E_SWITCH::new(name))
+    });
+    registry.register("E_TRAIN", |name| Box::new(event::train::E_TRAIN::new(name)));
+    registry.register("F_ADD", |name| Box::new(arith::F_ADD::new(name)));
+    registry.register("VOTER", |name| {
+        Box::new(voter::dynamic_disp::Voter::new(name))
+    });
+
+    convert::register(&mut registry);
+
+    registry
+}
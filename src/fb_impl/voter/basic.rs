@@ -95,7 +95,7 @@ impl Voter {
 // sequences
 impl Voter {
     /// Definition in `IEC 61131-3 Structured Text`:
-    /// ```
+    /// ```text
     /// ALGORITHM VoteAlg IN ST:
     ///     State := (A AND B) OR (A AND C) OR (B AND C);
     /// END_ALGORITHM
@@ -107,7 +107,7 @@ impl Voter {
     }
 
     /// Definition in `IEC 61131-3 Structured Text`:
-    /// ```
+    /// ```text
     /// ALGORITHM ResetAlg IN ST:
     ///     State := FALSE;
     /// END_ALGORITHM
@@ -237,8 +237,8 @@ pub fn run_sequence(sequence: Sequence) {
 
 // -- printing ------------------------------------------------------------------------------------
 #[allow(clippy::from_over_into)]
-impl Into<VoterInformation> for &Voter {
-    fn into(self) -> VoterInformation {
+impl<'a> Into<VoterInformation<'a>> for &'a Voter {
+    fn into(self) -> VoterInformation<'a> {
         VoterInformation {
             ecc: self.ecc_state.as_str(),
             vote: if self.ein_vote {
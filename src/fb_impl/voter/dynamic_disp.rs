@@ -1,28 +1,30 @@
 //! Builds upon the `typed` concept, and provides implementation of a trait for dynamic dispatch.
 
+use std::sync::Arc;
+
 use crate::{
     cli,
     fb::{
         Bfb,
-        data::{
-            Data,
-            comm::DataBuffer,
-            ty::{Bool, DataKind},
-        },
+        data::{Data, ty::Bool},
         direction::{In, Out},
+        ecc::{Condition, EcAction, Ecc, ExecutionControl},
         event::{Event, ty::Signal},
     },
-    fb_impl::voter::util::VoterState,
 };
 
-// TODO: make instance_name into a String -> allows for hotloading down the line
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Bfb)]
 pub struct Voter {
-    instance_name: &'static str,
-    ec_state: VoterState,
+    instance_name: String,
+    #[ecc]
+    ecc: ExecutionControl<Self>,
+    #[with(a, b, c)]
     vote: Event<In, Signal>,
+    #[with(a, b, c)]
     reset: Event<In, Signal>,
+    #[with(state)]
     voted: Event<Out, Signal>,
+    #[with(state)]
     ready: Event<Out, Signal>,
     a: Data<In, Bool>,
     b: Data<In, Bool>,
@@ -31,12 +33,42 @@ pub struct Voter {
 }
 
 impl Voter {
-    pub fn new(instance_name: &'static str) -> Self {
+    pub fn new(instance_name: &str) -> Self {
         Self {
-            instance_name,
+            instance_name: instance_name.to_string(),
+            ecc: ExecutionControl::new(Arc::new(Self::chart())),
             ..Default::default()
         }
     }
+
+    fn chart() -> Ecc<Self> {
+        Ecc::builder()
+            .state("Ready", vec![])
+            .state(
+                "Vote",
+                vec![EcAction::algorithm("VoteAlg", Self::vote_algorithm).then_output("voted")],
+            )
+            .state("VotedPos", vec![])
+            .state(
+                "Reset",
+                vec![EcAction::algorithm("ResetAlg", Self::reset_algorithm).then_output("ready")],
+            )
+            .transition("Ready", "Vote", Condition::event("vote"))
+            .transition(
+                "Vote",
+                "VotedPos",
+                Condition::guard("State", |fb: &Self| fb.state.read()),
+            )
+            .transition(
+                "Vote",
+                "Ready",
+                Condition::guard("NOT State", |fb: &Self| !fb.state.read()),
+            )
+            .transition("VotedPos", "Reset", Condition::event("reset"))
+            .transition("Reset", "Ready", Condition::always())
+            .build()
+            .expect("VOTER chart is valid")
+    }
 }
 
 impl Voter {
@@ -58,154 +90,12 @@ impl Voter {
     }
 }
 
-impl Bfb for Voter {
-    fn as_any(&self) -> &dyn std::any::Any {
-        self
-    }
-
-    fn instance_name(&self) -> &'static str {
-        self.instance_name
-    }
-
-    fn invoke_execution_control(&mut self) -> bool {
-        let mut unstable = false;
-
-        match self.ec_state {
-            VoterState::Ready => {
-                if self.vote.read_and_reset() {
-                    self.enter(VoterState::Vote);
-                    unstable = true;
-                }
-            }
-            VoterState::Vote => {
-                if self.state.read() {
-                    self.enter(VoterState::VotedPos);
-                } else {
-                    self.enter(VoterState::Ready);
-                }
-                unstable = true;
-            }
-            VoterState::VotedPos => {
-                if self.reset.read_and_reset() {
-                    self.enter(VoterState::Reset);
-                    unstable = true;
-                }
-            }
-            VoterState::Reset => {
-                self.enter(VoterState::Ready);
-                unstable = true;
-            }
-        }
-
-        unstable
-    }
-
-    fn set_event_in(&mut self, event: &str) {
-        match event {
-            "vote" => self.vote.receive(),
-            "reset" => self.reset.receive(),
-            _ => panic!("unknown event {event}"), // return Error later
-        }
-    }
-
-    fn active_event_in(&self) -> Option<&'static str> {
-        let mut event = None;
-
-        if self.vote.read() {
-            event = Some("vote");
-        }
-
-        if self.reset.read() {
-            event = Some("reset");
-        }
-
-        event
-    }
-
-    fn active_event_out(&self) -> Option<&'static str> {
-        let mut event = None;
-
-        if self.voted.read() {
-            event = Some("voted");
-        }
-
-        if self.ready.read() {
-            event = Some("ready");
-        }
-
-        event
-    }
-
-    fn clear_event_out(&mut self) {
-        self.voted.reset();
-        self.ready.reset();
-    }
-
-    fn with_for_event(&self, event: &str) -> Vec<&'static str> {
-        match event {
-            "vote" | "reset" => vec!["a", "b", "c"],
-            "voted" | "ready" => vec!["state"],
-            _ => panic!("unknown event {event}"), // return Error later
-        }
-    }
-
-    fn read_data_out(&self, data: &str) -> DataBuffer {
-        match data {
-            "state" => self.state.as_buf(),
-            _ => panic!("unknown data {data}"),
-        }
-    }
-
-    fn write_data_in(&mut self, data: &str, buf: &DataBuffer) {
-        match (data, buf) {
-            ("a", DataBuffer::Bool(v)) => {
-                self.a.update(*v);
-            }
-            ("b", DataBuffer::Bool(v)) => {
-                self.b.update(*v);
-            }
-            ("c", DataBuffer::Bool(v)) => {
-                self.c.update(*v);
-            }
-            _ => panic!("unknown data {data} or invalid communication data variant {buf:?}"),
-        }
-    }
-
-    fn data_kind(&self, data: &str) -> DataKind {
-        match data {
-            "a" => self.a.as_kind(),
-            "b" => self.b.as_kind(),
-            "c" => self.c.as_kind(),
-            "state" => self.state.as_kind(),
-            _ => panic!("unknown data {data}"),
-        }
-    }
-}
-
-impl Voter {
-    fn enter(&mut self, state: VoterState) {
-        match state {
-            VoterState::Ready | VoterState::VotedPos => {}
-            VoterState::Vote => {
-                self.vote_algorithm();
-                self.voted.send();
-            }
-            VoterState::Reset => {
-                self.reset_algorithm();
-                self.ready.send();
-            }
-        }
-
-        self.ec_state = state;
-    }
-}
-
 // -- printing ------------------------------------------------------------------------------------
 #[allow(clippy::from_over_into)]
-impl Into<cli::output::VoterInformation> for &Voter {
-    fn into(self) -> cli::output::VoterInformation {
+impl<'a> Into<cli::output::VoterInformation<'a>> for &'a Voter {
+    fn into(self) -> cli::output::VoterInformation<'a> {
         cli::output::VoterInformation {
-            ecc: self.ec_state.as_str(),
+            ecc: self.ecc.state(),
             vote: if self.vote.read() {
                 "RECEIVED"
             } else {
@@ -236,7 +126,7 @@ impl Into<cli::output::VoterInformation> for &Voter {
 
 impl std::fmt::Display for Voter {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let buf = cli::output::voter_str_w_name(self.into(), self.instance_name);
+        let buf = cli::output::voter_str_w_name(self.into(), &self.instance_name);
         write!(f, "{buf}")
     }
 }
@@ -7,7 +7,7 @@ use core::fmt;
 use crate::{
     cli::{self, args::Sequence, output::VoterInformation},
     fb::{
-        data::{Data, toggle, ty::Bool},
+        data::{Data, comm::DataBuffer, toggle, ty::Bool},
         direction::{In, Out},
         event::{Event, ty::Signal},
     },
@@ -122,6 +122,22 @@ impl Voter {
         }
     }
 
+    pub fn write_input_data(&mut self, data: &str, buf: &DataBuffer) {
+        let result = match data.to_lowercase().as_str() {
+            "a" => self.a.update_buf(data, buf),
+            "b" => self.b.update_buf(data, buf),
+            "c" => self.c.update_buf(data, buf),
+            _ => {
+                println!("unkown input data \"{data}\"");
+                return;
+            }
+        };
+
+        if let Err(e) = result {
+            println!("{e}");
+        }
+    }
+
     pub fn toggle_input_data(&mut self, data: &str) {
         match data.to_lowercase().as_str() {
             "a" => toggle(&mut self.a),
@@ -174,8 +190,8 @@ pub fn run_sequence(sequence: Sequence) {
 
 // -- printing ------------------------------------------------------------------------------------
 #[allow(clippy::from_over_into)]
-impl Into<VoterInformation> for &Voter {
-    fn into(self) -> VoterInformation {
+impl<'a> Into<VoterInformation<'a>> for &'a Voter {
+    fn into(self) -> VoterInformation<'a> {
         VoterInformation {
             ecc: self.ecc.as_str(),
             vote: if self.vote.read() {
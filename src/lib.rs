@@ -1,5 +1,10 @@
+// allows the derive macros to refer to `::iec_61499_fb_rs` inside of this crate
+extern crate self as iec_61499_fb_rs;
+
 pub mod cli;
 pub mod fb;
 pub mod fb_impl;
 pub mod run_time;
 pub mod run_time_impl;
+pub mod st;
+pub mod xml;
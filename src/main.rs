@@ -11,33 +11,102 @@ fn main() {
     use Mode::*;
 
     #[allow(unreachable_patterns)] // might be extended later
-    match (args.function_block, args.mode) {
+    let result = match (args.function_block, args.mode) {
         (VoterBasic, Sequence) => {
             fb_impl::voter::basic::run_sequence(args.sequence);
+            Ok(())
         }
         (VoterTyped, Sequence) => {
             fb_impl::voter::typed::run_sequence(args.sequence);
+            Ok(())
         }
         (VoterTyped, Interactive) => {
             run_time_impl::interactive::simple_typed_runtime();
+            Ok(())
         }
         (_, TestConnectionParallel) => {
-            run_time_impl::conn_test::test_rc_conn_par_voter();
+            run_time_impl::conn_test::test_rc_conn_par_voter().map_err(|e| e.to_string())
         }
-        (_, TestConnectionSequential) => {
-            run_time_impl::conn_test::test_id_conn_seq_voter();
+        (_, TestConnectionSequential) => run_time_impl::conn_test::test_id_conn_seq_voter()
+            .and_then(|()| run_time_impl::conn_test::test_id_conn_dynamic_names())
+            .and_then(|()| run_time_impl::conn_test::test_id_conn_event_queues())
+            .map_err(|e| e.to_string())
+            .and_then(|()| {
+                run_time_impl::conn_test::test_event_delivery().map_err(|e| e.to_string())
+            }),
+        (_, ParseTypes) => run_time_impl::fbt_test::test_parse_fbt()
+            .and_then(|()| run_time_impl::fbt_test::test_parse_conditions())
+            .map_err(|e| e.to_string()),
+        (_, LoadSystem) => run_time_impl::sys_test::test_load_system(args.path.as_deref())
+            .map_err(|e| e.to_string()),
+        (_, StructuredText) => run_time_impl::st_test::test_st_voter()
+            .and_then(|()| run_time_impl::st_test::test_st_program())
+            .map_err(|e| e.to_string()),
+        (_, GenericTypes) => run_time_impl::generic_test::test_generic_ctu()
+            .and_then(|()| run_time_impl::generic_test::test_generic_voter())
+            .and_then(|()| run_time_impl::generic_test::test_generic_system())
+            .and_then(|()| run_time_impl::generic_test::test_generic_ecc())
+            .map_err(|e| e.to_string()),
+        (_, Scheduler) => run_time_impl::sched_test::test_scheduler_seq_voter()
+            .and_then(|()| run_time_impl::sched_test::test_scheduler_par_voter())
+            .map_err(|e| e.to_string()),
+        (_, WithSemantics) => run_time_impl::with_test::test_with_ctu()
+            .and_then(|()| run_time_impl::with_test::test_with_switch())
+            .and_then(|()| run_time_impl::with_test::test_with_voter())
+            .map_err(|e| e.to_string()),
+        (_, Device) => {
+            run_time_impl::device_test::test_device_resources().map_err(|e| e.to_string())
         }
-        (Ctu, _) => {
-            run_time_impl::e_control_test::test_ctu();
+        (_, Timers) => run_time_impl::timer_test::test_timer_delay()
+            .and_then(|()| run_time_impl::timer_test::test_timer_cycle())
+            .and_then(|()| run_time_impl::timer_test::test_timer_train())
+            .map_err(|e| e.to_string()),
+        (_, Composite) => run_time_impl::cfb_test::test_cfb_counter()
+            .and_then(|()| run_time_impl::cfb_test::test_cfb_nested())
+            .and_then(|()| run_time_impl::cfb_test::test_cfb_emissions())
+            .map_err(|e| e.to_string()),
+        (_, Adapters) => run_time_impl::adapter_test::test_adapter_req_cnf()
+            .and_then(|()| run_time_impl::adapter_test::test_adapter_mismatch())
+            .map_err(|e| e.to_string()),
+        (_, Validate) => run_time_impl::validate_test::test_validate_system()
+            .and_then(|()| run_time_impl::validate_test::test_validate_network())
+            .map_err(|e| e.to_string()),
+        (_, Interfaces) => {
+            run_time_impl::interface_test::test_interfaces().map_err(|e| e.to_string())
         }
-        (Sr, _) => {
-            run_time_impl::e_control_test::test_sr();
+        (_, Registry) => run_time_impl::registry_test::test_registry().map_err(|e| e.to_string()),
+        (_, Literals) => run_time_impl::literal_test::test_literals().map_err(|e| e.to_string()),
+        (_, Calendar) => run_time_impl::calendar_test::test_calendar().map_err(|e| e.to_string()),
+        (_, Conversions) => {
+            run_time_impl::convert_test::test_conversions().map_err(|e| e.to_string())
         }
-        (Switch, _) => {
-            run_time_impl::e_control_test::test_switch();
+        (_, Widening) => run_time_impl::widening_test::test_widening().map_err(|e| e.to_string()),
+        (_, GenericPorts) => {
+            run_time_impl::generic_port_test::test_generic_ports().map_err(|e| e.to_string())
+        }
+        (_, Arrays) => run_time_impl::array_test::test_arrays().map_err(|e| e.to_string()),
+        (Ctu, _) => run_time_impl::e_control_test::test_ctu().map_err(|e| e.to_string()),
+        (Sr, _) => run_time_impl::e_control_test::test_sr().map_err(|e| e.to_string()),
+        (Switch, _) => run_time_impl::e_control_test::test_switch().map_err(|e| e.to_string()),
+        (Split | Merge | Rend, _) => {
+            run_time_impl::e_control_test::test_split_merge_rend().map_err(|e| e.to_string())
+        }
+        (Permit | Select | Demux, _) => {
+            run_time_impl::e_control_test::test_permit_select_demux().map_err(|e| e.to_string())
+        }
+        (Rs | DFf | RTrig | FTrig, _) => {
+            run_time_impl::e_control_test::test_rs_d_ff_trig().map_err(|e| e.to_string())
+        }
+        (Ctd | Ctud, _) => {
+            run_time_impl::e_control_test::test_ctd_ctud().map_err(|e| e.to_string())
         }
         (implementation, mode) => {
             println!("combination of \"{implementation}\"  and \"{mode}\" is not configured.");
+            Ok(())
         }
+    };
+
+    if let Err(e) = result {
+        println!("[error]: {e}");
     }
 }
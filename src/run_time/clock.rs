@@ -0,0 +1,124 @@
+//! Time of the runtime, used by the timed event function blocks (`E_DELAY`, `E_CYCLE`, `E_RESTART`, `E_TRAIN`).
+//!
+//! A `Clock` returns the time elapsed since its epoch:
+//! - `RealClock` follows the system time, all real clocks share the same epoch (first use in the process)
+//! - `SimClock` only moves when it is advanced, which makes timed networks deterministic
+//!
+//! Timed function blocks keep their `Timer` in a field annotated with `#[timer]`,
+//! the runtime asks them for their `Bfb::deadline` and invokes their execution control once it passed
+//! (see `Scheduler::poll_timers` and `Scheduler::advance`).
+//!
+//! ```text
+//! let clock = Arc::new(SimClock::default());
+//! rt.set_clock(clock.clone());
+//! scheduler.post("delay0", "start");
+//! scheduler.advance(&mut rt, &clock, Duration::from_millis(500), 100)?;
+//! ```
+
+use std::{
+    sync::{Arc, Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
+pub trait Clock: std::fmt::Debug + Send + Sync {
+    /// time elapsed since the epoch of the clock
+    fn now(&self) -> Duration;
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RealClock;
+
+impl Clock for RealClock {
+    fn now(&self) -> Duration {
+        static EPOCH: OnceLock<Instant> = OnceLock::new();
+
+        EPOCH.get_or_init(Instant::now).elapsed()
+    }
+}
+
+/// simulated clock starting at zero
+#[derive(Debug, Default)]
+pub struct SimClock {
+    now: Mutex<Duration>,
+}
+
+impl SimClock {
+    pub fn advance(&self, by: Duration) {
+        *self.now.lock().unwrap() += by;
+    }
+
+    /// moves the clock to the given time, the clock never moves backwards
+    pub fn set(&self, to: Duration) {
+        let mut now = self.now.lock().unwrap();
+        *now = (*now).max(to);
+    }
+}
+
+impl Clock for SimClock {
+    fn now(&self) -> Duration {
+        *self.now.lock().unwrap()
+    }
+}
+
+/// single shot timer of a function block, measured on a (shared) clock
+#[derive(Clone, Debug)]
+pub struct Timer {
+    clock: Arc<dyn Clock>,
+    deadline: Option<Duration>,
+}
+
+impl Default for Timer {
+    fn default() -> Self {
+        Self {
+            clock: Arc::new(RealClock),
+            deadline: None,
+        }
+    }
+}
+
+impl Timer {
+    /// expires `after` the current time, a running timer is restarted
+    pub fn start(&mut self, after: Duration) {
+        self.deadline = Some(self.clock.now() + after);
+    }
+
+    /// expires `period` after the previous deadline, so periodic timers do not drift
+    pub fn restart(&mut self, period: Duration) {
+        match self.deadline {
+            Some(deadline) => self.deadline = Some(deadline + period),
+            None => self.start(period),
+        }
+    }
+
+    pub fn stop(&mut self) {
+        self.deadline = None;
+    }
+
+    pub fn deadline(&self) -> Option<Duration> {
+        self.deadline
+    }
+
+    /// whether the timer is running and its deadline passed
+    pub fn expired(&self) -> bool {
+        self.deadline.is_some_and(|d| d <= self.clock.now())
+    }
+
+    /// switches to another clock, a running timer keeps its remaining time
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        if let Some(deadline) = self.deadline {
+            let remaining = deadline.saturating_sub(self.clock.now());
+            self.deadline = Some(clock.now() + remaining);
+        }
+
+        self.clock = clock;
+    }
+}
+
+impl std::fmt::Display for Timer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.deadline {
+            Some(deadline) => write!(f, "{deadline:?}"),
+            None => write!(f, "STOPPED"),
+        }
+    }
+}
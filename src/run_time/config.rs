@@ -0,0 +1,464 @@
+//! Instantiation of `IEC 61499` system configurations in the `IdConnRuntime`.
+//!
+//! A system consists of devices, which consist of resources, each of which contains one function block network.
+//! Every resource network is loaded into its own `IdConnRuntime`:
+//! - function blocks are created by type name from a `TypeRegistry`
+//! - `Parameter` values are written to the data inputs of the created instances
+//! - event and data connections are validated and created via `connect_event`/`connect_data`
+//!
+//! Port names are lowercased, since the implemented function blocks use lowercase field names.
+//! Connections starting at `START.COLD`/`START.WARM` refer to the restart block of the resource (`E_RESTART`),
+//! which is not part of the network itself: the `COLD` events are posted to a `Scheduler` when the resource is run.
+
+use std::{fmt::Display, path::Path};
+
+use crate::{
+    fb::{
+        Bfb,
+        data::{
+            comm::DataBuffer,
+            ty::{self, DataKind},
+        },
+        error::FbError,
+        registry::TypeRegistry,
+    },
+    run_time::{id_conn::IdConnRuntime, scheduler::Scheduler},
+    xml::{Location, XmlError, sys},
+};
+
+/// instance name of the implicit restart block of a resource
+const START: &str = "START";
+
+#[derive(Clone, Debug)]
+pub struct SystemDesc {
+    pub name: String,
+    pub comment: String,
+    pub devices: Vec<DeviceDesc>,
+}
+
+#[derive(Clone, Debug)]
+pub struct DeviceDesc {
+    pub name: String,
+    pub type_name: String,
+    pub resources: Vec<ResourceDesc>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ResourceDesc {
+    pub name: String,
+    pub type_name: String,
+    pub network: FbNetworkDesc,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct FbNetworkDesc {
+    pub fbs: Vec<FbInstanceDesc>,
+    pub event_connections: Vec<ConnectionDesc>,
+    pub data_connections: Vec<ConnectionDesc>,
+}
+
+#[derive(Clone, Debug)]
+pub struct FbInstanceDesc {
+    pub name: String,
+    pub type_name: String,
+    pub parameters: Vec<ParameterDesc>,
+    pub location: Option<Location>,
+}
+
+/// initial value of a data input
+#[derive(Clone, Debug)]
+pub struct ParameterDesc {
+    pub name: String,
+    /// value in `IEC 61131-3` literal notation
+    pub value: String,
+    pub location: Option<Location>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ConnectionDesc {
+    pub source: PortRef,
+    pub destination: PortRef,
+    pub location: Option<Location>,
+}
+
+/// `instance.port` reference as used in connections
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PortRef {
+    pub instance: String,
+    pub port: String,
+}
+
+impl Display for PortRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.instance, self.port)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum ConfigErrorKind {
+    Xml(String),
+    UnknownType {
+        instance: String,
+        type_name: String,
+    },
+    DuplicateInstance(String),
+    UnknownInstance(String),
+    /// a port that does not exist or has the wrong direction
+    InvalidPort {
+        port: PortRef,
+        error: FbError,
+    },
+    DataKindMismatch {
+        source: PortRef,
+        destination: PortRef,
+        source_kind: DataKind,
+        destination_kind: DataKind,
+    },
+    InvalidParameter {
+        instance: String,
+        parameter: String,
+        value: String,
+        kind: DataKind,
+    },
+}
+
+/// error while loading a system configuration, located in the configuration file if possible
+#[derive(Clone, Debug)]
+pub struct ConfigError {
+    pub location: Option<Location>,
+    pub kind: Box<ConfigErrorKind>,
+}
+
+impl ConfigError {
+    fn new(location: Option<Location>, kind: ConfigErrorKind) -> Self {
+        Self {
+            location,
+            kind: Box::new(kind),
+        }
+    }
+}
+
+impl Display for ConfigErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigErrorKind::Xml(message) => write!(f, "{message}"),
+            ConfigErrorKind::UnknownType {
+                instance,
+                type_name,
+            } => write!(f, "unknown type \"{type_name}\" of instance \"{instance}\""),
+            ConfigErrorKind::DuplicateInstance(name) => {
+                write!(f, "duplicate instance name \"{name}\"")
+            }
+            ConfigErrorKind::UnknownInstance(name) => write!(f, "unknown instance \"{name}\""),
+            ConfigErrorKind::InvalidPort { port, error } => write!(f, "{port}: {error}"),
+            ConfigErrorKind::DataKindMismatch {
+                source,
+                destination,
+                source_kind,
+                destination_kind,
+            } => write!(
+                f,
+                "can not connect {source} ({}) to {destination} ({})",
+                source_kind.iec_name(),
+                destination_kind.iec_name()
+            ),
+            ConfigErrorKind::InvalidParameter {
+                instance,
+                parameter,
+                value,
+                kind,
+            } => write!(
+                f,
+                "invalid value \"{value}\" for parameter {instance}.{parameter} ({})",
+                kind.iec_name()
+            ),
+        }
+    }
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.location {
+            Some(Location { line, column }) => write!(f, "{line}:{column}: {}", self.kind),
+            None => write!(f, "{}", self.kind),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl From<XmlError> for ConfigError {
+    fn from(e: XmlError) -> Self {
+        Self::new(e.location, ConfigErrorKind::Xml(e.message))
+    }
+}
+
+/// a loaded resource, ready to run
+#[derive(Debug)]
+pub struct ResourceRuntime {
+    pub device: String,
+    pub name: String,
+    pub runtime: IdConnRuntime,
+    /// targets of the `START.COLD` connections
+    cold_start: Vec<(String, String)>,
+}
+
+impl ResourceRuntime {
+    /// instantiates the function block network of a resource
+    pub fn load(
+        device: &str,
+        resource: &ResourceDesc,
+        registry: &TypeRegistry,
+    ) -> Result<Self, ConfigError> {
+        let desc = &resource.network;
+        let mut runtime = IdConnRuntime::default();
+
+        for fb_desc in &desc.fbs {
+            let name = fb_desc.name.as_str();
+
+            if runtime.fbs().contains_key(name) || fb_desc.name.eq_ignore_ascii_case(START) {
+                return Err(ConfigError::new(
+                    fb_desc.location,
+                    ConfigErrorKind::DuplicateInstance(fb_desc.name.clone()),
+                ));
+            }
+
+            let Some(mut fb) = registry.create(&fb_desc.type_name, name) else {
+                return Err(ConfigError::new(
+                    fb_desc.location,
+                    ConfigErrorKind::UnknownType {
+                        instance: fb_desc.name.clone(),
+                        type_name: fb_desc.type_name.clone(),
+                    },
+                ));
+            };
+
+            for parameter in &fb_desc.parameters {
+                apply_parameter(fb.as_mut(), parameter)?;
+            }
+
+            runtime.add_boxed_fb(fb);
+        }
+
+        let mut cold_start = vec![];
+
+        for conn in &desc.event_connections {
+            let destination = event_port(&runtime, conn, &conn.destination, true)?;
+
+            if conn.source.instance.eq_ignore_ascii_case(START) {
+                match conn.source.port.to_ascii_uppercase().as_str() {
+                    "COLD" => cold_start.push(destination),
+                    "WARM" | "STOP" => {}
+                    _ => {
+                        return Err(ConfigError::new(
+                            conn.location,
+                            ConfigErrorKind::InvalidPort {
+                                port: conn.source.clone(),
+                                error: FbError::unknown_event(&conn.source.port),
+                            },
+                        ));
+                    }
+                }
+
+                continue;
+            }
+
+            let source = event_port(&runtime, conn, &conn.source, false)?;
+
+            runtime.connect_event((&source.0, &source.1), (&destination.0, &destination.1));
+        }
+
+        for conn in &desc.data_connections {
+            let (source, source_kind) = data_port(&runtime, conn, &conn.source, false)?;
+            let (destination, destination_kind) =
+                data_port(&runtime, conn, &conn.destination, true)?;
+
+            if !ty::assignable(&source_kind, &destination_kind) {
+                return Err(ConfigError::new(
+                    conn.location,
+                    ConfigErrorKind::DataKindMismatch {
+                        source: conn.source.clone(),
+                        destination: conn.destination.clone(),
+                        source_kind,
+                        destination_kind,
+                    },
+                ));
+            }
+
+            runtime
+                .connect_data((&source.0, &source.1), (&destination.0, &destination.1))
+                .map_err(|error| port_error(conn, &conn.source, error))?;
+        }
+
+        Ok(Self {
+            device: device.to_string(),
+            name: resource.name.clone(),
+            runtime,
+            cold_start,
+        })
+    }
+
+    /// delivers the cold start events and processes all resulting event chains,
+    /// returns `false` if events are still pending after `max_deliveries`
+    pub fn run(&mut self, max_deliveries: usize) -> Result<bool, FbError> {
+        let mut scheduler = Scheduler::default();
+
+        for (fb_name, event) in &self.cold_start {
+            scheduler.post(fb_name, event);
+        }
+
+        scheduler.run(&mut self.runtime, max_deliveries)
+    }
+}
+
+#[derive(Debug)]
+pub struct SystemRuntime {
+    pub name: String,
+    pub resources: Vec<ResourceRuntime>,
+}
+
+impl SystemRuntime {
+    /// instantiates all resources of the system
+    pub fn load(desc: &SystemDesc, registry: &TypeRegistry) -> Result<Self, ConfigError> {
+        let mut resources = vec![];
+
+        for device in &desc.devices {
+            for resource in &device.resources {
+                resources.push(ResourceRuntime::load(&device.name, resource, registry)?);
+            }
+        }
+
+        Ok(Self {
+            name: desc.name.clone(),
+            resources,
+        })
+    }
+
+    /// parses and instantiates the content of a `.sys` file
+    pub fn load_str(text: &str, registry: &TypeRegistry) -> Result<Self, ConfigError> {
+        Self::load(&sys::parse_sys(text)?, registry)
+    }
+
+    /// reads, parses and instantiates a `.sys` file
+    pub fn load_file(path: impl AsRef<Path>, registry: &TypeRegistry) -> Result<Self, ConfigError> {
+        Self::load(&sys::parse_sys_file(path)?, registry)
+    }
+
+    /// runs all resources one after another, see `ResourceRuntime::run`
+    pub fn run(&mut self, max_deliveries: usize) -> Result<bool, FbError> {
+        let mut stable = true;
+
+        for resource in &mut self.resources {
+            stable &= resource.run(max_deliveries)?;
+        }
+
+        Ok(stable)
+    }
+}
+
+fn apply_parameter(fb: &mut dyn Bfb, parameter: &ParameterDesc) -> Result<(), ConfigError> {
+    let instance = fb.instance_name().to_string();
+    let port = parameter.name.to_lowercase();
+    let invalid_port = |error| {
+        ConfigError::new(
+            parameter.location,
+            ConfigErrorKind::InvalidPort {
+                port: PortRef {
+                    instance: instance.clone(),
+                    port: parameter.name.clone(),
+                },
+                error,
+            },
+        )
+    };
+
+    let kind = fb.data_kind(&port).map_err(invalid_port)?;
+
+    // parameters can only be applied to data inputs
+    if fb.read_data_out(&port).is_ok() {
+        return Err(invalid_port(FbError::wrong_direction(&port)));
+    }
+
+    let Some(buf) = DataBuffer::parse_literal(&kind, &parameter.value) else {
+        return Err(ConfigError::new(
+            parameter.location,
+            ConfigErrorKind::InvalidParameter {
+                instance,
+                parameter: parameter.name.clone(),
+                value: parameter.value.clone(),
+                kind,
+            },
+        ));
+    };
+
+    fb.write_data_in(&port, &buf).map_err(invalid_port)
+}
+
+/// resolves an event port and checks its direction against the events of the instance
+fn event_port(
+    runtime: &IdConnRuntime,
+    conn: &ConnectionDesc,
+    port: &PortRef,
+    input: bool,
+) -> Result<(String, String), ConfigError> {
+    let fb = instance(runtime, conn, port)?;
+    let event = port.port.to_lowercase();
+
+    let (expected, other) = if input {
+        (fb.event_inputs(), fb.event_outputs())
+    } else {
+        (fb.event_outputs(), fb.event_inputs())
+    };
+    let has = |events: Vec<&str>| events.iter().any(|e| e.eq_ignore_ascii_case(&event));
+
+    match (has(expected), has(other)) {
+        (true, _) => Ok((fb.instance_name().to_string(), event)),
+        (false, true) => Err(port_error(conn, port, FbError::wrong_direction(&event))),
+        (false, false) => Err(port_error(conn, port, FbError::unknown_event(&event))),
+    }
+}
+
+/// resolves a data port and its kind, outputs can be read while inputs are rejected as the wrong direction
+fn data_port(
+    runtime: &IdConnRuntime,
+    conn: &ConnectionDesc,
+    port: &PortRef,
+    input: bool,
+) -> Result<((String, String), DataKind), ConfigError> {
+    let fb = instance(runtime, conn, port)?;
+    let data = port.port.to_lowercase();
+
+    let kind = fb
+        .data_kind(&data)
+        .map_err(|error| port_error(conn, port, error))?;
+
+    if fb.read_data_out(&data).is_ok() == input {
+        return Err(port_error(conn, port, FbError::wrong_direction(&data)));
+    }
+
+    Ok(((fb.instance_name().to_string(), data), kind))
+}
+
+fn instance<'a>(
+    runtime: &'a IdConnRuntime,
+    conn: &ConnectionDesc,
+    port: &PortRef,
+) -> Result<&'a dyn Bfb, ConfigError> {
+    match runtime.fbs().get(port.instance.as_str()) {
+        Some(fb) => Ok(fb.as_ref()),
+        None => Err(ConfigError::new(
+            conn.location,
+            ConfigErrorKind::UnknownInstance(port.instance.clone()),
+        )),
+    }
+}
+
+fn port_error(conn: &ConnectionDesc, port: &PortRef, error: FbError) -> ConfigError {
+    ConfigError::new(
+        conn.location,
+        ConfigErrorKind::InvalidPort {
+            port: port.clone(),
+            error,
+        },
+    )
+}
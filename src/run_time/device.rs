@@ -0,0 +1,490 @@
+//! Devices containing several resources, which run in parallel.
+//!
+//! Every resource runs its `IdConnRuntime` with a `Scheduler` on its own worker thread,
+//! the worker processes the messages of its channel one after another:
+//! - events are posted to the scheduler, which runs the resulting event chains to completion
+//! - output events of function blocks connected to other resources are forwarded over the channels
+//!   of the target resources, the values of connected WITH outputs are sent right before the event
+//!   and latched as remote inputs (see `IdConnRuntime::write_remote_input`)
+//!
+//! ```text
+//! let mut device = Device::new("Dev");
+//! device.add_resource("Res1", counter);
+//! device.add_resource("Res2", latch);
+//! device.connect_event(("Res1", "switch0", "eo1"), ("Res2", "sr0", "s"));
+//! device.start();
+//! device.post("Res1", "ctu0", "cu");
+//! device.wait_idle();
+//! device.stop();
+//! ```
+
+use std::{
+    sync::{
+        Arc, Condvar, Mutex,
+        mpsc::{self, Receiver, Sender},
+    },
+    thread::JoinHandle,
+};
+
+use crate::{
+    fb::{data::comm::DataBuffer, error::FbError},
+    run_time::{
+        id_conn::IdConnRuntime,
+        scheduler::{Emission, Scheduler},
+    },
+};
+
+/// upper bound of deliveries a resource processes for a single received event
+const MAX_DELIVERIES: usize = 10_000;
+
+/// port of a function block inside of a resource: `(resource, function block, port)`
+pub type ResourcePort<'a> = (&'a str, &'a str, &'a str);
+
+#[derive(Debug)]
+pub struct Device {
+    pub name: String,
+    resources: Vec<Resource>,
+    activity: Arc<Activity>,
+}
+
+#[derive(Debug)]
+struct Resource {
+    name: String,
+    state: ResourceState,
+    links: Vec<Link>,
+}
+
+#[derive(Debug)]
+enum ResourceState {
+    Stopped(IdConnRuntime),
+    Running {
+        sender: Sender<Message>,
+        handle: JoinHandle<IdConnRuntime>,
+    },
+    /// only present while switching between the other states
+    Switching,
+}
+
+/// connection from a function block of one resource into another resource
+#[derive(Clone, Debug)]
+enum Link {
+    Event {
+        fb_name: String,
+        event: String,
+        resource: usize,
+        to_fb: String,
+        to_event: String,
+    },
+    Data {
+        fb_name: String,
+        port: String,
+        resource: usize,
+        to_fb: String,
+        to_port: String,
+    },
+}
+
+enum Message {
+    Event {
+        fb_name: String,
+        event: String,
+    },
+    Data {
+        fb_name: String,
+        port: String,
+        buf: DataBuffer,
+    },
+    Snapshot(Sender<String>),
+    Stop,
+}
+
+/// number of messages which are sent, but not completely processed yet
+#[derive(Debug, Default)]
+struct Activity {
+    pending: Mutex<usize>,
+    idle: Condvar,
+}
+
+impl Activity {
+    fn begin(&self) {
+        *self.pending.lock().unwrap() += 1;
+    }
+
+    fn end(&self) {
+        let mut pending = self.pending.lock().unwrap();
+        *pending -= 1;
+
+        if *pending == 0 {
+            self.idle.notify_all();
+        }
+    }
+
+    fn wait(&self) {
+        let mut pending = self.pending.lock().unwrap();
+
+        while *pending > 0 {
+            pending = self.idle.wait(pending).unwrap();
+        }
+    }
+}
+
+impl Device {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            resources: vec![],
+            activity: Arc::default(),
+        }
+    }
+
+    /// adds a resource with its function block network, only possible while the device is stopped
+    pub fn add_resource(&mut self, name: &str, runtime: IdConnRuntime) {
+        if self.is_running() {
+            println!("[error device]: can not add resource {name} to running device");
+            return;
+        }
+
+        if self.index(name).is_some() {
+            println!("[error device]: resource with name=\"{name}\" already exists");
+            return;
+        }
+
+        self.resources.push(Resource {
+            name: name.to_string(),
+            state: ResourceState::Stopped(runtime),
+            links: vec![],
+        });
+    }
+
+    /// connects an event output with an event input of a different resource
+    pub fn connect_event(&mut self, from: ResourcePort, to: ResourcePort) {
+        let Some((from_index, to_index)) = self.link_valid(from, to) else {
+            return;
+        };
+
+        self.resources[from_index].links.push(Link::Event {
+            fb_name: from.1.to_string(),
+            event: from.2.to_string(),
+            resource: to_index,
+            to_fb: to.1.to_string(),
+            to_event: to.2.to_string(),
+        });
+    }
+
+    /// connects a data output with a data input of a different resource,
+    /// the current value of the output is latched as initial value
+    pub fn connect_data(&mut self, from: ResourcePort, to: ResourcePort) -> Result<(), FbError> {
+        let Some((from_index, to_index)) = self.link_valid(from, to) else {
+            return Ok(());
+        };
+
+        let buf = self
+            .runtime(from.0)
+            .unwrap()
+            .fbs()
+            .get(from.1)
+            .unwrap()
+            .read_data_out(from.2)?;
+
+        if let ResourceState::Stopped(runtime) = &mut self.resources[to_index].state {
+            runtime.write_remote_input((to.1, to.2), &buf);
+        }
+
+        self.resources[from_index].links.push(Link::Data {
+            fb_name: from.1.to_string(),
+            port: from.2.to_string(),
+            resource: to_index,
+            to_fb: to.1.to_string(),
+            to_port: to.2.to_string(),
+        });
+
+        Ok(())
+    }
+
+    /// spawns one worker thread per resource
+    pub fn start(&mut self) {
+        if self.is_running() {
+            return;
+        }
+
+        let channels: Vec<(Sender<Message>, Receiver<Message>)> =
+            self.resources.iter().map(|_| mpsc::channel()).collect();
+        let senders: Vec<Sender<Message>> = channels.iter().map(|(tx, _)| tx.clone()).collect();
+
+        for (resource, (sender, receiver)) in self.resources.iter_mut().zip(channels) {
+            let ResourceState::Stopped(runtime) =
+                std::mem::replace(&mut resource.state, ResourceState::Switching)
+            else {
+                unreachable!("all resources of a stopped device are stopped");
+            };
+
+            let worker = Worker {
+                name: format!("{}.{}", self.name, resource.name),
+                links: resource.links.clone(),
+                senders: senders.clone(),
+                activity: Arc::clone(&self.activity),
+            };
+
+            let handle = std::thread::Builder::new()
+                .name(worker.name.clone())
+                .spawn(move || worker.run(runtime, receiver))
+                .expect("failed to spawn resource thread");
+
+            resource.state = ResourceState::Running { sender, handle };
+        }
+    }
+
+    /// sends an event from outside of the device to a function block
+    pub fn post(&self, resource: &str, fb_name: &str, event: &str) {
+        let message = Message::Event {
+            fb_name: fb_name.to_string(),
+            event: event.to_string(),
+        };
+
+        match self.index(resource).map(|i| &self.resources[i].state) {
+            Some(ResourceState::Running { sender, .. }) => send(&self.activity, sender, message),
+            Some(_) => println!("[error device]: resource {resource} is not running"),
+            None => println!("[error device]: no resource with name=\"{resource}\" exists"),
+        }
+    }
+
+    /// blocks until all resources processed all events, including the ones they sent each other
+    pub fn wait_idle(&self) {
+        self.activity.wait();
+    }
+
+    /// renders the runtime of a running resource, after it processed all previously received messages
+    pub fn snapshot(&self, resource: &str) -> Option<String> {
+        let ResourceState::Running { sender, .. } = &self.resources[self.index(resource)?].state
+        else {
+            return self.runtime(resource).map(IdConnRuntime::to_string);
+        };
+
+        let (tx, rx) = mpsc::channel();
+        send(&self.activity, sender, Message::Snapshot(tx));
+
+        rx.recv().ok()
+    }
+
+    /// stops all worker threads after they processed their pending messages,
+    /// the runtimes can be inspected again afterwards
+    pub fn stop(&mut self) {
+        for resource in &self.resources {
+            if let ResourceState::Running { sender, .. } = &resource.state {
+                send(&self.activity, sender, Message::Stop);
+            }
+        }
+
+        for resource in &mut self.resources {
+            let state = std::mem::replace(&mut resource.state, ResourceState::Switching);
+
+            resource.state = match state {
+                ResourceState::Running { handle, .. } => {
+                    ResourceState::Stopped(handle.join().expect("resource thread panicked"))
+                }
+                state => state,
+            };
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.resources
+            .iter()
+            .any(|r| matches!(r.state, ResourceState::Running { .. }))
+    }
+
+    /// runtime of a resource, only accessible while the device is stopped
+    pub fn runtime(&self, resource: &str) -> Option<&IdConnRuntime> {
+        match &self.resources[self.index(resource)?].state {
+            ResourceState::Stopped(runtime) => Some(runtime),
+            _ => None,
+        }
+    }
+
+    pub fn resource_names(&self) -> impl Iterator<Item = &str> {
+        self.resources.iter().map(|r| r.name.as_str())
+    }
+}
+
+impl Device {
+    fn index(&self, resource: &str) -> Option<usize> {
+        self.resources.iter().position(|r| r.name == resource)
+    }
+
+    /// returns the indices of both resources, if both ports refer to existing function blocks
+    fn link_valid(&self, from: ResourcePort, to: ResourcePort) -> Option<(usize, usize)> {
+        if self.is_running() {
+            println!("[error device]: can not connect resources of a running device");
+            return None;
+        }
+
+        if from.0 == to.0 {
+            println!(
+                "[error device]: use the runtime of {} to connect its function blocks",
+                from.0
+            );
+            return None;
+        }
+
+        for (resource, fb_name) in [(from.0, from.1), (to.0, to.1)] {
+            let Some(runtime) = self.runtime(resource) else {
+                println!("[error device]: no resource with name=\"{resource}\" exists");
+                return None;
+            };
+
+            if !runtime.fbs().contains_key(fb_name) {
+                println!("[error device]: no fb with name=\"{fb_name}\" exists in {resource}");
+                return None;
+            }
+        }
+
+        Some((self.index(from.0)?, self.index(to.0)?))
+    }
+}
+
+impl std::fmt::Display for Device {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Device {}:", self.name)?;
+
+        for resource in &self.resources {
+            for link in &resource.links {
+                let (fb_name, port, to, to_fb, to_port) = match link {
+                    Link::Event {
+                        fb_name,
+                        event,
+                        resource,
+                        to_fb,
+                        to_event,
+                    } => (fb_name, event, resource, to_fb, to_event),
+                    Link::Data {
+                        fb_name,
+                        port,
+                        resource,
+                        to_fb,
+                        to_port,
+                    } => (fb_name, port, resource, to_fb, to_port),
+                };
+
+                writeln!(
+                    f,
+                    "({}, {fb_name}, {port})=========>({}, {to_fb}, {to_port})",
+                    resource.name, self.resources[*to].name
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn send(activity: &Activity, sender: &Sender<Message>, message: Message) {
+    activity.begin();
+
+    if sender.send(message).is_err() {
+        activity.end();
+    }
+}
+
+/// state owned by the thread of a resource
+struct Worker {
+    name: String,
+    links: Vec<Link>,
+    senders: Vec<Sender<Message>>,
+    activity: Arc<Activity>,
+}
+
+impl Worker {
+    fn run(self, mut runtime: IdConnRuntime, receiver: Receiver<Message>) -> IdConnRuntime {
+        let mut scheduler = Scheduler::default();
+
+        for link in &self.links {
+            match link {
+                Link::Event { fb_name, .. } | Link::Data { fb_name, .. } => {
+                    scheduler.watch(fb_name);
+                }
+            }
+        }
+
+        for message in receiver {
+            let stop = matches!(message, Message::Stop);
+
+            match message {
+                Message::Event { fb_name, event } => {
+                    scheduler.post(&fb_name, &event);
+
+                    match scheduler.run(&mut runtime, MAX_DELIVERIES) {
+                        Ok(true) => {}
+                        Ok(false) => println!(
+                            "[error {}]: events still pending after {MAX_DELIVERIES} deliveries",
+                            self.name
+                        ),
+                        Err(e) => println!("[error {}]: {e}", self.name),
+                    }
+
+                    for emission in scheduler.take_emissions() {
+                        self.forward(&emission);
+                    }
+                }
+                Message::Data { fb_name, port, buf } => {
+                    runtime.write_remote_input((&fb_name, &port), &buf);
+                }
+                Message::Snapshot(reply) => {
+                    _ = reply.send(runtime.to_string());
+                }
+                Message::Stop => {}
+            }
+
+            self.activity.end();
+
+            if stop {
+                break;
+            }
+        }
+
+        runtime
+    }
+
+    /// sends the latched data first, so it is available when the event is delivered
+    fn forward(&self, emission: &Emission) {
+        for link in &self.links {
+            if let Link::Data {
+                fb_name,
+                port,
+                resource,
+                to_fb,
+                to_port,
+            } = link
+                && *fb_name == emission.fb_name
+                && let Some((_, buf)) = emission.data.iter().find(|(field, _)| field == port)
+            {
+                let message = Message::Data {
+                    fb_name: to_fb.clone(),
+                    port: to_port.clone(),
+                    buf: buf.clone(),
+                };
+
+                send(&self.activity, &self.senders[*resource], message);
+            }
+        }
+
+        for link in &self.links {
+            if let Link::Event {
+                fb_name,
+                event,
+                resource,
+                to_fb,
+                to_event,
+            } = link
+                && *fb_name == emission.fb_name
+                && *event == emission.event
+            {
+                let message = Message::Event {
+                    fb_name: to_fb.clone(),
+                    event: to_event.clone(),
+                };
+
+                send(&self.activity, &self.senders[*resource], message);
+            }
+        }
+    }
+}
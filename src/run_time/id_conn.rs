@@ -3,21 +3,35 @@
 //! ### FunctionBlock IDs
 //! In this implementation we defer the role of unique `id`s to the function block `instance_name`.
 //! - The instance name will be enforced to be unique in the run time
-//! - Currently a function block's `instance_name` is a `&'static str`
-//!     - this means the name needs to be present in the source code in the form of a string literal (static lifetime)
-//!     - if we want to dynamically add function blocks duriong runtime in future implementations,
-//!       we need to redesign the `instance_name` implementation
+//! - Instance and port names are owned `String`s, so function blocks and connections
+//!   can be added and removed at runtime, e.g. from a system configuration or user input
+//!
+//! ### Input event queues
+//! Every function block has an `EventQueue` in the runtime, events sent to a function block
+//! with a pending input event are queued instead of being lost (see `run_time::queue`).
 //!
 //! If this proves to be insufficient for more complicated tasks down the line,
 //! we will evaluate external crates for arena allocation / specialized containers.
 //! In the case that no fitting implementation exists, we might need to implement
 //! our own solution.
 
-use std::collections::HashMap;
-
-use crate::fb::{
-    Bfb,
-    direction::{In, Out},
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    fb::{
+        Bfb,
+        adapter::AdapterType,
+        data::{comm::DataBuffer, ty},
+        direction::{In, Out},
+        error::FbError,
+        registry::TypeRegistry,
+    },
+    run_time::{
+        clock::Clock,
+        queue::{EventQueue, OverflowPolicy},
+        sampling,
+        validate::{self, Diagnostic},
+    },
 };
 
 use conns::{DataConn, EventConn};
@@ -25,14 +39,22 @@ use port::Port;
 
 #[derive(Default, Debug)]
 pub struct IdConnRuntime {
-    fbs: std::collections::HashMap<&'static str, Box<dyn Bfb>>,
+    fbs: std::collections::HashMap<String, Box<dyn Bfb>>,
     data_conns: Vec<DataConn>,
     event_conns: Vec<EventConn>,
+    queues: HashMap<String, EventQueue>,
+    /// buffers of data connections from outside of the runtime, keyed by destination port
+    remote_inputs: HashMap<(String, String), DataBuffer>,
 }
 
 impl IdConnRuntime {
     /// adds any struct that implements the `Fb` to the HashMap
     pub fn add_fb<T: Bfb + 'static>(&mut self, fb: T) {
+        self.add_boxed_fb(Box::new(fb));
+    }
+
+    /// adds an already boxed function block, e.g. one created by a `TypeRegistry`
+    pub fn add_boxed_fb(&mut self, fb: Box<dyn Bfb>) {
         if self.fb_exists(fb.instance_name()) {
             println!(
                 "fb with instance name {} already exists in runtime",
@@ -42,11 +64,31 @@ impl IdConnRuntime {
             return;
         }
 
-        self.fbs.insert(fb.instance_name(), Box::new(fb));
+        self.queues
+            .insert(fb.instance_name().to_string(), EventQueue::default());
+        self.fbs.insert(fb.instance_name().to_string(), fb);
     }
 
-    pub fn remove_fb(&mut self, name: &'static str) {
+    /// creates a function block of a registered type and adds it, see `add_boxed_fb`
+    pub fn create_fb(
+        &mut self,
+        registry: &TypeRegistry,
+        type_name: &str,
+        instance_name: &str,
+    ) -> Result<(), FbError> {
+        let fb = registry
+            .create(type_name, instance_name)
+            .ok_or_else(|| FbError::unknown_type(type_name))?;
+
+        self.add_boxed_fb(fb);
+
+        Ok(())
+    }
+
+    pub fn remove_fb(&mut self, name: &str) {
         self.fbs.remove(name);
+        self.queues.remove(name);
+        self.remote_inputs.retain(|(fb_name, _), _| fb_name != name);
 
         self.data_conns
             .retain(|dc| dc.from.fb_name != name && dc.to.fb_name != name);
@@ -55,33 +97,55 @@ impl IdConnRuntime {
             .retain(|ec| ec.from.fb_name != name && ec.to.fb_name != name);
     }
 
-    pub fn connect_data(
-        &mut self,
-        from: (&'static str, &'static str),
-        to: (&'static str, &'static str),
-    ) {
-        if !self.connection_valid(from.0, to.0) {
-            return;
+    /// connects the data output `from` to the data input `to`,
+    /// generic ports are fixed once the connection is known to be valid
+    pub fn connect_data(&mut self, from: (&str, &str), to: (&str, &str)) -> Result<(), FbError> {
+        self.connection_valid(from.0, to.0)?;
+
+        let source = &self.fbs[from.0];
+        let destination = &self.fbs[to.0];
+
+        data_direction(source.as_ref(), from.1, false)?;
+        data_direction(destination.as_ref(), to.1, true)?;
+
+        // verify the data type of the output fits the input
+        let kinds = (source.data_kind(from.1)?, destination.data_kind(to.1)?);
+
+        if !ty::assignable(&kinds.0, &kinds.1) {
+            return Err(FbError::kind_mismatch(
+                &format!("{}.{}", from.0, from.1),
+                &format!("{}.{}", to.0, to.1),
+                kinds.0,
+                kinds.1,
+            ));
+        }
+
+        let mut buf = source.read_data_out(from.1)?;
+
+        // generic ports are fixed to the kind of the other end
+        match ty::fixing(&kinds.0, &kinds.1) {
+            Some(ty::Fixing::Source(kind)) => {
+                let source = self.fb_mut(from.0)?;
+                source.fix_data_kind(from.1, &kind)?;
+                buf = source.read_data_out(from.1)?;
+            }
+            Some(ty::Fixing::Destination(kind)) => {
+                self.fb_mut(to.0)?.fix_data_kind(to.1, &kind)?;
+            }
+            None => {}
         }
 
         let from = Port::<Out>::new(from.0, from.1);
         let to = Port::<In>::new(to.0, to.1);
-        let buf = self
-            .fbs
-            .get(from.fb_name)
-            .unwrap()
-            .as_ref()
-            .read_data_out(from.fb_field);
 
         self.data_conns.push(DataConn { from, to, buf });
+
+        Ok(())
     }
 
-    pub fn connect_event(
-        &mut self,
-        from: (&'static str, &'static str),
-        to: (&'static str, &'static str),
-    ) {
-        if !self.connection_valid(from.0, to.0) {
+    pub fn connect_event(&mut self, from: (&str, &str), to: (&str, &str)) {
+        if let Err(e) = self.connection_valid(from.0, to.0) {
+            println!("[error connect]: {e}");
             return;
         }
 
@@ -91,14 +155,105 @@ impl IdConnRuntime {
         self.event_conns.push(EventConn { from, to });
     }
 
-    pub fn fbs(&self) -> &HashMap<&'static str, Box<dyn Bfb>> {
+    /// connects the plug `plug.1` of the function block `plug.0` with the socket `socket.1` of `socket.0`,
+    /// the adapter is expanded into event and data connections in both directions (see `fb::adapter`)
+    ///
+    /// All ports are checked before the first connection is added, so a failing adapter connection
+    /// leaves the runtime unchanged.
+    pub fn connect_adapter(
+        &mut self,
+        adapter: &AdapterType,
+        plug: (&str, &str),
+        socket: (&str, &str),
+    ) -> Result<(), FbError> {
+        self.connection_valid(plug.0, socket.0)?;
+
+        let decl = |(fb_name, name): (&str, &str)| {
+            self.fbs[fb_name]
+                .adapters()
+                .into_iter()
+                .find(|a| a.name.eq_ignore_ascii_case(name))
+                .ok_or(FbError::unknown_adapter(name))
+        };
+
+        let plug_decl = decl(plug)?;
+        let socket_decl = decl(socket)?;
+        let (events, data) = adapter.expand((plug.0, &plug_decl), (socket.0, &socket_decl))?;
+
+        for (from_fb, from_port, to_fb, to_port) in &events {
+            self.fbs[from_fb].with_for_event(from_port)?;
+            self.fbs[to_fb].with_for_event(to_port)?;
+        }
+
+        for (from_fb, from_port, to_fb, to_port) in &data {
+            let buf = self.fbs[from_fb].read_data_out(from_port)?;
+            let found = self.fbs[from_fb].data_kind(from_port)?;
+            let expected = self.fbs[to_fb].data_kind(to_port)?;
+
+            if self.fbs[to_fb].read_data_out(to_port).is_ok() {
+                return Err(FbError::wrong_direction(to_port));
+            }
+
+            if !ty::assignable(&found, &expected) {
+                return Err(FbError::type_mismatch(to_port, expected, &buf));
+            }
+        }
+
+        for (from_fb, from_port, to_fb, to_port) in &events {
+            self.connect_event((from_fb, from_port), (to_fb, to_port));
+        }
+
+        for (from_fb, from_port, to_fb, to_port) in &data {
+            self.connect_data((from_fb, from_port), (to_fb, to_port))?;
+        }
+
+        Ok(())
+    }
+
+    /// latches a value from outside of the runtime (e.g. another resource) for a data input,
+    /// it is sampled with the next event associated WITH the input like any other data connection
+    pub fn write_remote_input(&mut self, to: (&str, &str), buf: &DataBuffer) {
+        self.remote_inputs
+            .insert((to.0.to_string(), to.1.to_string()), buf.clone());
+    }
+
+    /// sets the clock of the timed function blocks added so far (see `run_time::clock`)
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        for fb in self.fbs.values_mut() {
+            fb.set_clock(Arc::clone(&clock));
+        }
+    }
+
+    /// checks the network without executing it, see `run_time::validate`
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        validate::validate(self)
+    }
+
+    pub fn fbs(&self) -> &HashMap<String, Box<dyn Bfb>> {
         &self.fbs
     }
 
-    pub fn fbs_mut(&mut self) -> &mut HashMap<&'static str, Box<dyn Bfb>> {
+    pub fn fbs_mut(&mut self) -> &mut HashMap<String, Box<dyn Bfb>> {
         &mut self.fbs
     }
 
+    /// sets capacity and overflow policy of the input event queue of a function block
+    pub fn configure_queue(&mut self, fb_name: &str, capacity: usize, overflow: OverflowPolicy) {
+        match self.queues.get_mut(fb_name) {
+            Some(queue) => queue.configure(capacity, overflow),
+            None => println!("[error queue]: no fb with name=\"{fb_name}\" exists"),
+        }
+    }
+
+    pub fn queue(&self, fb_name: &str) -> Option<&EventQueue> {
+        self.queues.get(fb_name)
+    }
+
+    /// number of dropped input events of all function blocks
+    pub fn dropped_events(&self) -> usize {
+        self.queues.values().map(EventQueue::dropped).sum()
+    }
+
     pub fn event_conns(&self) -> &Vec<EventConn> {
         &self.event_conns
     }
@@ -109,118 +264,178 @@ impl IdConnRuntime {
 }
 
 impl IdConnRuntime {
-    fn fb_exists(&self, name: &'static str) -> bool {
-        self.fbs.iter().any(|(n, _)| *n == name)
+    fn fb_exists(&self, name: &str) -> bool {
+        self.fbs.contains_key(name)
     }
 
-    fn connection_valid(&self, from: &'static str, to: &'static str) -> bool {
-        let mut valid = false;
+    fn connection_valid(&self, from: &str, to: &str) -> Result<(), FbError> {
+        match [from, to].into_iter().find(|name| !self.fb_exists(name)) {
+            Some(name) => Err(FbError::unknown_instance(name)),
+            None => Ok(()),
+        }
+    }
 
-        match (self.fb_exists(from), self.fb_exists(to)) {
-            (true, true) => {
-                valid = true;
-            }
-            (true, false) => {
-                println!("[error to]: no fb with name=\"{to}\" exists");
-            }
-            (false, true) => {
-                println!("[error from]: no fb with name=\"{from}\" exists");
-            }
-            (false, false) => {
-                println!("[error from/to]: no fbs with names=\"{from}\"/\"{to}\" exists");
-            }
-        };
+    fn fb_mut(&mut self, name: &str) -> Result<&mut Box<dyn Bfb>, FbError> {
+        self.fbs
+            .get_mut(name)
+            .ok_or_else(|| FbError::unknown_instance(name))
+    }
+}
+
+/// checks that the function block has the data port in the expected direction
+fn data_direction(fb: &dyn Bfb, data: &str, is_input: bool) -> Result<(), FbError> {
+    let (expected, other) = if is_input {
+        (fb.data_inputs(), fb.data_outputs())
+    } else {
+        (fb.data_outputs(), fb.data_inputs())
+    };
 
-        valid
+    match (expected.contains(&data), other.contains(&data)) {
+        (true, _) => Ok(()),
+        (false, true) => Err(FbError::wrong_direction(data)),
+        (false, false) => Err(FbError::unknown_data(data)),
     }
 }
 
 // Data/Event Sending
 impl IdConnRuntime {
-    pub fn send_from(&mut self) {
+    pub fn send_from(&mut self) -> Result<(), FbError> {
+        // latch the data outputs associated WITH the active out events
+        let emitted: Vec<(String, String)> = self
+            .fbs
+            .iter()
+            .flat_map(|(name, fb)| {
+                fb.active_events_out()
+                    .into_iter()
+                    .map(|event| (name.clone(), event.to_string()))
+            })
+            .collect();
+
+        for (fb_name, event) in &emitted {
+            self.sample_out(fb_name, event)?;
+        }
+
+        // an overflowing queue does not stop the delivery to the other function blocks
+        let mut result = Ok(());
+
         // check all event connections for active from events
         for ec in &self.event_conns {
-            let from = self
-                .fbs
-                .get(ec.from.fb_name)
-                .expect("from in send_from is invalid");
+            let is_active = emitted
+                .iter()
+                .any(|(name, event)| *name == ec.from.fb_name && *event == ec.from.fb_field);
 
-            let no_active_from_event = from.active_event_out().is_none();
-            let wrong_from_event = from.active_event_out().unwrap_or("INVALID") != ec.from.fb_field;
-
-            if no_active_from_event || wrong_from_event {
+            if !is_active {
                 continue;
             }
 
-            // get associated WITH fields of data_out
-            let from_name = ec.from.fb_name;
-            let from_event = from.active_event_out().unwrap();
-            let from_fields = from.with_for_event(from_event);
-
-            // update all relevant data conn buffers
-            if !from_fields.is_empty() {
-                for dc in self.data_conns.iter_mut().filter(|conn| {
-                    let is_correct_fb = conn.from.fb_name == from_name;
-                    let targets_relevant_field = from_fields.contains(&conn.from.fb_field);
-
-                    is_correct_fb && targets_relevant_field
-                }) {
-                    dc.buf = from.read_data_out(dc.from.fb_field);
+            let from_name = &ec.from.fb_name;
+            let to_name = &ec.to.fb_name;
+            let queue = self.queues.entry(to_name.clone()).or_default();
+            let to = self.fbs.get_mut(to_name).expect("to in send_from invalid");
+
+            match queue.deliver(&ec.to.fb_field, to.as_mut()) {
+                Ok(true) => println!("{from_name} sent event {} to {to_name}", ec.to.fb_field),
+                Ok(false) => println!(
+                    "{from_name} sent event {} to {to_name}, but the full event queue of {to_name} dropped an event",
+                    ec.to.fb_field
+                ),
+                Err(e) => {
+                    if result.is_ok() {
+                        result = Err(e);
+                    }
                 }
             }
-
-            let to = self
-                .fbs
-                .get_mut(ec.to.fb_name)
-                .expect("to in send_from invalid");
-
-            let to_event_can_be_scheduled = to.active_event_in().is_none();
-            let to_name = to.instance_name();
-
-            if to_event_can_be_scheduled {
-                to.set_event_in(ec.to.fb_field);
-                println!("{from_name} sent event {} to {to_name}", ec.to.fb_field);
-            } else {
-                println!(
-                    "{from_name} failed to send event to {to_name}, since {to_name} already has an event scheduled"
-                );
-            }
         }
 
         // Note: this is bad unless we have a strict manual execution order (that we have)
         self.clear_out_events();
-    }
 
-    pub fn read_in(&mut self) {
-        // check all event connections for active to events
-        for ec in &self.event_conns {
-            let to = self
-                .fbs
-                .get_mut(ec.to.fb_name)
-                .expect("to in send_from invalid");
+        result?;
 
-            let no_active_to_event = to.active_event_in().is_none();
-            let wrong_to_event = to.active_event_in().unwrap_or("INVALID") != ec.to.fb_field;
+        self.dispatch_events()
+    }
+
+    /// sets the next queued input event of every function block without a pending input event
+    pub fn dispatch_events(&mut self) -> Result<(), FbError> {
+        for (name, queue) in self.queues.iter_mut() {
+            let Some(fb) = self.fbs.get_mut(name) else {
+                continue;
+            };
 
-            if no_active_to_event || wrong_to_event {
+            if fb.active_event_in().is_some() {
                 continue;
             }
 
-            let to_name = ec.to.fb_name;
-            let to_event = to.active_event_in().unwrap();
-            let to_fields = to.with_for_event(to_event);
-
-            // write correct data from data conn buffer to relevant target function blocks
-            if !to_fields.is_empty() {
-                for dc in self
-                    .data_conns
-                    .iter_mut()
-                    .filter(|conn| conn.to.fb_name == to_name)
-                {
-                    to.write_data_in(dc.to.fb_field, &dc.buf);
-                }
+            if let Some(event) = queue.pop() {
+                fb.set_event_in(&event)?;
             }
         }
+
+        Ok(())
+    }
+
+    /// samples the data inputs associated WITH the pending input event of every function block
+    pub fn read_in(&mut self) -> Result<(), FbError> {
+        let pending: Vec<(String, String)> = self
+            .fbs
+            .iter()
+            .filter_map(|(name, fb)| {
+                fb.active_event_in()
+                    .map(|event| (name.clone(), event.to_string()))
+            })
+            .collect();
+
+        for (fb_name, event) in &pending {
+            self.sample_in(fb_name, event)?;
+        }
+
+        Ok(())
+    }
+
+    /// writes the connection buffers into the data inputs associated WITH the input event
+    pub fn sample_in(&mut self, fb_name: &str, event: &str) -> Result<(), FbError> {
+        let Some(fb) = self.fbs.get_mut(fb_name) else {
+            return Ok(());
+        };
+
+        let remote = self
+            .remote_inputs
+            .iter()
+            .filter(|((name, _), _)| name == fb_name)
+            .map(|((_, port), buf)| (port.as_str(), buf));
+
+        let conns = self
+            .data_conns
+            .iter()
+            .filter(|conn| conn.to.fb_name == fb_name)
+            .map(|conn| (conn.to.fb_field.as_str(), &conn.buf))
+            .chain(remote);
+
+        sampling::sample_inputs(fb.as_mut(), event, conns)
+    }
+
+    /// latches the data outputs associated WITH the output event into the connection buffers
+    pub fn sample_out(&mut self, fb_name: &str, event: &str) -> Result<(), FbError> {
+        let Some(fb) = self.fbs.get(fb_name) else {
+            return Ok(());
+        };
+
+        let conns = self
+            .data_conns
+            .iter_mut()
+            .filter(|conn| conn.from.fb_name == fb_name)
+            .map(|conn| (conn.from.fb_field.as_str(), &mut conn.buf));
+
+        sampling::latch_outputs(fb.as_ref(), event, conns)
+    }
+
+    /// returns the input events connected to the given output event
+    pub fn event_targets(&self, fb_name: &str, event: &str) -> Vec<(&str, &str)> {
+        self.event_conns
+            .iter()
+            .filter(|ec| ec.from.fb_name == fb_name && ec.from.fb_field == event)
+            .map(|ec| (ec.to.fb_name.as_str(), ec.to.fb_field.as_str()))
+            .collect()
     }
 
     pub fn clear_out_events(&mut self) {
@@ -229,10 +444,37 @@ impl IdConnRuntime {
         }
     }
 
-    pub fn step(&mut self) {
+    /// invokes the execution control of every function block once,
+    /// returns whether any of them fired a transition
+    pub fn step(&mut self) -> Result<bool, FbError> {
+        let mut fired = false;
+
         for fb in self.fbs.values_mut() {
-            fb.as_mut().invoke_execution_control();
+            fired |= fb.as_mut().invoke_execution_control()?;
         }
+
+        Ok(fired)
+    }
+
+    /// repeats `read_in`, `step` and `send_from` until no function block fires and no events are pending,
+    /// returns `false` if the network is not stable after `max_cycles` cycles
+    pub fn run(&mut self, max_cycles: usize) -> Result<bool, FbError> {
+        for _ in 0..max_cycles {
+            self.read_in()?;
+
+            let fired = self.step()?;
+            let sent = self.fbs.values().any(|fb| fb.active_event_out().is_some());
+
+            self.send_from()?;
+
+            let pending = self.fbs.values().any(|fb| fb.active_event_in().is_some());
+
+            if !fired && !sent && !pending {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
     }
 }
 
@@ -255,6 +497,15 @@ impl std::fmt::Display for IdConnRuntime {
             writeln!(f, "{dc}")?;
         }
 
+        writeln!(f)?;
+        writeln!(f, "Event queues:")?;
+
+        for (name, queue) in &self.queues {
+            if !queue.is_empty() || queue.dropped() > 0 {
+                writeln!(f, "({name}) {queue}")?;
+            }
+        }
+
         writeln!(f)?;
         writeln!(f, "Function blocks:")?;
 
@@ -287,11 +538,11 @@ pub mod conns {
 
     impl std::fmt::Display for DataConn {
         fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            let from_name = self.from.fb_name;
-            let from_field = self.from.fb_field;
+            let from_name = &self.from.fb_name;
+            let from_field = &self.from.fb_field;
             let buf = &self.buf;
-            let to_name = self.to.fb_name;
-            let to_field = self.to.fb_field;
+            let to_name = &self.to.fb_name;
+            let to_field = &self.to.fb_field;
 
             write!(
                 f,
@@ -308,10 +559,10 @@ pub mod conns {
 
     impl std::fmt::Display for EventConn {
         fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            let from_name = self.from.fb_name;
-            let from_field = self.from.fb_field;
-            let to_name = self.to.fb_name;
-            let to_field = self.to.fb_field;
+            let from_name = &self.from.fb_name;
+            let from_field = &self.from.fb_field;
+            let to_name = &self.to.fb_name;
+            let to_field = &self.to.fb_field;
 
             write!(
                 f,
@@ -327,17 +578,17 @@ pub mod port {
     /// represents in/output as references to function blocks including the relevant field name
     #[derive(Debug)]
     pub struct Port<D: Direction> {
-        pub fb_name: &'static str,
-        pub fb_field: &'static str,
+        pub fb_name: String,
+        pub fb_field: String,
 
         _direction_marker: std::marker::PhantomData<D>,
     }
 
     impl<D: Direction> Port<D> {
-        pub fn new(fb_name: &'static str, field: &'static str) -> Self {
+        pub fn new(fb_name: &str, field: &str) -> Self {
             Port {
-                fb_name,
-                fb_field: field,
+                fb_name: fb_name.to_string(),
+                fb_field: field.to_string(),
                 _direction_marker: std::marker::PhantomData,
             }
         }
@@ -1,2 +1,9 @@
+pub mod clock;
+pub mod config;
+pub mod device;
 pub mod id_conn;
+pub mod queue;
 pub mod rc_conn;
+pub mod sampling;
+pub mod scheduler;
+pub mod validate;
@@ -0,0 +1,145 @@
+//! Input event queues of function blocks.
+//!
+//! A function block can only hold a single active input event. Events that arrive while
+//! another one is still pending are kept in a bounded queue per function block, instead of being lost,
+//! and are set one after another whenever the function block is idle again.
+//! If the queue is full, the `OverflowPolicy` decides which event is dropped, every dropped event is counted.
+
+use std::collections::VecDeque;
+
+use crate::fb::{Bfb, error::FbError};
+
+/// capacity of queues that were not configured explicitly
+pub const DEFAULT_CAPACITY: usize = 16;
+
+/// decides what happens to an event which arrives at a full queue
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// the arriving event is dropped
+    #[default]
+    DropNewest,
+    /// the oldest queued event is dropped to make room for the arriving event
+    DropOldest,
+    /// the arriving event is dropped and the sender receives an error
+    Error,
+}
+
+#[derive(Clone, Debug)]
+pub struct EventQueue {
+    events: VecDeque<String>,
+    capacity: usize,
+    overflow: OverflowPolicy,
+    dropped: usize,
+}
+
+impl Default for EventQueue {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY, OverflowPolicy::default())
+    }
+}
+
+impl EventQueue {
+    pub fn new(capacity: usize, overflow: OverflowPolicy) -> Self {
+        Self {
+            events: VecDeque::with_capacity(capacity),
+            capacity,
+            overflow,
+            dropped: 0,
+        }
+    }
+
+    /// appends an event, applying the overflow policy if the queue is full
+    pub fn push(&mut self, event: &str) -> Result<(), FbError> {
+        if self.events.len() < self.capacity {
+            self.events.push_back(event.to_string());
+            return Ok(());
+        }
+
+        self.dropped += 1;
+
+        match self.overflow {
+            OverflowPolicy::DropNewest => Ok(()),
+            OverflowPolicy::DropOldest => {
+                self.events.pop_front();
+
+                if self.capacity > 0 {
+                    self.events.push_back(event.to_string());
+                }
+
+                Ok(())
+            }
+            OverflowPolicy::Error => Err(FbError::event_queue_full(event, self.capacity)),
+        }
+    }
+
+    /// hands the event to `fb` right away if it is idle, otherwise the event is queued.
+    /// indicates whether the event was delivered without dropping an event
+    pub fn deliver(&mut self, event: &str, fb: &mut dyn Bfb) -> Result<bool, FbError> {
+        if fb.active_event_in().is_none() {
+            // events queued before keep their order
+            let Some(queued) = self.pop() else {
+                fb.set_event_in(event)?;
+                return Ok(true);
+            };
+
+            fb.set_event_in(&queued)?;
+        }
+
+        let dropped = self.dropped;
+        self.push(event)?;
+
+        Ok(self.dropped == dropped)
+    }
+
+    pub fn pop(&mut self) -> Option<String> {
+        self.events.pop_front()
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn overflow(&self) -> OverflowPolicy {
+        self.overflow
+    }
+
+    /// number of events dropped since the queue was created
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    /// changes capacity and policy, events beyond the new capacity are dropped from the back
+    pub fn configure(&mut self, capacity: usize, overflow: OverflowPolicy) {
+        while self.events.len() > capacity {
+            self.events.pop_back();
+            self.dropped += 1;
+        }
+
+        self.capacity = capacity;
+        self.overflow = overflow;
+    }
+}
+
+impl std::fmt::Display for EventQueue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let events: Vec<&str> = self.events.iter().map(String::as_str).collect();
+
+        write!(
+            f,
+            "[{}] ({}/{}, {:?}, dropped={})",
+            events.join(", "),
+            self.events.len(),
+            self.capacity,
+            self.overflow,
+            self.dropped
+        )
+    }
+}
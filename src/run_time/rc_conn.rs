@@ -4,7 +4,7 @@
 //!
 //! ### Dynamic dispatch
 //! Necessary to add non specified function blocks to the runtime
-//! ```
+//! ```text
 //! voters: Vec<Voter> = ...; // voters present in the runtime
 //! fbs: Vec<Box<dyn Fb>> = ...; // any `struct`s that implement the `Fb` trait
 //! ```
@@ -16,12 +16,22 @@
 //! These connections are external to function blocks.
 //! This implementation uses `Rc<RefCell<dyn Fb>>` fields to store references to the function blocks.
 //! However, an `Arena` style implementation might be better, but was not evaluated here.
+//!
+//! Events sent to a function block with a pending input event are kept in its `EventQueue`.
 
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
-use crate::fb::{
-    Bfb, data,
-    direction::{In, Out},
+use crate::{
+    fb::{
+        Bfb,
+        data::{self, comm::DataBuffer},
+        direction::{In, Out},
+        error::FbError,
+    },
+    run_time::{
+        queue::{EventQueue, OverflowPolicy},
+        sampling,
+    },
 };
 
 use conns::{DataConn, EventConn};
@@ -32,6 +42,7 @@ pub struct RcConnRuntime {
     data_conns: Vec<DataConn>,
     event_conns: Vec<EventConn>,
     fbs: Vec<Rc<RefCell<dyn Bfb>>>,
+    queues: HashMap<String, EventQueue>,
 }
 
 impl RcConnRuntime {
@@ -50,39 +61,55 @@ impl RcConnRuntime {
             return;
         }
 
+        self.queues
+            .insert(fb.instance_name().to_string(), EventQueue::default());
         self.fbs.push(Rc::new(RefCell::new(fb)));
     }
 
-    /// create a `DataConn` between `Data<Out, T>` and `Data<In, T>` fields of 2 seperate function blocks
-    /// -> `T` has to be the same type for both
-    pub fn connect_data(&mut self, from: (usize, &'static str), to: (usize, &'static str)) {
+    /// create a `DataConn` between `Data<Out, T>` and `Data<In, U>` fields of 2 seperate function blocks
+    /// -> `T` has to be the same type as `U` or be widened to it (see `data::ty::widens_to`)
+    pub fn connect_data(&mut self, from: (usize, &str), to: (usize, &str)) -> Result<(), FbError> {
         if from.0 == to.0 {
             println!("cannot connect a function block with itself");
-            return;
+            return Ok(());
         }
 
         let from = Port::<Out>::new(self.fbs[from.0].clone(), from.1);
         let to = Port::<In>::new(self.fbs[to.0].clone(), to.1);
-        let buf = from.fb_ref.borrow().read_data_out(from.field);
 
-        // verify ports use same data types
+        // verify the data type of the output fits the input
         {
-            let data_kind_eq = data::ty::kind_eq(
-                &from.fb_ref.borrow().data_kind(from.field),
-                &to.fb_ref.borrow().data_kind(to.field),
+            let kinds = (
+                from.fb_ref.borrow().data_kind(&from.field)?,
+                to.fb_ref.borrow().data_kind(&to.field)?,
             );
 
-            if !data_kind_eq {
-                println!("({from}) and ({to}) use different DataTypes!");
-                return;
+            if !data::ty::assignable(&kinds.0, &kinds.1) {
+                println!("({from}) and ({to}) use incompatible DataTypes!");
+                return Ok(());
+            }
+
+            // generic ports are fixed to the kind of the other end
+            match data::ty::fixing(&kinds.0, &kinds.1) {
+                Some(data::ty::Fixing::Source(kind)) => {
+                    from.fb_ref.borrow_mut().fix_data_kind(&from.field, &kind)?
+                }
+                Some(data::ty::Fixing::Destination(kind)) => {
+                    to.fb_ref.borrow_mut().fix_data_kind(&to.field, &kind)?
+                }
+                None => {}
             }
         }
 
+        let buf = from.fb_ref.borrow().read_data_out(&from.field)?;
+
         self.data_conns.push(DataConn { from, to, buf });
+
+        Ok(())
     }
 
     /// create an `EventConn` between `Event<Out>` and `Event<In>` fields of 2 seperate function blocks
-    pub fn connect_event(&mut self, from: (usize, &'static str), to: (usize, &'static str)) {
+    pub fn connect_event(&mut self, from: (usize, &str), to: (usize, &str)) {
         if from.0 == to.0 {
             println!("cannot connect a function block with itself");
             return;
@@ -93,6 +120,14 @@ impl RcConnRuntime {
 
         self.event_conns.push(EventConn { from, to });
     }
+
+    /// sets capacity and overflow policy of the input event queue of a function block
+    pub fn configure_queue(&mut self, fb_name: &str, capacity: usize, overflow: OverflowPolicy) {
+        match self.queues.get_mut(fb_name) {
+            Some(queue) => queue.configure(capacity, overflow),
+            None => println!("[error queue]: no fb with name=\"{fb_name}\" exists"),
+        }
+    }
 }
 
 // getters
@@ -101,6 +136,15 @@ impl RcConnRuntime {
         &self.fbs
     }
 
+    pub fn queue(&self, fb_name: &str) -> Option<&EventQueue> {
+        self.queues.get(fb_name)
+    }
+
+    /// number of dropped input events of all function blocks
+    pub fn dropped_events(&self) -> usize {
+        self.queues.values().map(EventQueue::dropped).sum()
+    }
+
     pub fn event_conns(&self) -> &Vec<EventConn> {
         &self.event_conns
     }
@@ -112,57 +156,99 @@ impl RcConnRuntime {
 
 impl RcConnRuntime {
     /// updates all data connection buffers where the `from` function block has an active out event
-    pub fn send_from(&mut self) {
+    pub fn send_from(&mut self) -> Result<(), FbError> {
+        // latch the data outputs associated WITH the active out events
+        for fb_ref in &self.fbs {
+            let fb = fb_ref.borrow();
+            let name = fb.instance_name();
+
+            for event in fb.active_events_out() {
+                let conns = self
+                    .data_conns
+                    .iter_mut()
+                    .filter(|dc| name == dc.from_name())
+                    .map(|dc| (dc.from.field.as_str(), &mut dc.buf));
+
+                sampling::latch_outputs(&*fb, event, conns)?;
+            }
+        }
+
+        // an overflowing queue does not stop the delivery to the other function blocks
+        let mut result = Ok(());
+
         for e_conn in &self.event_conns {
-            if !e_conn.from_out_active() {
+            if !e_conn.from_field_active() {
                 continue;
             }
 
             let name = e_conn.from_name();
-            let fields = e_conn.from_out_fields();
 
-            if !fields.is_empty() {
-                for d_conn in self
-                    .data_conns
-                    .iter_mut()
-                    .filter(|dc| name == dc.from_name() && fields.contains(&dc.from.field))
-                {
-                    d_conn.load_from();
+            let queue = self.queues.entry(e_conn.to_name()).or_default();
+
+            match e_conn.send(queue) {
+                Ok(true) => println!("{name} sent event"),
+                Ok(false) => println!("{name} sent event, but a full event queue dropped an event"),
+                Err(e) => {
+                    if result.is_ok() {
+                        result = Err(e);
+                    }
                 }
             }
+        }
+
+        self.clear_out_events();
+
+        result?;
+
+        self.dispatch_events()
+    }
+
+    /// sets the next queued input event of every function block without a pending input event
+    pub fn dispatch_events(&mut self) -> Result<(), FbError> {
+        for fb_ref in &self.fbs {
+            let mut fb = fb_ref.borrow_mut();
+
+            if fb.active_event_in().is_some() {
+                continue;
+            }
+
+            let event = self
+                .queues
+                .get_mut(fb.instance_name())
+                .and_then(EventQueue::pop);
 
-            if !e_conn.send() {
-                println!("{name} failed to send event");
-            } else {
-                println!("{name} sent event")
+            if let Some(event) = event {
+                fb.set_event_in(&event)?;
             }
         }
 
-        for e_conn in &self.event_conns {
-            e_conn.from.fb_ref.borrow_mut().clear_event_out();
-        }
+        Ok(())
     }
 
     /// reads all data connection buffers into the associated data fields where 'to' function block has an active in event
-    pub fn read_in(&mut self) {
+    pub fn read_in(&mut self) -> Result<(), FbError> {
         for e_conn in &self.event_conns {
             if !e_conn.to_in_active() {
                 continue;
             }
 
             let name = e_conn.to_name();
-            let fields = e_conn.to_in_fields();
 
-            if !fields.is_empty() {
-                for d_conn in self
-                    .data_conns
-                    .iter_mut()
-                    .filter(|dc| name == dc.to_name() && fields.contains(&dc.to.field))
-                {
-                    d_conn.fetch_to();
-                }
-            }
+            // the names are resolved before the function block is borrowed mutably
+            let conns: Vec<(&str, &DataBuffer)> = self
+                .data_conns
+                .iter()
+                .filter(|dc| name == dc.to_name())
+                .map(|dc| (dc.to.field.as_str(), &dc.buf))
+                .collect();
+
+            let mut fb_to = e_conn.to.fb_ref.borrow_mut();
+            let event = fb_to.active_event_in().unwrap().to_string();
+
+            sampling::sample_inputs(&mut *fb_to, &event, conns)?;
         }
+
+        Ok(())
     }
 
     /// clears all out events of function blocks
@@ -172,11 +258,43 @@ impl RcConnRuntime {
         }
     }
 
-    /// invokes the execution control of all function block
-    pub fn step(&self) {
+    /// invokes the execution control of all function block,
+    /// returns whether any of them fired a transition
+    pub fn step(&self) -> Result<bool, FbError> {
+        let mut fired = false;
+
         for fb in &self.fbs {
-            fb.borrow_mut().invoke_execution_control();
+            fired |= fb.borrow_mut().invoke_execution_control()?;
+        }
+
+        Ok(fired)
+    }
+
+    /// repeats `read_in`, `step` and `send_from` until no function block fires and no events are pending,
+    /// returns `false` if the network is not stable after `max_cycles` cycles
+    pub fn run(&mut self, max_cycles: usize) -> Result<bool, FbError> {
+        for _ in 0..max_cycles {
+            self.read_in()?;
+
+            let fired = self.step()?;
+            let sent = self
+                .fbs
+                .iter()
+                .any(|fb| fb.borrow().active_event_out().is_some());
+
+            self.send_from()?;
+
+            let pending = self
+                .fbs
+                .iter()
+                .any(|fb| fb.borrow().active_event_in().is_some());
+
+            if !fired && !sent && !pending {
+                return Ok(true);
+            }
         }
+
+        Ok(false)
     }
 }
 
@@ -195,6 +313,15 @@ impl std::fmt::Display for RcConnRuntime {
             writeln!(f, "{dc}")?;
         }
 
+        writeln!(f)?;
+        writeln!(f, "Event queues:")?;
+
+        for (name, queue) in &self.queues {
+            if !queue.is_empty() || queue.dropped() > 0 {
+                writeln!(f, "({name}) {queue}")?;
+            }
+        }
+
         writeln!(f)?;
         writeln!(f, "Function blocks:")?;
 
@@ -207,9 +334,13 @@ impl std::fmt::Display for RcConnRuntime {
 }
 
 pub mod conns {
-    use crate::fb::{
-        data::comm::DataBuffer,
-        direction::{In, Out},
+    use crate::{
+        fb::{
+            data::comm::DataBuffer,
+            direction::{In, Out},
+            error::FbError,
+        },
+        run_time::queue::EventQueue,
     };
 
     use super::port::Port;
@@ -222,41 +353,23 @@ pub mod conns {
         pub buf: DataBuffer,
     }
 
-    impl DataConn {
-        pub fn load_from(&mut self) {
-            self.buf = self
-                .from
-                .fb_ref
-                .borrow()
-                .read_data_out(self.from.field)
-                .clone();
-        }
-
-        pub fn fetch_to(&self) {
-            self.to
-                .fb_ref
-                .borrow_mut()
-                .write_data_in(self.to.field, &self.buf);
-        }
-    }
-
     // utils for easier usage for now
     impl DataConn {
-        pub fn from_name(&self) -> &'static str {
-            self.from.fb_ref.borrow().instance_name()
+        pub fn from_name(&self) -> String {
+            self.from.fb_ref.borrow().instance_name().to_string()
         }
 
-        pub fn to_name(&self) -> &'static str {
-            self.to.fb_ref.borrow().instance_name()
+        pub fn to_name(&self) -> String {
+            self.to.fb_ref.borrow().instance_name().to_string()
         }
     }
 
     impl std::fmt::Display for DataConn {
         fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
             let from_name = self.from_name();
-            let from_field = self.from.field;
+            let from_field = &self.from.field;
             let to_name = self.to_name();
-            let to_field = self.to.field;
+            let to_field = &self.to.field;
             let buf = &self.buf;
 
             write!(
@@ -274,24 +387,11 @@ pub mod conns {
     }
 
     impl EventConn {
-        /// sends notification of the `from` event output to the `to` event input.
-        /// indicates successful sending with boolean flag
-        pub fn send(&self) -> bool {
-            let mut sent = false;
-            let f = self.from.fb_ref.borrow();
-            let mut t = self.to.fb_ref.borrow_mut();
-
-            if let Some(e) = f.active_event_out() {
-                let relevant_field = e == self.from.field;
-                let no_active_in = t.active_event_in().is_none();
-
-                if relevant_field && no_active_in {
-                    t.set_event_in(self.to.field);
-                    sent = true;
-                }
-            }
-
-            sent
+        /// notifies the `to` event input about the active `from` event output,
+        /// the event is queued if the `to` function block is busy (see `EventQueue::deliver`).
+        /// indicates successful sending (no event was dropped) with boolean flag
+        pub fn send(&self, queue: &mut EventQueue) -> Result<bool, FbError> {
+            queue.deliver(&self.to.field, &mut *self.to.fb_ref.borrow_mut())
         }
     }
 
@@ -301,47 +401,34 @@ pub mod conns {
             self.from.fb_ref.borrow().active_event_out().is_some()
         }
 
-        pub fn to_in_active(&self) -> bool {
-            self.to.fb_ref.borrow().active_event_in().is_some()
-        }
-
-        pub fn from_name(&self) -> &'static str {
-            self.from.fb_ref.borrow().instance_name()
+        /// whether the `from` event output of this connection is active
+        pub fn from_field_active(&self) -> bool {
+            self.from
+                .fb_ref
+                .borrow()
+                .active_events_out()
+                .contains(&self.from.field.as_str())
         }
 
-        pub fn to_name(&self) -> &'static str {
-            self.to.fb_ref.borrow().instance_name()
+        pub fn to_in_active(&self) -> bool {
+            self.to.fb_ref.borrow().active_event_in().is_some()
         }
 
-        pub fn from_out_fields(&self) -> Vec<&'static str> {
-            if !self.from_out_active() {
-                return vec![];
-            }
-
-            let fb_from = self.from.fb_ref.borrow();
-            let event = fb_from.active_event_out().unwrap();
-
-            fb_from.with_for_event(event)
+        pub fn from_name(&self) -> String {
+            self.from.fb_ref.borrow().instance_name().to_string()
         }
 
-        pub fn to_in_fields(&self) -> Vec<&'static str> {
-            if !self.to_in_active() {
-                return vec![];
-            }
-
-            let fb_to = self.to.fb_ref.borrow();
-            let event = fb_to.active_event_in().unwrap();
-
-            fb_to.with_for_event(event)
+        pub fn to_name(&self) -> String {
+            self.to.fb_ref.borrow().instance_name().to_string()
         }
     }
 
     impl std::fmt::Display for EventConn {
         fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
             let from_name = self.from_name();
-            let from_field = self.from.field;
+            let from_field = &self.from.field;
             let to_name = self.to_name();
-            let to_field = self.to.field;
+            let to_field = &self.to.field;
             let active = self.from_out_active();
 
             write!(
@@ -361,16 +448,16 @@ pub mod port {
     #[derive(Debug)]
     pub struct Port<D: Direction> {
         pub fb_ref: Rc<RefCell<dyn Bfb>>,
-        pub field: &'static str,
+        pub field: String,
 
         _direction_marker: std::marker::PhantomData<D>,
     }
 
     impl<D: Direction> Port<D> {
-        pub fn new(fb_ref: Rc<RefCell<dyn Bfb>>, field: &'static str) -> Self {
+        pub fn new(fb_ref: Rc<RefCell<dyn Bfb>>, field: &str) -> Self {
             Port {
                 fb_ref,
-                field,
+                field: field.to_string(),
                 _direction_marker: std::marker::PhantomData,
             }
         }
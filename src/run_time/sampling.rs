@@ -0,0 +1,67 @@
+//! WITH semantics of data connections, shared by all runtimes.
+//!
+//! Data only travels along a data connection together with an event:
+//! - when an output event is emitted, the data outputs associated WITH it are latched into
+//!   the buffers of their data connections, other outputs keep their previously latched value
+//! - when an input event is delivered, only the data inputs associated WITH it are sampled
+//!   from the buffers of their data connections, other inputs keep their value
+//!
+//! Values of a narrower type are widened to the type of the data input when they are sampled (see `ty::widens_to`).
+//!
+//! Both functions receive the connections of a single function block as `(port, buffer)` pairs,
+//! which keeps them independent of how a runtime stores its connections.
+
+use crate::fb::{
+    Bfb,
+    data::{comm::DataBuffer, convert},
+    error::FbError,
+};
+
+/// writes the buffers of the connections into the data inputs associated WITH the input event,
+/// `conns` are the data connections ending at the function block
+pub fn sample_inputs<'a>(
+    fb: &mut dyn Bfb,
+    event: &str,
+    conns: impl IntoIterator<Item = (&'a str, &'a DataBuffer)>,
+) -> Result<(), FbError> {
+    let fields: Vec<String> = fb
+        .with_for_event(event)?
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+
+    for (field, buf) in conns {
+        if !fields.iter().any(|f| f == field) {
+            continue;
+        }
+
+        match fb
+            .data_kind(field)
+            .ok()
+            .and_then(|kind| convert::widen(buf, &kind))
+        {
+            Some(widened) => fb.write_data_in(field, &widened)?,
+            None => fb.write_data_in(field, buf)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// reads the data outputs associated WITH the output event into the buffers of the connections,
+/// `conns` are the data connections starting at the function block
+pub fn latch_outputs<'a>(
+    fb: &dyn Bfb,
+    event: &str,
+    conns: impl IntoIterator<Item = (&'a str, &'a mut DataBuffer)>,
+) -> Result<(), FbError> {
+    let fields = fb.with_for_event(event)?;
+
+    for (field, buf) in conns {
+        if fields.contains(&field) {
+            *buf = fb.read_data_out(field)?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,255 @@
+//! Event driven execution of an `IdConnRuntime`.
+//!
+//! Instead of manually interleaving `step`, `send_from` and `read_in`,
+//! the scheduler keeps a FIFO queue of pending event deliveries. Delivering an event:
+//! 1. samples the data inputs associated WITH the event from the data connection buffers
+//! 2. sets the input event and invokes the execution control until it is stable
+//! 3. loads the data outputs associated WITH every emitted output event into the connection buffers
+//!    and queues a delivery for every event connection leaving the emitted output
+//!
+//! The queue is processed until it is empty, so every event chain runs to completion
+//! in the order the events were emitted. Since a function block only receives the next event
+//! after the previous one has been handled, no event is dropped as "already pending".
+//!
+//! Timed function blocks are invoked once their deadline passed, either on the current time of a clock (`poll_timers`)
+//! or while advancing a simulated clock from deadline to deadline (`advance`).
+//!
+//! Function blocks connected to other resources are `watch`ed: their emitted output events are collected
+//! together with the values of the associated WITH outputs, so they can be forwarded (see `run_time::device`).
+//!
+//! ```text
+//! let mut scheduler = Scheduler::default();
+//! scheduler.post("ctu0", "cu");
+//! scheduler.run(&mut rt, 100)?;
+//! ```
+
+use std::{collections::VecDeque, time::Duration};
+
+use crate::{
+    fb::{data::comm::DataBuffer, error::FbError},
+    run_time::{
+        clock::{Clock, SimClock},
+        id_conn::IdConnRuntime,
+    },
+};
+
+/// upper bound of ECC steps per delivered event, protects against charts that never become stable
+const MAX_ECC_STEPS: usize = 1000;
+
+/// an input event waiting to be delivered to a function block
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Delivery {
+    pub fb_name: String,
+    pub event: String,
+}
+
+impl std::fmt::Display for Delivery {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.fb_name, self.event)
+    }
+}
+
+/// an output event of a watched function block, including the data outputs associated WITH it latched at emission
+#[derive(Clone, Debug)]
+pub struct Emission {
+    pub fb_name: String,
+    pub event: String,
+    pub data: Vec<(String, DataBuffer)>,
+}
+
+#[derive(Default, Debug)]
+pub struct Scheduler {
+    queue: VecDeque<Delivery>,
+    delivered: usize,
+    watched: Vec<String>,
+    emissions: Vec<Emission>,
+}
+
+impl Scheduler {
+    /// queues an input event, e.g. a start event from outside of the network
+    pub fn post(&mut self, fb_name: &str, event: &str) {
+        self.queue.push_back(Delivery {
+            fb_name: fb_name.to_string(),
+            event: event.to_string(),
+        });
+    }
+
+    /// deliveries that have not been processed yet, in the order they will be processed
+    pub fn pending(&self) -> &VecDeque<Delivery> {
+        &self.queue
+    }
+
+    /// number of events delivered since the scheduler was created
+    pub fn delivered(&self) -> usize {
+        self.delivered
+    }
+
+    /// collects every output event emitted by the function block, see `take_emissions`
+    pub fn watch(&mut self, fb_name: &str) {
+        if !self.watched.iter().any(|name| name == fb_name) {
+            self.watched.push(fb_name.to_string());
+        }
+    }
+
+    /// returns the output events emitted by watched function blocks since the last call
+    pub fn take_emissions(&mut self) -> Vec<Emission> {
+        std::mem::take(&mut self.emissions)
+    }
+
+    /// processes the queue until it is empty,
+    /// returns `false` if deliveries are still pending after `max_deliveries`
+    pub fn run(&mut self, rt: &mut IdConnRuntime, max_deliveries: usize) -> Result<bool, FbError> {
+        for _ in 0..max_deliveries {
+            if !self.deliver_next(rt)? {
+                return Ok(true);
+            }
+        }
+
+        Ok(self.queue.is_empty())
+    }
+
+    /// delivers the oldest pending event,
+    /// returns `false` if there was nothing to deliver
+    pub fn deliver_next(&mut self, rt: &mut IdConnRuntime) -> Result<bool, FbError> {
+        let Some(delivery) = self.queue.pop_front() else {
+            return Ok(false);
+        };
+
+        self.delivered += 1;
+
+        let Delivery { fb_name, event } = &delivery;
+
+        if !rt.fbs().contains_key(fb_name) {
+            println!("[error scheduler]: no fb with name=\"{fb_name}\" exists");
+            return Ok(true);
+        }
+
+        rt.sample_in(fb_name, event)?;
+        rt.fbs_mut().get_mut(fb_name).unwrap().set_event_in(event)?;
+
+        self.execute(rt, fb_name)?;
+
+        Ok(true)
+    }
+
+    /// invokes the execution control of every function block whose deadline passed at `now`
+    /// (see `run_time::clock`) and processes the resulting event chains,
+    /// returns `false` if deliveries are still pending after `max_deliveries`
+    pub fn poll_timers(
+        &mut self,
+        rt: &mut IdConnRuntime,
+        now: Duration,
+        max_deliveries: usize,
+    ) -> Result<bool, FbError> {
+        let mut expired: Vec<(Duration, String)> = rt
+            .fbs()
+            .iter()
+            .filter_map(|(name, fb)| fb.deadline().map(|d| (d, name.clone())))
+            .filter(|(deadline, _)| *deadline <= now)
+            .collect();
+
+        // earlier deadlines first, equal ones in a reproducible order
+        expired.sort();
+
+        for (_, fb_name) in &expired {
+            self.execute(rt, fb_name)?;
+        }
+
+        self.run(rt, max_deliveries)
+    }
+
+    /// advances the simulated clock `by` the given time, stopping at every deadline on the way,
+    /// so timed function blocks fire in order and at the exact time they expire,
+    /// returns `false` if the network did not become stable within `max_deliveries` at any point,
+    /// fails if timers still expire after `max_deliveries` polls
+    pub fn advance(
+        &mut self,
+        rt: &mut IdConnRuntime,
+        clock: &SimClock,
+        by: Duration,
+        max_deliveries: usize,
+    ) -> Result<bool, FbError> {
+        let until = clock.now() + by;
+
+        if !self.run(rt, max_deliveries)? {
+            return Ok(false);
+        }
+
+        let mut expiring = None;
+
+        for _ in 0..max_deliveries {
+            let next = rt
+                .fbs()
+                .iter()
+                .filter_map(|(name, fb)| fb.deadline().map(|d| (d, name)))
+                .filter(|(deadline, _)| *deadline <= until)
+                .min();
+
+            let Some((next, fb_name)) = next else {
+                clock.set(until);
+                return Ok(true);
+            };
+
+            expiring = Some(fb_name.clone());
+            clock.set(next);
+
+            if !self.poll_timers(rt, clock.now(), max_deliveries)? {
+                return Ok(false);
+            }
+        }
+
+        // a timer that keeps expiring without the time advancing (e.g. `E_CYCLE` with `DT=T#0s`)
+        Err(FbError::not_stable(
+            expiring.as_deref().unwrap_or_default(),
+            max_deliveries,
+        ))
+    }
+
+    /// invokes the execution control of the function block until it is stable
+    fn execute(&mut self, rt: &mut IdConnRuntime, fb_name: &str) -> Result<(), FbError> {
+        for _ in 0..MAX_ECC_STEPS {
+            let fb = rt.fbs_mut().get_mut(fb_name).unwrap();
+            let fired = fb.invoke_execution_control()?;
+            let emitted: Vec<String> = fb
+                .active_events_out()
+                .into_iter()
+                .map(str::to_string)
+                .collect();
+
+            fb.clear_event_out();
+
+            for out in &emitted {
+                rt.sample_out(fb_name, out)?;
+
+                if self.watched.iter().any(|name| name == fb_name) {
+                    self.emissions.push(emission(rt, fb_name, out)?);
+                }
+
+                for (to_name, to_event) in rt.event_targets(fb_name, out) {
+                    self.post(to_name, to_event);
+                }
+            }
+
+            if !fired {
+                return Ok(());
+            }
+        }
+
+        Err(FbError::not_stable(fb_name, MAX_ECC_STEPS))
+    }
+}
+
+fn emission(rt: &IdConnRuntime, fb_name: &str, event: &str) -> Result<Emission, FbError> {
+    let fb = &rt.fbs()[fb_name];
+    let mut data = vec![];
+
+    for field in fb.with_for_event(event)? {
+        data.push((field.to_string(), fb.read_data_out(field)?));
+    }
+
+    Ok(Emission {
+        fb_name: fb_name.to_string(),
+        event: event.to_string(),
+        data,
+    })
+}
@@ -0,0 +1,394 @@
+//! Static validation of the function block network of an `IdConnRuntime`.
+//!
+//! `connect_event` only checks that both instances exist and `connect_data` only checks the kinds of the
+//! ports, so a network can contain connections which fail (or silently do nothing) once events are delivered.
+//! `validate` checks the whole network without executing it and returns every finding as a `Diagnostic`:
+//! - connections to unknown instances or ports
+//! - connections using a port in the wrong direction (e.g. starting at an input)
+//! - data connections between `DataKind`s which are not assignable (e.g. kinds changed after connecting)
+//! - data inputs associated WITH a connected event input, which are not connected themselves
+//! - data inputs driven by more than one connection
+//! - cycles of event connections, which might keep the network busy forever
+//!
+//! The last two checks can not know about values written from outside of the network
+//! (parameters, remote inputs) or about blocks breaking a cycle (e.g. `E_DELAY`),
+//! so they are reported as warnings.
+
+use std::{collections::HashMap, fmt::Display};
+
+use crate::{
+    fb::{
+        data::ty::{self, DataKind},
+        error::FbError,
+    },
+    run_time::{config::PortRef, id_conn::IdConnRuntime},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Clone, Debug)]
+pub enum Diagnostic {
+    UnknownInstance(String),
+    UnknownPort {
+        port: PortRef,
+        error: FbError,
+    },
+    /// the port exists, but `input` tells which direction the connection requires
+    WrongDirection {
+        port: PortRef,
+        input: bool,
+    },
+    /// the kind of the source is neither equal to nor widened to the kind of the destination
+    KindMismatch {
+        source: PortRef,
+        destination: PortRef,
+        source_kind: DataKind,
+        destination_kind: DataKind,
+    },
+    /// a data input associated WITH a connected event input has no incoming data connection
+    UnconnectedInput {
+        port: PortRef,
+        event: String,
+    },
+    MultipleDrivers {
+        port: PortRef,
+        sources: Vec<PortRef>,
+    },
+    /// instances connected in a cycle by event connections
+    EventCycle(Vec<String>),
+}
+
+impl Diagnostic {
+    pub fn severity(&self) -> Severity {
+        match self {
+            Diagnostic::UnconnectedInput { .. } | Diagnostic::EventCycle(_) => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.severity() {
+            Severity::Error => write!(f, "[error] ")?,
+            Severity::Warning => write!(f, "[warning] ")?,
+        }
+
+        match self {
+            Diagnostic::UnknownInstance(name) => write!(f, "unknown instance \"{name}\""),
+            Diagnostic::UnknownPort { port, error } => write!(f, "{port}: {error}"),
+            Diagnostic::WrongDirection { port, input } => write!(
+                f,
+                "{port}: is connected as an {}, but is an {}",
+                if *input { "input" } else { "output" },
+                if *input { "output" } else { "input" }
+            ),
+            Diagnostic::KindMismatch {
+                source,
+                destination,
+                source_kind,
+                destination_kind,
+            } => write!(
+                f,
+                "can not connect {source} ({}) to {destination} ({})",
+                source_kind.iec_name(),
+                destination_kind.iec_name()
+            ),
+            Diagnostic::UnconnectedInput { port, event } => write!(
+                f,
+                "{port}: is sampled WITH the connected event \"{event}\", but has no data connection"
+            ),
+            Diagnostic::MultipleDrivers { port, sources } => {
+                let sources: Vec<_> = sources.iter().map(PortRef::to_string).collect();
+                write!(f, "{port}: is driven by {}", sources.join(", "))
+            }
+            Diagnostic::EventCycle(fbs) => {
+                write!(
+                    f,
+                    "event connections form a cycle between {}",
+                    fbs.join(", ")
+                )
+            }
+        }
+    }
+}
+
+/// checks the network of the runtime, see the module documentation
+pub fn validate(rt: &IdConnRuntime) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+
+    for ec in rt.event_conns() {
+        let source = port_ref(&ec.from.fb_name, &ec.from.fb_field);
+        let destination = port_ref(&ec.to.fb_name, &ec.to.fb_field);
+
+        for (port, input) in [(source, false), (destination, true)] {
+            if let Err(d) = check_port(rt, &port, input, false) {
+                diagnostics.push(d);
+            }
+        }
+    }
+
+    let mut drivers: HashMap<(String, String), Vec<PortRef>> = HashMap::new();
+
+    for dc in rt.data_conns() {
+        let source = port_ref(&dc.from.fb_name, &dc.from.fb_field);
+        let destination = port_ref(&dc.to.fb_name, &dc.to.fb_field);
+
+        let source_kind = check_port(rt, &source, false, true);
+        let destination_kind = check_port(rt, &destination, true, true);
+
+        match (source_kind, destination_kind) {
+            (Ok(Some(source_kind)), Ok(Some(destination_kind)))
+                if !ty::assignable(&source_kind, &destination_kind) =>
+            {
+                diagnostics.push(Diagnostic::KindMismatch {
+                    source: source.clone(),
+                    destination: destination.clone(),
+                    source_kind,
+                    destination_kind,
+                });
+            }
+            (source_kind, destination_kind) => {
+                diagnostics.extend(source_kind.err());
+                diagnostics.extend(destination_kind.err());
+            }
+        }
+
+        drivers.entry(key(&destination)).or_default().push(source);
+    }
+
+    let mut driven: Vec<_> = drivers.into_iter().collect();
+    driven.sort_by(|a, b| a.0.cmp(&b.0));
+
+    for ((instance, port), sources) in &driven {
+        if sources.len() > 1 {
+            diagnostics.push(Diagnostic::MultipleDrivers {
+                port: port_ref(instance, port),
+                sources: sources.clone(),
+            });
+        }
+    }
+
+    diagnostics.extend(unconnected_inputs(rt, &driven));
+    diagnostics.extend(event_cycles(rt));
+
+    diagnostics
+}
+
+/// checks that the port exists with the required direction, returns the `DataKind` of data ports
+#[allow(clippy::result_large_err)]
+fn check_port(
+    rt: &IdConnRuntime,
+    port: &PortRef,
+    input: bool,
+    data: bool,
+) -> Result<Option<DataKind>, Diagnostic> {
+    let Some(fb) = rt.fbs().get(&port.instance) else {
+        return Err(Diagnostic::UnknownInstance(port.instance.clone()));
+    };
+
+    let (inputs, outputs) = if data {
+        (fb.data_inputs(), fb.data_outputs())
+    } else {
+        (fb.event_inputs(), fb.event_outputs())
+    };
+
+    let (expected, other) = if input {
+        (inputs, outputs)
+    } else {
+        (outputs, inputs)
+    };
+
+    if other.iter().any(|p| p.eq_ignore_ascii_case(&port.port)) {
+        return Err(Diagnostic::WrongDirection {
+            port: port.clone(),
+            input,
+        });
+    }
+
+    if !expected.iter().any(|p| p.eq_ignore_ascii_case(&port.port)) {
+        let error = if data {
+            FbError::unknown_data(&port.port)
+        } else {
+            FbError::unknown_event(&port.port)
+        };
+
+        return Err(Diagnostic::UnknownPort {
+            port: port.clone(),
+            error,
+        });
+    }
+
+    if !data {
+        return Ok(None);
+    }
+
+    fb.data_kind(&port.port)
+        .map(Some)
+        .map_err(|error| Diagnostic::UnknownPort {
+            port: port.clone(),
+            error,
+        })
+}
+
+/// data inputs WITH event inputs that have an incoming event connection, but no data connection
+fn unconnected_inputs(
+    rt: &IdConnRuntime,
+    driven: &[((String, String), Vec<PortRef>)],
+) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    let mut events: Vec<_> = rt
+        .event_conns()
+        .iter()
+        .map(|ec| port_ref(&ec.to.fb_name, &ec.to.fb_field))
+        .collect();
+
+    events.sort_by_key(key);
+    events.dedup();
+
+    for event in events {
+        let Some(fb) = rt.fbs().get(&event.instance) else {
+            continue;
+        };
+
+        let Ok(with) = fb.with_for_event(&event.port) else {
+            continue;
+        };
+
+        for data in with {
+            let port = port_ref(&event.instance, data);
+
+            let connected = driven.iter().any(|((instance, input), _)| {
+                *instance == port.instance && input.eq_ignore_ascii_case(&port.port)
+            });
+
+            let reported = diagnostics
+                .iter()
+                .any(|d| matches!(d, Diagnostic::UnconnectedInput { port: p, .. } if *p == port));
+
+            if !connected && !reported {
+                diagnostics.push(Diagnostic::UnconnectedInput {
+                    port,
+                    event: event.port.clone(),
+                });
+            }
+        }
+    }
+
+    diagnostics
+}
+
+/// strongly connected components of the instances linked by event connections (Tarjan's algorithm),
+/// every component with more than one instance, or a single instance connected to itself, is a cycle
+fn event_cycles(rt: &IdConnRuntime) -> Vec<Diagnostic> {
+    let mut names: Vec<&str> = rt.fbs().keys().map(String::as_str).collect();
+    names.sort();
+
+    let index_of = |name: &str| names.iter().position(|n| *n == name);
+
+    let mut successors = vec![vec![]; names.len()];
+
+    for ec in rt.event_conns() {
+        if let (Some(from), Some(to)) = (index_of(&ec.from.fb_name), index_of(&ec.to.fb_name))
+            && !successors[from].contains(&to)
+        {
+            successors[from].push(to);
+        }
+    }
+
+    let mut tarjan = Tarjan {
+        successors: &successors,
+        index: vec![None; names.len()],
+        low_link: vec![0; names.len()],
+        on_stack: vec![false; names.len()],
+        stack: vec![],
+        next_index: 0,
+        components: vec![],
+    };
+
+    for node in 0..names.len() {
+        if tarjan.index[node].is_none() {
+            tarjan.visit(node);
+        }
+    }
+
+    let mut cycles: Vec<_> = tarjan
+        .components
+        .into_iter()
+        .filter(|c| c.len() > 1 || successors[c[0]].contains(&c[0]))
+        .map(|mut c| {
+            c.sort();
+            c.into_iter()
+                .map(|i| names[i].to_string())
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    cycles.sort();
+    cycles.into_iter().map(Diagnostic::EventCycle).collect()
+}
+
+struct Tarjan<'a> {
+    successors: &'a [Vec<usize>],
+    index: Vec<Option<usize>>,
+    low_link: Vec<usize>,
+    on_stack: Vec<bool>,
+    stack: Vec<usize>,
+    next_index: usize,
+    components: Vec<Vec<usize>>,
+}
+
+impl Tarjan<'_> {
+    fn visit(&mut self, node: usize) {
+        self.index[node] = Some(self.next_index);
+        self.low_link[node] = self.next_index;
+        self.next_index += 1;
+        self.stack.push(node);
+        self.on_stack[node] = true;
+
+        for &next in &self.successors[node] {
+            match self.index[next] {
+                None => {
+                    self.visit(next);
+                    self.low_link[node] = self.low_link[node].min(self.low_link[next]);
+                }
+                Some(index) if self.on_stack[next] => {
+                    self.low_link[node] = self.low_link[node].min(index);
+                }
+                Some(_) => {}
+            }
+        }
+
+        if Some(self.low_link[node]) != self.index[node] {
+            return;
+        }
+
+        let mut component = vec![];
+
+        while let Some(top) = self.stack.pop() {
+            self.on_stack[top] = false;
+            component.push(top);
+
+            if top == node {
+                break;
+            }
+        }
+
+        self.components.push(component);
+    }
+}
+
+fn port_ref(instance: &str, port: &str) -> PortRef {
+    PortRef {
+        instance: instance.to_string(),
+        port: port.to_string(),
+    }
+}
+
+fn key(port: &PortRef) -> (String, String) {
+    (port.instance.clone(), port.port.to_lowercase())
+}
@@ -0,0 +1,236 @@
+//! Adapter connections inside of a runtime:
+//! - the adapter type `REQ_CNF` carries a request with a value and a confirmation with a result
+//! - `REQUESTER` uses it as plug `srv`, `DOUBLER` serves it as socket `srv`
+//! - a single `connect_adapter` call replaces the four connections of both directions
+
+use std::{error::Error, sync::Arc};
+
+use crate::{
+    fb::{
+        Bfb,
+        adapter::AdapterType,
+        data::{
+            Data,
+            comm::DataBuffer,
+            ty::{DataKind, UInt},
+        },
+        desc::{EventDesc, InterfaceDesc, VarDesc},
+        direction::{In, Out},
+        ecc::{Condition, EcAction, Ecc, ExecutionControl},
+        event::{Event, ty::Signal},
+    },
+    run_time::id_conn::IdConnRuntime,
+    run_time_impl::check,
+};
+
+const MAX_CYCLES: usize = 100;
+
+pub fn test_adapter_req_cnf() -> Result<(), Box<dyn Error>> {
+    // SETUP --------------------------------------------------------------------------------------
+    let mut rt = IdConnRuntime::default();
+
+    rt.add_fb(REQUESTER::new("req0"));
+    rt.add_fb(DOUBLER::new("dbl0"));
+
+    rt.connect_adapter(&req_cnf(), ("req0", "srv"), ("dbl0", "srv"))?;
+    println!("{rt}");
+
+    check("REQ_CNF: expands into two event connections", || {
+        rt.event_conns().len() == 2
+    })?;
+    check("REQ_CNF: expands into two data connections", || {
+        rt.data_conns().len() == 2
+    })?;
+
+    // TESTING ------------------------------------------------------------------------------------
+    let req0 = rt.fbs_mut().get_mut("req0").unwrap();
+    req0.write_data_in("x", &DataBuffer::UInt(21))?;
+    req0.set_event_in("start")?;
+    rt.run(MAX_CYCLES)?;
+    println!("{rt}");
+
+    check("REQ_CNF: the request reaches the socket", || {
+        rt.fbs()["dbl0"].ec_state() == Some("CNF")
+    })?;
+    check("REQ_CNF: the confirmation returns to the plug", || {
+        rt.fbs()["req0"].read_data_out("result").ok() == Some(DataBuffer::UInt(42))
+    })?;
+
+    Ok(())
+}
+
+pub fn test_adapter_mismatch() -> Result<(), Box<dyn Error>> {
+    let mut rt = IdConnRuntime::default();
+
+    rt.add_fb(REQUESTER::new("req0"));
+    rt.add_fb(DOUBLER::new("dbl0"));
+
+    let other = AdapterType::new("OTHER", InterfaceDesc::default());
+
+    let attempts = [
+        (
+            "plug and socket swapped",
+            rt.connect_adapter(&req_cnf(), ("dbl0", "srv"), ("req0", "srv")),
+        ),
+        (
+            "different adapter type",
+            rt.connect_adapter(&other, ("req0", "srv"), ("dbl0", "srv")),
+        ),
+        (
+            "unknown plug",
+            rt.connect_adapter(&req_cnf(), ("req0", "cli"), ("dbl0", "srv")),
+        ),
+    ];
+
+    for (label, result) in attempts {
+        match result {
+            Err(e) => println!("[expected error] {label}: {e}"),
+            Ok(()) => return Err(format!("[failed] {label} is accepted").into()),
+        }
+    }
+
+    check(
+        "REQ_CNF: failed adapter connections add no connections",
+        || rt.event_conns().is_empty() && rt.data_conns().is_empty(),
+    )
+}
+
+/// request/confirmation adapter, declared from the point of view of the plug
+fn req_cnf() -> AdapterType {
+    AdapterType::new(
+        "REQ_CNF",
+        InterfaceDesc {
+            event_inputs: vec![EventDesc::new("CNF", &["RESULT"])],
+            event_outputs: vec![EventDesc::new("REQ", &["VALUE"])],
+            input_vars: vec![VarDesc::new("RESULT", DataKind::UInt)],
+            output_vars: vec![VarDesc::new("VALUE", DataKind::UInt)],
+        },
+    )
+}
+
+/// sends `x` through its plug on `start`, emits `done` WITH the confirmed result
+#[derive(Default, Debug, Bfb)]
+#[plug(srv, "REQ_CNF")]
+pub struct REQUESTER {
+    instance_name: String,
+    #[ecc]
+    ecc: ExecutionControl<Self>,
+    #[with(x)]
+    start: Event<In, Signal>,
+    #[with(result)]
+    done: Event<Out, Signal>,
+    x: Data<In, UInt>,
+    result: Data<Out, UInt>,
+    #[with(srv_value)]
+    srv_req: Event<Out, Signal>,
+    srv_value: Data<Out, UInt>,
+    #[with(srv_result)]
+    srv_cnf: Event<In, Signal>,
+    srv_result: Data<In, UInt>,
+}
+
+impl REQUESTER {
+    pub fn new(instance_name: &str) -> Self {
+        Self {
+            instance_name: instance_name.to_string(),
+            ecc: ExecutionControl::new(Arc::new(Self::chart())),
+            ..Default::default()
+        }
+    }
+
+    fn chart() -> Ecc<Self> {
+        Ecc::builder()
+            .state("IDLE", vec![])
+            .state(
+                "REQ",
+                vec![EcAction::algorithm("REQ", Self::req_algorithm).then_output("srv_req")],
+            )
+            .state(
+                "DONE",
+                vec![EcAction::algorithm("DONE", Self::done_algorithm).then_output("done")],
+            )
+            .transition("IDLE", "REQ", Condition::event("start"))
+            .transition("REQ", "DONE", Condition::event("srv_cnf"))
+            .transition("DONE", "REQ", Condition::event("start"))
+            .build()
+            .expect("REQUESTER chart is valid")
+    }
+
+    fn req_algorithm(&mut self) {
+        self.srv_value.write(self.x.read());
+    }
+
+    fn done_algorithm(&mut self) {
+        self.result.write(self.srv_result.read());
+    }
+}
+
+impl std::fmt::Display for REQUESTER {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}={{x={}, result={}, srv_value={}, srv_result={}, state={}}}",
+            self.instance_name,
+            self.x.as_buf(),
+            self.result.as_buf(),
+            self.srv_value.as_buf(),
+            self.srv_result.as_buf(),
+            self.ecc.state(),
+        )
+    }
+}
+
+/// serves requests on its socket, confirms them with the doubled value
+#[derive(Default, Debug, Bfb)]
+#[socket(srv, "REQ_CNF")]
+pub struct DOUBLER {
+    instance_name: String,
+    #[ecc]
+    ecc: ExecutionControl<Self>,
+    #[with(srv_value)]
+    srv_req: Event<In, Signal>,
+    srv_value: Data<In, UInt>,
+    #[with(srv_result)]
+    srv_cnf: Event<Out, Signal>,
+    srv_result: Data<Out, UInt>,
+}
+
+impl DOUBLER {
+    pub fn new(instance_name: &str) -> Self {
+        Self {
+            instance_name: instance_name.to_string(),
+            ecc: ExecutionControl::new(Arc::new(Self::chart())),
+            ..Default::default()
+        }
+    }
+
+    fn chart() -> Ecc<Self> {
+        Ecc::builder()
+            .state("IDLE", vec![])
+            .state(
+                "CNF",
+                vec![EcAction::algorithm("CNF", Self::cnf_algorithm).then_output("srv_cnf")],
+            )
+            .transition("IDLE", "CNF", Condition::event("srv_req"))
+            .transition("CNF", "CNF", Condition::event("srv_req"))
+            .build()
+            .expect("DOUBLER chart is valid")
+    }
+
+    fn cnf_algorithm(&mut self) {
+        self.srv_result.write(self.srv_value.read() * 2);
+    }
+}
+
+impl std::fmt::Display for DOUBLER {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}={{srv_value={}, srv_result={}, state={}}}",
+            self.instance_name,
+            self.srv_value.as_buf(),
+            self.srv_result.as_buf(),
+            self.ecc.state(),
+        )
+    }
+}
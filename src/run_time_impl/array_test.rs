@@ -0,0 +1,333 @@
+//! Array data types (see `fb::data::array`):
+//! - `ARRAY [lo..hi, ...] OF <type>` declarations, literals and row-major element access
+//! - writes outside of the bounds are rejected
+//! - `SENSORS` sends a 16-channel vector to `ARRAY_AVG`, which is loaded from `types/ARRAY_AVG.fbt`
+//!   and indexes it in Structured Text
+//! - transition conditions indexing an array (`IN[0] >= 0`) are guards without event
+//! - connections need matching element types and bounds
+
+use std::{error::Error, sync::Arc};
+
+use crate::{
+    fb::{
+        Bfb,
+        data::{
+            Data,
+            array::{ArrayBuffer, ArrayKind},
+            comm::DataBuffer,
+            ty::{self, Array, Array2, Bool, DataKind, Int},
+        },
+        desc::FbBodyDesc,
+        direction::{In, Out},
+        ecc::{Condition, EcAction, Ecc, ExecutionControl},
+        event::{Event, ty::Signal},
+    },
+    fb_impl::generic::GenericType,
+    run_time::{id_conn::IdConnRuntime, rc_conn::RcConnRuntime, validate::Diagnostic},
+    run_time_impl::check,
+    xml::fbt,
+};
+
+const MAX_CYCLES: usize = 100;
+
+const ARRAY_AVG_FBT: &str = include_str!("../../types/ARRAY_AVG.fbt");
+
+pub fn test_arrays() -> Result<(), Box<dyn Error>> {
+    // SETUP --------------------------------------------------------------------------------------
+    let kind = ArrayKind::parse("array [1..2, 0..2] of int").ok_or("invalid declaration")?;
+    let matrix_kind = DataKind::Array(Box::new(kind.clone()));
+
+    // TESTING ------------------------------------------------------------------------------------
+    println!("{kind}");
+    check("ARRAY: declarations round trip", || {
+        DataKind::from_iec_name(&kind.to_string()).as_ref() == Some(&matrix_kind)
+            && kind.element_count() == 6
+    })?;
+    check("ARRAY: invalid declarations are rejected", || {
+        ArrayKind::parse("ARRAY [2..1] OF INT").is_none()
+            && ArrayKind::parse("ARRAY [0..1] OF ANY_NUM").is_none()
+            && ArrayKind::parse("ARRAY [0..1] INT").is_none()
+    })?;
+    check("ARRAY: elements are stored in row-major order", || {
+        kind.offset(&[1, 0]) == Some(0)
+            && kind.offset(&[1, 2]) == Some(2)
+            && kind.offset(&[2, 0]) == Some(3)
+            && kind.offset(&[3, 0]).is_none()
+            && kind.offset(&[1]).is_none()
+    })?;
+
+    let mut matrix =
+        DataBuffer::parse_literal(&matrix_kind, "[1, 2(5), -3]").ok_or("invalid literal")?;
+
+    println!("{matrix}");
+    check(
+        "ARRAY: literals repeat values and keep the initial value",
+        || matrix.to_string() == "[INT#1, INT#5, INT#5, INT#-3, INT#0, INT#0]",
+    )?;
+
+    if let DataBuffer::Array(array) = &mut matrix {
+        array.set(&[2, 2], DataBuffer::Int(9))?;
+
+        check("ARRAY: elements are accessed by index", || {
+            array.get(&[2, 2]) == Some(&DataBuffer::Int(9))
+        })?;
+
+        for (index, value) in [
+            (vec![0, 0], DataBuffer::Int(1)),
+            (vec![1, 1], DataBuffer::DInt(1)),
+        ] {
+            match array.set(&index, value) {
+                Err(e) => println!("[expected error]: {e}"),
+                Ok(()) => return Err(format!("[failed] ARRAY: {index:?} is written").into()),
+            }
+        }
+    }
+
+    for text in [
+        "[1, 2, 3, 4, 5, 6, 7]",
+        "[70000]",
+        "[1, , 2]",
+        "1, 2",
+        "[1, 6(0)]",
+        "[100000000(0)]",
+        "[18446744073709551615(0)]",
+    ] {
+        check(&format!("ARRAY: {text} is rejected"), || {
+            DataBuffer::parse_literal(&matrix_kind, text).is_none()
+        })?;
+    }
+
+    let mut grid: Data<Out, Array2<Bool, 1, 2, 1, 3>> = Data::default();
+    grid.set_element("grid", &[2, 3], true)?;
+
+    check("ARRAY: typed element access", || {
+        grid.element(&[2, 3]) == Some(true)
+            && grid.element(&[1, 3]) == Some(false)
+            && grid.read().iter().filter(|v| **v).count() == 1
+    })?;
+
+    match grid.set_element("grid", &[3, 1], true) {
+        Err(e) => println!("[expected error]: {e}"),
+        Ok(()) => return Err("[failed] ARRAY: an element outside of the bounds is set".into()),
+    }
+
+    check(
+        "ARRAY: values with the wrong number of elements are rejected",
+        || !grid.write(vec![true; 5]) && grid.element(&[2, 3]) == Some(true),
+    )?;
+
+    test_array_network()?;
+    test_array_guard()?;
+    test_array_mismatch()
+}
+
+fn test_array_network() -> Result<(), Box<dyn Error>> {
+    // SETUP --------------------------------------------------------------------------------------
+    let mut rt = IdConnRuntime::default();
+
+    rt.add_fb(SENSORS::new("sensors0"));
+    rt.add_fb(GenericType::new(fbt::parse_fbt(ARRAY_AVG_FBT)?)?.instantiate("avg0"));
+
+    rt.connect_event(("sensors0", "cnf"), ("avg0", "req"));
+    rt.connect_data(("sensors0", "values"), ("avg0", "in"))?;
+
+    // TESTING ------------------------------------------------------------------------------------
+    println!("{}", rt.fbs()["avg0"].interface());
+    check("NETWORK: matching arrays are connected", || {
+        rt.validate()
+            .iter()
+            .all(|d| !matches!(d, Diagnostic::KindMismatch { .. }))
+    })?;
+
+    for _ in 0..2 {
+        rt.fbs_mut()
+            .get_mut("sensors0")
+            .unwrap()
+            .set_event_in("req")?;
+        rt.run(MAX_CYCLES)?;
+    }
+
+    println!("{rt}");
+    check("NETWORK: the channels are averaged", || {
+        rt.fbs()["avg0"].read_data_out("avg").ok() == Some(DataBuffer::Int(77))
+            && rt.fbs()["avg0"].read_data_out("peak").ok() == Some(DataBuffer::Int(152))
+    })?;
+
+    let short = ArrayBuffer::new(ArrayKind::new(vec![0..=7], DataKind::Int).ok_or("invalid kind")?);
+
+    match rt
+        .fbs_mut()
+        .get_mut("avg0")
+        .unwrap()
+        .write_data_in("in", &DataBuffer::Array(Box::new(short)))
+    {
+        Err(e) => println!("[expected error]: {e}"),
+        Ok(()) => return Err("[failed] NETWORK: an array with other bounds is written".into()),
+    }
+
+    Ok(())
+}
+
+/// a condition indexing an array is a guard, not an event followed by a guard
+fn test_array_guard() -> Result<(), Box<dyn Error>> {
+    let text = ARRAY_AVG_FBT.replace(r#"Condition="1""#, r#"Condition="IN[0] &gt;= 0""#);
+    let desc = fbt::parse_fbt(&text)?;
+
+    check("FBT: IN[0] >= 0 is a guard only", || {
+        let FbBodyDesc::Basic(basic) = &desc.body else {
+            return false;
+        };
+
+        basic.ecc.transitions.iter().any(|t| {
+            t.source == "REQ" && t.event.is_none() && t.guard.as_deref() == Some("IN[0] >= 0")
+        })
+    })?;
+
+    GenericType::new(desc)?;
+
+    Ok(())
+}
+
+fn test_array_mismatch() -> Result<(), Box<dyn Error>> {
+    let sixteen = |lo, element| {
+        DataKind::Array(Box::new(
+            ArrayKind::new(vec![lo..=lo + 15], element).unwrap(),
+        ))
+    };
+
+    check("ARRAY: other bounds are not assignable", || {
+        ty::assignable(&sixteen(0, DataKind::Int), &sixteen(0, DataKind::Int))
+            && !ty::assignable(&sixteen(0, DataKind::Int), &sixteen(1, DataKind::Int))
+    })?;
+    check("ARRAY: other element types are not assignable", || {
+        !ty::assignable(&sixteen(0, DataKind::Int), &sixteen(0, DataKind::DInt))
+            && !ty::assignable(&sixteen(0, DataKind::Int), &DataKind::Int)
+    })?;
+
+    let mut rt = IdConnRuntime::default();
+
+    rt.add_fb(SENSORS::new("sensors0"));
+    rt.add_fb(DISPLAY::new("display0"));
+
+    match rt.connect_data(("sensors0", "values"), ("display0", "values")) {
+        Err(e) => println!("[expected error]: {e}"),
+        Ok(()) => return Err("[failed] NETWORK: [0..15] is connected to [1..16]".into()),
+    }
+
+    check("NETWORK: [0..15] is not connected to [1..16]", || {
+        rt.data_conns().is_empty()
+    })?;
+
+    let mut rc_rt = RcConnRuntime::default();
+
+    rc_rt.add_fb(SENSORS::new("sensors0"));
+    rc_rt.add_fb(DISPLAY::new("display0"));
+    rc_rt.connect_data((0, "values"), (1, "values"))?;
+
+    check("RC: the mismatching connection is not created", || {
+        rc_rt.data_conns().is_empty()
+    })
+}
+
+/// samples 16 channels on `req`, channel `i` reads `10 * i + n` in the `n`-th sample
+#[derive(Default, Debug, Bfb)]
+pub struct SENSORS {
+    instance_name: String,
+    #[ecc]
+    ecc: ExecutionControl<Self>,
+    req: Event<In, Signal>,
+    #[with(values)]
+    cnf: Event<Out, Signal>,
+    values: Data<Out, Array<Int, 0, 15>>,
+}
+
+impl SENSORS {
+    pub fn new(instance_name: &str) -> Self {
+        Self {
+            instance_name: instance_name.to_string(),
+            ecc: ExecutionControl::new(Arc::new(Self::chart())),
+            ..Default::default()
+        }
+    }
+
+    fn chart() -> Ecc<Self> {
+        Ecc::builder()
+            .state("START", vec![])
+            .state(
+                "REQ",
+                vec![EcAction::algorithm("REQ", Self::req_algorithm).then_output("cnf")],
+            )
+            .transition("START", "REQ", Condition::event("req"))
+            .transition("REQ", "START", Condition::always())
+            .build()
+            .expect("SENSORS chart is valid")
+    }
+
+    fn req_algorithm(&mut self) {
+        for i in 0..16 {
+            let sample = self.values.element(&[i]).unwrap_or_default() % 10 + 1;
+
+            if let Err(e) = self
+                .values
+                .set_element("values", &[i], 10 * i as i16 + sample)
+            {
+                println!("[error SENSORS]: {e}");
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for SENSORS {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}={{values={}, state={}}}",
+            self.instance_name,
+            self.values.as_buf(),
+            self.ecc.state(),
+        )
+    }
+}
+
+/// shows 16 channels numbered from 1
+#[derive(Default, Debug, Bfb)]
+pub struct DISPLAY {
+    instance_name: String,
+    #[ecc]
+    ecc: ExecutionControl<Self>,
+    #[with(values)]
+    req: Event<In, Signal>,
+    values: Data<In, Array<Int, 1, 16>>,
+}
+
+impl DISPLAY {
+    pub fn new(instance_name: &str) -> Self {
+        Self {
+            instance_name: instance_name.to_string(),
+            ecc: ExecutionControl::new(Arc::new(Self::chart())),
+            ..Default::default()
+        }
+    }
+
+    fn chart() -> Ecc<Self> {
+        Ecc::builder()
+            .state("START", vec![])
+            .state("REQ", vec![])
+            .transition("START", "REQ", Condition::event("req"))
+            .transition("REQ", "START", Condition::always())
+            .build()
+            .expect("DISPLAY chart is valid")
+    }
+}
+
+impl std::fmt::Display for DISPLAY {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}={{values={}, state={}}}",
+            self.instance_name,
+            self.values.as_buf(),
+            self.ecc.state(),
+        )
+    }
+}
@@ -0,0 +1,115 @@
+//! Calendar values of the date and time types (see `fb::data::calendar`):
+//! - dates and times of day are validated on construction
+//! - `DT` and `TOD` are shifted by `TIME`, the difference of two points in time is a `TIME`
+//! - `DT` converts to and from the Unix epoch
+//! - the long types (`LTIME`, `LDATE`, `LTOD`, `LDT`) share the values of the short ones
+//! - the same arithmetic in Structured Text
+
+use std::{collections::HashMap, error::Error, time::Duration};
+
+use crate::{
+    fb::data::{
+        calendar::{Date, DateAndTime, TimeOfDay},
+        comm::DataBuffer,
+        ty::DataKind,
+    },
+    run_time_impl::check,
+    st::{Program, Variables},
+};
+
+const PROGRAM: &str = "
+    later := start + T#5m;
+    elapsed := later - start;
+    midnight := TOD#23:59:00 + T#2m;
+    days := D#2024-03-01 - D#2024-02-01;
+    overdue := later > LDT#2024-02-29-23:59:59;
+";
+
+pub fn test_calendar() -> Result<(), Box<dyn Error>> {
+    // SETUP --------------------------------------------------------------------------------------
+    let leap_day = Date::new(2024, 2, 29).ok_or("invalid date")?;
+    let noon = TimeOfDay::new(12, 0, 0, 0).ok_or("invalid time of day")?;
+    let dt = DateAndTime::new(leap_day, noon).ok_or("invalid DT")?;
+
+    // TESTING ------------------------------------------------------------------------------------
+    check("DATE: invalid dates are rejected", || {
+        Date::new(2023, 2, 29).is_none() && Date::new(2024, 4, 31).is_none()
+    })?;
+    check("DATE: days outside of 0000..=9999 are rejected", || {
+        Date::from_unix_days(-719_528) == Date::new(0, 1, 1)
+            && Date::from_unix_days(2_932_896) == Date::new(9999, 12, 31)
+            && Date::from_unix_days(-719_529).is_none()
+            && Date::from_unix_days(i64::MAX).is_none()
+            && leap_day.checked_add_days(i64::MAX - 1).is_none()
+            && leap_day.checked_add_days(i64::MIN).is_none()
+    })?;
+    check("TOD: invalid times of day are rejected", || {
+        TimeOfDay::new(24, 0, 0, 0).is_none() && TimeOfDay::new(12, 60, 0, 0).is_none()
+    })?;
+    check("DT: DT#2024-02-29-12:00:00 + T#5m", || {
+        (dt + Duration::from_secs(300)).to_string() == "2024-02-29-12:05:00"
+    })?;
+    check("DT: crossing the end of the month", || {
+        (dt + Duration::from_secs(12 * 3600)).date() == Date::new(2024, 3, 1).unwrap()
+    })?;
+    check("TOD: wraps around midnight", || {
+        noon + Duration::from_secs(13 * 3600) == TimeOfDay::new(1, 0, 0, 0).unwrap()
+    })?;
+    check("DT: earlier values compare less", || {
+        dt - Duration::from_nanos(1) < dt && leap_day < Date::new(2024, 3, 1).unwrap()
+    })?;
+    check("DT: difference is a duration", || {
+        (dt + Duration::from_secs(90)).duration_since(&dt) == Some(Duration::from_secs(90))
+            && dt.duration_since(&(dt + Duration::from_secs(90))).is_none()
+    })?;
+    check("DT: Unix epoch round trip", || {
+        dt.unix_secs() == 1_709_208_000 && DateAndTime::from_unix_secs(1_709_208_000) == Some(dt)
+    })?;
+    check("DT: dates before the epoch", || {
+        DateAndTime::from_unix_secs(-1).map(|v| v.to_string())
+            == Some("1969-12-31-23:59:59".to_string())
+    })?;
+    check("LDT: shares the value of DT", || {
+        "LDT#2024-02-29-12:00:00".parse::<DataBuffer>().ok() == Some(DataBuffer::LDateTime(dt))
+            && DataBuffer::default_for(&DataKind::LDateTime).to_string()
+                == "LDT#1970-01-01-00:00:00"
+    })?;
+
+    println!("now: {}", DataBuffer::DateTime(DateAndTime::now()));
+
+    let mut vars: HashMap<String, DataBuffer> = HashMap::from([
+        ("start".to_string(), DataBuffer::LDateTime(dt)),
+        ("later".to_string(), DataBuffer::LDateTime(dt)),
+        ("elapsed".to_string(), DataBuffer::LTime(Duration::ZERO)),
+        ("midnight".to_string(), DataBuffer::TimeOfDay(noon)),
+        ("days".to_string(), DataBuffer::Time(Duration::ZERO)),
+        ("overdue".to_string(), DataBuffer::Bool(false)),
+    ]);
+
+    Program::parse(PROGRAM)?.execute(&mut vars)?;
+
+    for name in ["later", "elapsed", "midnight", "days", "overdue"] {
+        println!(
+            "{name} = {}",
+            vars.read(name).unwrap_or(DataBuffer::Unassigned)
+        );
+    }
+
+    check("ST: arithmetic on points in time", || {
+        vars.read("later") == Some(DataBuffer::LDateTime(dt + Duration::from_secs(300)))
+            && vars.read("elapsed") == Some(DataBuffer::LTime(Duration::from_secs(300)))
+            && vars.read("midnight") == TimeOfDay::new(0, 1, 0, 0).map(DataBuffer::TimeOfDay)
+            && vars.read("days") == Some(DataBuffer::Time(Duration::from_secs(29 * 86_400)))
+            && vars.read("overdue") == Some(DataBuffer::Bool(false))
+    })?;
+
+    for text in ["later := start - later - T#1s;", "days := DT#2024-01-31;"] {
+        if let Err(e) = Program::parse(text).and_then(|p| p.execute(&mut vars)) {
+            println!("[expected error]: {e}");
+        } else {
+            return Err(format!("[failed] ST: {text} is executed").into());
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,351 @@
+//! Composite function blocks inside of a runtime:
+//! - `COUNTER` encapsulates an `E_CTU` and an `E_SWITCH`, it emits `done` once the preset is reached
+//! - the composite is connected to basic blocks like any other function block
+//! - composites can be nested, `test_cfb_nested` wraps `COUNTER` in another composite
+//! - every emission of a component leaves the composite with the data of its own emission
+//! - interface ports are bound to assignable and generic component ports of the same direction,
+//!   internal failures and endless internal event chains reach the runtime
+
+use std::error::Error;
+
+use crate::{
+    fb::{
+        Bfb,
+        data::{comm::DataBuffer, ty::DataKind},
+        desc::{EventDesc, InterfaceDesc, VarDesc},
+        error::FbError,
+    },
+    fb_impl::{
+        arith::F_ADD,
+        composite::Cfb,
+        event::{ctu::E_CTU, merge::E_MERGE, split::E_SPLIT, sr::E_SR, switch::E_SWITCH},
+    },
+    run_time::{id_conn::IdConnRuntime, scheduler::Scheduler},
+    run_time_impl::check,
+};
+
+const MAX_CYCLES: usize = 100;
+
+pub fn test_cfb_counter() -> Result<(), Box<dyn Error>> {
+    // SETUP --------------------------------------------------------------------------------------
+    let mut rt = IdConnRuntime::default();
+
+    let mut counter = counter("cnt0")?;
+    counter.write_data_in("pv", &DataBuffer::UInt(2))?;
+
+    rt.add_fb(counter);
+    rt.add_fb(E_SR::new("sr0"));
+    rt.add_fb(E_CTU::new("ctu1"));
+
+    rt.connect_event(("cnt0", "done"), ("sr0", "s"));
+    rt.connect_event(("cnt0", "cuo"), ("ctu1", "cu"));
+    rt.connect_data(("cnt0", "cv"), ("ctu1", "pv"))?;
+
+    // TESTING ------------------------------------------------------------------------------------
+    fire(&mut rt, "cnt0", "cu")?;
+    println!("{rt}");
+    check("COUNTER: cuo leaves the composite", || {
+        output(&rt, "ctu1", "cv") == DataBuffer::UInt(1)
+    })?;
+    check("COUNTER: cv is sent WITH cuo", || {
+        output(&rt, "ctu1", "q") == DataBuffer::Bool(true)
+    })?;
+    check("COUNTER: done is not emitted below pv", || {
+        output(&rt, "sr0", "q") == DataBuffer::Bool(false)
+    })?;
+
+    fire(&mut rt, "cnt0", "cu")?;
+    println!("{rt}");
+    check("COUNTER: done is emitted at pv", || {
+        output(&rt, "sr0", "q") == DataBuffer::Bool(true)
+    })?;
+    check("COUNTER: q reads the component output", || {
+        output(&rt, "cnt0", "q") == DataBuffer::Bool(true)
+    })?;
+
+    fire(&mut rt, "cnt0", "r")?;
+    check("COUNTER: r resets the component", || {
+        output(&rt, "cnt0", "cv") == DataBuffer::UInt(0)
+    })
+}
+
+pub fn test_cfb_nested() -> Result<(), Box<dyn Error>> {
+    // SETUP --------------------------------------------------------------------------------------
+    let interface = InterfaceDesc {
+        event_inputs: vec![EventDesc::new("TICK", &[])],
+        event_outputs: vec![EventDesc::new("DONE", &[])],
+        ..Default::default()
+    };
+
+    let mut counter = counter("cnt")?;
+    counter.write_data_in("pv", &DataBuffer::UInt(3))?;
+
+    let mut outer = Cfb::new("outer0", "TICKS", interface);
+    outer.add_fb(counter);
+    outer.bind_event_in("tick", ("cnt", "cu"))?;
+    outer.bind_event_out(("cnt", "done"), "done")?;
+
+    let mut rt = IdConnRuntime::default();
+    rt.add_fb(outer);
+    rt.add_fb(E_SR::new("sr0"));
+    rt.connect_event(("outer0", "done"), ("sr0", "s"));
+
+    // TESTING ------------------------------------------------------------------------------------
+    let mut scheduler = Scheduler::default();
+
+    for _ in 0..2 {
+        scheduler.post("outer0", "tick");
+    }
+
+    scheduler.run(&mut rt, MAX_CYCLES)?;
+    check("TICKS: done is not emitted below pv", || {
+        output(&rt, "sr0", "q") == DataBuffer::Bool(false)
+    })?;
+
+    scheduler.post("outer0", "tick");
+    scheduler.run(&mut rt, MAX_CYCLES)?;
+    println!("{rt}");
+    check("TICKS: done passes both composite boundaries", || {
+        output(&rt, "sr0", "q") == DataBuffer::Bool(true)
+    })?;
+
+    match rt.fbs_mut().get_mut("outer0").unwrap().set_event_in("done") {
+        Err(e) => println!("[expected error]: {e}"),
+        Ok(()) => return Err("[failed] TICKS: done is accepted as input event".into()),
+    }
+
+    Ok(())
+}
+
+pub fn test_cfb_emissions() -> Result<(), Box<dyn Error>> {
+    // SETUP --------------------------------------------------------------------------------------
+    let interface = InterfaceDesc {
+        event_inputs: vec![EventDesc::new("REQ", &[])],
+        event_outputs: vec![EventDesc::new("CNF", &["CV"])],
+        output_vars: vec![VarDesc::new("CV", DataKind::UInt)],
+        ..Default::default()
+    };
+
+    // a single req counts three times
+    let mut triple = Cfb::new("triple0", "TRIPLE", interface);
+    triple.add_fb(E_SPLIT::new("split0"));
+    triple.add_fb(E_SPLIT::new("split1"));
+    triple.add_fb(E_CTU::new("ctu"));
+    triple.connect_event(("split0", "eo1"), ("ctu", "cu"));
+    triple.connect_event(("split0", "eo2"), ("split1", "ei"));
+    triple.connect_event(("split1", "eo1"), ("ctu", "cu"));
+    triple.connect_event(("split1", "eo2"), ("ctu", "cu"));
+    triple.bind_event_in("req", ("split0", "ei"))?;
+    triple.bind_event_out(("ctu", "cuo"), "cnf")?;
+    triple.bind_data_out(("ctu", "cv"), "cv")?;
+
+    // TESTING ------------------------------------------------------------------------------------
+    triple.set_event_in("req")?;
+
+    let mut emitted = vec![];
+
+    while triple.invoke_execution_control()? {
+        for event in triple.active_events_out() {
+            emitted.push(format!("{event}:{}", triple.read_data_out("cv")?));
+        }
+
+        triple.clear_event_out();
+    }
+
+    println!("{emitted:?}");
+    check("TRIPLE: every emission leaves the composite", || {
+        emitted == ["cnf:UINT#1", "cnf:UINT#2", "cnf:UINT#3"]
+    })?;
+
+    let mut rt = IdConnRuntime::default();
+    rt.add_fb(triple);
+    rt.add_fb(E_CTU::new("ctu1"));
+    rt.connect_event(("triple0", "cnf"), ("ctu1", "cu"));
+
+    fire(&mut rt, "triple0", "req")?;
+    println!("{rt}");
+    check("TRIPLE: the runtime receives every emission", || {
+        output(&rt, "ctu1", "cv") == DataBuffer::UInt(3)
+    })?;
+
+    test_cfb_binding()
+}
+
+fn test_cfb_binding() -> Result<(), Box<dyn Error>> {
+    // SETUP --------------------------------------------------------------------------------------
+    let interface = InterfaceDesc {
+        event_inputs: vec![EventDesc::new("REQ", &["IN1", "IN2"])],
+        event_outputs: vec![EventDesc::new("CNF", &["OUT"])],
+        input_vars: vec![
+            VarDesc::new("IN1", DataKind::Int),
+            VarDesc::new("IN2", DataKind::SInt),
+            VarDesc::new("FLAG", DataKind::Bool),
+        ],
+        output_vars: vec![VarDesc::new("OUT", DataKind::DInt)],
+    };
+
+    let mut adder = Cfb::new("adder0", "ADDER", interface);
+    adder.add_fb(F_ADD::new("add"));
+    adder.bind_event_in("req", ("add", "req"))?;
+    adder.bind_event_out(("add", "cnf"), "cnf")?;
+    adder.bind_data_in("in1", ("add", "in1"))?;
+    adder.bind_data_in("in2", ("add", "in2"))?;
+    adder.bind_data_out(("add", "out"), "out")?;
+
+    let interface = InterfaceDesc {
+        event_inputs: vec![EventDesc::new("CU", &["PV"])],
+        event_outputs: vec![EventDesc::new("CUO", &["Q"])],
+        input_vars: vec![VarDesc::new("PV", DataKind::USInt)],
+        output_vars: vec![VarDesc::new("Q", DataKind::Bool)],
+    };
+
+    let mut wide = Cfb::new("wide0", "WIDE", interface);
+    wide.add_fb(E_CTU::new("ctu"));
+    wide.bind_event_in("cu", ("ctu", "cu"))?;
+    wide.bind_event_out(("ctu", "cuo"), "cuo")?;
+    wide.bind_data_in("pv", ("ctu", "pv"))?;
+    wide.bind_data_out(("ctu", "q"), "q")?;
+
+    // TESTING ------------------------------------------------------------------------------------
+    check("ADDER: generic ports are fixed to the interface", || {
+        let add = &adder.network().fbs()["add"];
+
+        add.data_kind("in1").ok() == Some(DataKind::Int)
+            && add.data_kind("in2").ok() == Some(DataKind::SInt)
+            && add.data_kind("out").ok() == Some(DataKind::DInt)
+    })?;
+
+    match adder.bind_data_in("flag", ("add", "in1")) {
+        Err(e) => println!("[expected error]: {e}"),
+        Ok(()) => return Err("[failed] ADDER: BOOL is bound to INT".into()),
+    }
+
+    adder.write_data_in("in1", &DataBuffer::Int(300))?;
+    adder.write_data_in("in2", &DataBuffer::SInt(-5))?;
+    wide.write_data_in("pv", &DataBuffer::USInt(1))?;
+
+    let mut rt = IdConnRuntime::default();
+    rt.add_fb(adder);
+    rt.add_fb(wide);
+
+    fire(&mut rt, "adder0", "req")?;
+    fire(&mut rt, "wide0", "cu")?;
+    println!("{rt}");
+    check("ADDER: the sum is widened to DINT", || {
+        output(&rt, "adder0", "out") == DataBuffer::DInt(295)
+    })?;
+    check("WIDE: USINT is bound to UINT", || {
+        output(&rt, "wide0", "q") == DataBuffer::Bool(true)
+    })?;
+
+    test_cfb_failure()
+}
+
+/// failures inside of the composite are returned to the runtime
+fn test_cfb_failure() -> Result<(), Box<dyn Error>> {
+    let interface = InterfaceDesc {
+        event_inputs: vec![EventDesc::new("CU", &[])],
+        ..Default::default()
+    };
+
+    let mut broken = Cfb::new("broken0", "BROKEN", interface);
+    broken.add_fb(E_CTU::new("ctu"));
+    broken.add_fb(E_SR::new("sr"));
+    broken.connect_event(("ctu", "cuo"), ("sr", "unknown"));
+    broken.bind_event_in("cu", ("ctu", "cu"))?;
+
+    let mut rt = IdConnRuntime::default();
+    rt.add_fb(broken);
+
+    match fire(&mut rt, "broken0", "cu") {
+        Err(e) => println!("[expected error]: {e}"),
+        Ok(()) => return Err("[failed] BROKEN: the internal failure is not returned".into()),
+    }
+
+    let interface = InterfaceDesc {
+        event_inputs: vec![EventDesc::new("EI", &[])],
+        event_outputs: vec![EventDesc::new("EO", &[])],
+        ..Default::default()
+    };
+
+    // `eo` of the merge is fed back into it, so the internal events never stop
+    let mut looped = Cfb::new("looped0", "LOOPED", interface);
+    looped.add_fb(E_MERGE::new("merge"));
+    looped.connect_event(("merge", "eo"), ("merge", "ei2"));
+    looped.bind_event_in("ei", ("merge", "ei1"))?;
+
+    check(
+        "LOOPED: an input event is not bound to an output event",
+        || {
+            matches!(
+                looped.bind_event_in("ei", ("merge", "eo")),
+                Err(FbError::WrongDirection(_))
+            )
+        },
+    )?;
+    check(
+        "LOOPED: an output event is not bound from an input event",
+        || {
+            matches!(
+                looped.bind_event_out(("merge", "ei1"), "eo"),
+                Err(FbError::WrongDirection(_))
+            )
+        },
+    )?;
+
+    let mut rt = IdConnRuntime::default();
+    rt.add_fb(looped);
+
+    let result = fire(&mut rt, "looped0", "ei");
+    check("LOOPED: endless internal events are not stable", || {
+        matches!(result, Err(FbError::NotStable { .. }))
+    })
+}
+
+/// `cu` counts, `done` is emitted once `cv` reaches `pv`
+pub fn counter(instance_name: &str) -> Result<Cfb, FbError> {
+    let interface = InterfaceDesc {
+        event_inputs: vec![EventDesc::new("CU", &["PV"]), EventDesc::new("R", &[])],
+        event_outputs: vec![
+            EventDesc::new("CUO", &["Q", "CV"]),
+            EventDesc::new("DONE", &[]),
+        ],
+        input_vars: vec![VarDesc::new("PV", DataKind::UInt)],
+        output_vars: vec![
+            VarDesc::new("Q", DataKind::Bool),
+            VarDesc::new("CV", DataKind::UInt),
+        ],
+    };
+
+    let mut cfb = Cfb::new(instance_name, "COUNTER", interface);
+
+    cfb.add_fb(E_CTU::new("ctu"));
+    cfb.add_fb(E_SWITCH::new("switch"));
+
+    cfb.connect_event(("ctu", "cuo"), ("switch", "ei"));
+    cfb.connect_data(("ctu", "q"), ("switch", "g"))?;
+
+    cfb.bind_event_in("cu", ("ctu", "cu"))?;
+    cfb.bind_event_in("r", ("ctu", "r"))?;
+    cfb.bind_data_in("pv", ("ctu", "pv"))?;
+    cfb.bind_event_out(("ctu", "cuo"), "cuo")?;
+    cfb.bind_event_out(("switch", "eo1"), "done")?;
+    cfb.bind_data_out(("ctu", "q"), "q")?;
+    cfb.bind_data_out(("ctu", "cv"), "cv")?;
+
+    Ok(cfb)
+}
+
+/// delivers an event from outside of the network and runs the network until it is stable
+fn fire(rt: &mut IdConnRuntime, fb_name: &str, event: &str) -> Result<(), FbError> {
+    rt.fbs_mut().get_mut(fb_name).unwrap().set_event_in(event)?;
+    rt.run(MAX_CYCLES)?;
+
+    Ok(())
+}
+
+fn output(rt: &IdConnRuntime, fb_name: &str, data: &str) -> DataBuffer {
+    rt.fbs()[fb_name]
+        .read_data_out(data)
+        .unwrap_or(DataBuffer::Unassigned)
+}
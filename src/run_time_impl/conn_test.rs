@@ -5,40 +5,43 @@
 //! All tests rely on manually connected `voter::dynamic_disp::Voter` instancecs,
 //! and on a manual execution order (stepping etc.)
 
+use std::error::Error;
+
 use crate::{
-    fb::{Bfb, data::comm::DataBuffer},
+    fb::{Bfb, data::comm::DataBuffer, error::FbError},
     fb_impl::voter::dynamic_disp::Voter,
-    run_time,
+    run_time::{self, queue::OverflowPolicy},
+    run_time_impl::check,
 };
 
-pub fn test_rc_conn_par_voter() {
+pub fn test_rc_conn_par_voter() -> Result<(), FbError> {
     let mut rt = run_time::rc_conn::RcConnRuntime::default();
 
     let t = DataBuffer::Bool(true);
     let f = DataBuffer::Bool(false);
 
     let mut v0 = Voter::new("voter0");
-    v0.write_data_in("a", &t);
-    v0.write_data_in("b", &t);
-    v0.write_data_in("c", &f);
-    v0.set_event_in("vote");
+    v0.write_data_in("a", &t)?;
+    v0.write_data_in("b", &t)?;
+    v0.write_data_in("c", &f)?;
+    v0.set_event_in("vote")?;
 
     let mut v1 = Voter::new("voter1");
-    v1.write_data_in("a", &t);
-    v1.write_data_in("b", &f);
-    v1.write_data_in("c", &t);
-    v1.set_event_in("vote");
+    v1.write_data_in("a", &t)?;
+    v1.write_data_in("b", &f)?;
+    v1.write_data_in("c", &t)?;
+    v1.set_event_in("vote")?;
 
     let mut v2 = Voter::new("voter2");
-    v2.write_data_in("a", &f);
-    v2.write_data_in("b", &t);
-    v2.write_data_in("c", &t);
-    v2.set_event_in("vote");
+    v2.write_data_in("a", &f)?;
+    v2.write_data_in("b", &t)?;
+    v2.write_data_in("c", &t)?;
+    v2.set_event_in("vote")?;
 
     let mut v3 = Voter::new("voter3");
-    v3.write_data_in("a", &f);
-    v3.write_data_in("b", &f);
-    v3.write_data_in("c", &f);
+    v3.write_data_in("a", &f)?;
+    v3.write_data_in("b", &f)?;
+    v3.write_data_in("c", &f)?;
 
     rt.add_fb(v0);
     rt.add_fb(v1);
@@ -49,33 +52,33 @@ pub fn test_rc_conn_par_voter() {
     rt.connect_event((1, "voted"), (3, "vote"));
     rt.connect_event((2, "voted"), (3, "vote"));
 
-    rt.connect_data((0, "state"), (3, "a"));
-    rt.connect_data((1, "state"), (3, "b"));
-    rt.connect_data((2, "state"), (3, "c"));
+    rt.connect_data((0, "state"), (3, "a"))?;
+    rt.connect_data((1, "state"), (3, "b"))?;
+    rt.connect_data((2, "state"), (3, "c"))?;
 
     println!("{rt}");
 
-    rt.step();
+    rt.step()?;
 
     println!("{rt}");
 
-    rt.step();
+    rt.step()?;
 
     println!("{rt}");
 
-    rt.send_from();
+    rt.send_from()?;
 
     println!("{rt}");
 
-    rt.read_in();
+    rt.read_in()?;
 
     println!("{rt}");
 
-    rt.step();
+    rt.step()?;
 
     println!("{rt}");
 
-    rt.step();
+    rt.step()?;
 
     println!("{rt}");
 
@@ -84,34 +87,38 @@ pub fn test_rc_conn_par_voter() {
     //      > the events failed to send since v0 sent first
     //      > makes sense though, since we have buffered the data onto the connection, so it can be sampled!
     // NOTE: Failures do not concern us here, we just wanted to showcase that connections work as implemented (which they do)
+    // NOTE: the vote events of (v1, v2) are no longer lost, they wait in the event queue of v3
+    println!("[dropped events]: {}", rt.dropped_events());
+
+    Ok(())
 }
 
-pub fn test_id_conn_seq_voter() {
+pub fn test_id_conn_seq_voter() -> Result<(), FbError> {
     // SETUP --------------------------------------------------------------------------------------
     let mut rt = run_time::id_conn::IdConnRuntime::default();
     let t = DataBuffer::Bool(true);
     let f = DataBuffer::Bool(false);
 
     let mut v0 = Voter::new("voter0");
-    v0.write_data_in("a", &t);
-    v0.write_data_in("b", &t);
-    v0.write_data_in("c", &f);
-    v0.set_event_in("vote");
+    v0.write_data_in("a", &t)?;
+    v0.write_data_in("b", &t)?;
+    v0.write_data_in("c", &f)?;
+    v0.set_event_in("vote")?;
 
     let mut v1 = Voter::new("voter1");
-    v1.write_data_in("a", &t);
-    v1.write_data_in("b", &f);
-    v1.write_data_in("c", &t);
+    v1.write_data_in("a", &t)?;
+    v1.write_data_in("b", &f)?;
+    v1.write_data_in("c", &t)?;
 
     let mut v2 = Voter::new("voter2");
-    v2.write_data_in("a", &f);
-    v2.write_data_in("b", &t);
-    v2.write_data_in("c", &t);
+    v2.write_data_in("a", &f)?;
+    v2.write_data_in("b", &t)?;
+    v2.write_data_in("c", &t)?;
 
     let mut v3 = Voter::new("voter3");
-    v3.write_data_in("a", &f);
-    v3.write_data_in("b", &f);
-    v3.write_data_in("c", &f);
+    v3.write_data_in("a", &f)?;
+    v3.write_data_in("b", &f)?;
+    v3.write_data_in("c", &f)?;
 
     rt.add_fb(v0);
     rt.add_fb(v1);
@@ -122,9 +129,9 @@ pub fn test_id_conn_seq_voter() {
     rt.connect_event(("voter1", "voted"), ("voter2", "vote"));
     rt.connect_event(("voter2", "voted"), ("voter3", "vote"));
 
-    rt.connect_data(("voter0", "state"), ("voter3", "a"));
-    rt.connect_data(("voter1", "state"), ("voter3", "b"));
-    rt.connect_data(("voter2", "state"), ("voter3", "c"));
+    rt.connect_data(("voter0", "state"), ("voter3", "a"))?;
+    rt.connect_data(("voter1", "state"), ("voter3", "b"))?;
+    rt.connect_data(("voter2", "state"), ("voter3", "c"))?;
 
     rt.connect_event(("voter3", "voted"), ("voter0", "reset"));
     rt.connect_event(("voter3", "voted"), ("voter1", "reset"));
@@ -133,32 +140,194 @@ pub fn test_id_conn_seq_voter() {
     // TESTING ------------------------------------------------------------------------------------
     println!("{rt}");
 
-    rt.step(); // v0 ready -> vote
-    rt.send_from(); // send (v0, voted) -> (v1, vote)
+    rt.step()?; // v0 ready -> vote
+    rt.send_from()?; // send (v0, voted) -> (v1, vote)
 
     println!("{rt}");
 
-    rt.step(); // v0 vote -> votedpos & v1 ready -> vote
-    rt.send_from(); // send (v1, voted) -> (v2, vote)
+    rt.step()?; // v0 vote -> votedpos & v1 ready -> vote
+    rt.send_from()?; // send (v1, voted) -> (v2, vote)
 
     println!("{rt}");
 
-    rt.step(); // v1 vote -> votedpos & v2 ready -> vote
-    rt.send_from(); // send (v2, voted) -> (v3, vote)
-    rt.read_in(); // fetch (v0, v1, v2) state buffers into v3
+    rt.step()?; // v1 vote -> votedpos & v2 ready -> vote
+    rt.send_from()?; // send (v2, voted) -> (v3, vote)
+    rt.read_in()?; // fetch (v0, v1, v2) state buffers into v3
 
     println!("{rt}");
 
-    rt.step(); // v2 vote -> votedpos & v3 ready -> vote & reset to v1,2,3
+    rt.step()?; // v2 vote -> votedpos & v3 ready -> vote & reset to v1,2,3
 
     println!("{rt}");
 
-    rt.send_from(); // send (v3, voted) -> (v0-2, reset)
+    rt.send_from()?; // send (v3, voted) -> (v0-2, reset)
 
     println!("{rt}");
 
-    rt.step(); // v0-2 votedpos -> reset
-    rt.step(); // v0-2 reset -> ready
+    rt.step()?; // v0-2 votedpos -> reset
+    rt.step()?; // v0-2 reset -> ready
+
+    println!("{rt}");
+
+    Ok(())
+}
+
+pub fn test_id_conn_dynamic_names() -> Result<(), FbError> {
+    // SETUP --------------------------------------------------------------------------------------
+    let mut rt = run_time::id_conn::IdConnRuntime::default();
+    let t = DataBuffer::Bool(true);
+    let count = 4;
+
+    // instance names are only known at runtime
+    for i in 0..count {
+        let mut voter = Voter::new(&format!("voter{i}"));
+        voter.write_data_in("a", &t)?;
+        voter.write_data_in("b", &t)?;
+        rt.add_fb(voter);
+    }
+
+    for i in 1..count {
+        let from = format!("voter{}", i - 1);
+        let to = format!("voter{i}");
+
+        rt.connect_event((&from, "voted"), (&to, "vote"));
+        rt.connect_data((&from, "state"), (&to, "c"))?;
+    }
+
+    // TESTING ------------------------------------------------------------------------------------
+    rt.fbs_mut()
+        .get_mut("voter0")
+        .unwrap()
+        .set_event_in("vote")?;
+
+    let stable = rt.run(100)?;
+    println!("{rt}");
+    println!("[stable]: {stable}");
 
+    // removing a block also removes all of its connections
+    let removed = String::from("voter2");
+    rt.remove_fb(&removed);
     println!("{rt}");
+
+    Ok(())
+}
+
+pub fn test_id_conn_event_queues() -> Result<(), FbError> {
+    let policies = [
+        OverflowPolicy::DropNewest,
+        OverflowPolicy::DropOldest,
+        OverflowPolicy::Error,
+    ];
+
+    for policy in policies {
+        // SETUP ----------------------------------------------------------------------------------
+        let mut rt = run_time::id_conn::IdConnRuntime::default();
+
+        for i in 0..4 {
+            rt.add_fb(Voter::new(&format!("voter{i}")));
+        }
+
+        // voter3 can hold one pending event and one queued event
+        rt.configure_queue("voter3", 1, policy);
+
+        rt.connect_event(("voter0", "voted"), ("voter3", "vote"));
+        rt.connect_event(("voter1", "voted"), ("voter3", "reset"));
+        rt.connect_event(("voter2", "voted"), ("voter3", "vote"));
+
+        // TESTING --------------------------------------------------------------------------------
+        for i in 0..3 {
+            rt.fbs_mut()
+                .get_mut(&format!("voter{i}"))
+                .unwrap()
+                .set_event_in("vote")?;
+        }
+
+        rt.step()?; // voter0-2 ready -> vote
+        rt.step()?; // voter0-2 vote -> votedneg
+
+        match rt.send_from() {
+            Ok(()) => {}
+            Err(e) => println!("[expected error]: {e}"),
+        }
+
+        let queue = rt.queue("voter3").unwrap();
+        println!(
+            "[{policy:?}]: pending={:?}, queue={queue}, dropped={}",
+            rt.fbs()["voter3"].active_event_in(),
+            rt.dropped_events()
+        );
+    }
+
+    Ok(())
+}
+
+/// events are handed to idle function blocks right away, only busy ones queue them
+pub fn test_event_delivery() -> Result<(), Box<dyn Error>> {
+    // SETUP --------------------------------------------------------------------------------------
+    let mut id_rt = run_time::id_conn::IdConnRuntime::default();
+    let mut rc_rt = run_time::rc_conn::RcConnRuntime::default();
+
+    for i in 0..3 {
+        id_rt.add_fb(Voter::new(&format!("voter{i}")));
+        rc_rt.add_fb(Voter::new(&format!("voter{i}")));
+    }
+
+    // voter2 can not queue any event
+    id_rt.configure_queue("voter2", 0, OverflowPolicy::Error);
+    rc_rt.configure_queue("voter2", 0, OverflowPolicy::DropNewest);
+
+    id_rt.connect_event(("voter0", "voted"), ("voter2", "vote"));
+    id_rt.connect_event(("voter1", "voted"), ("voter2", "vote"));
+    rc_rt.connect_event((0, "voted"), (2, "vote"));
+
+    // TESTING ------------------------------------------------------------------------------------
+    for i in 0..2 {
+        id_rt
+            .fbs_mut()
+            .get_mut(&format!("voter{i}"))
+            .unwrap()
+            .set_event_in("vote")?;
+    }
+
+    rc_rt.fbs()[0].borrow_mut().set_event_in("vote")?;
+
+    for _ in 0..2 {
+        id_rt.step()?;
+        rc_rt.step()?;
+    }
+
+    rc_rt.send_from()?;
+
+    check(
+        "RC: an idle function block receives an event without queue",
+        || rc_rt.fbs()[2].borrow().active_event_in() == Some("vote") && rc_rt.dropped_events() == 0,
+    )?;
+
+    match id_rt.send_from() {
+        Err(e) => println!("[expected error]: {e}"),
+        Ok(()) => return Err("[failed] ID: the second event is not rejected".into()),
+    }
+
+    check(
+        "ID: an idle function block receives an event without queue",
+        || id_rt.fbs()["voter2"].active_event_in() == Some("vote") && id_rt.dropped_events() == 1,
+    )?;
+    check(
+        "ID: the out events are cleared after an overflow error",
+        || {
+            (0..2).all(|i| {
+                id_rt.fbs()[&format!("voter{i}")]
+                    .active_events_out()
+                    .is_empty()
+            })
+        },
+    )?;
+
+    id_rt.send_from()?;
+
+    check("ID: the out events are not sent again", || {
+        id_rt.dropped_events() == 1
+    })?;
+
+    Ok(())
 }
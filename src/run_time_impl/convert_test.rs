@@ -0,0 +1,201 @@
+//! Type conversions of `IEC 61131-3` (see `fb::data::convert`):
+//! - the library functions, including rounding, truncation and rejected values
+//! - `F_UINT_TO_DINT` bridging the `UINT` counter value of `E_CTU` to a `DINT`
+//! - the conversion functions in Structured Text
+
+use std::{collections::HashMap, error::Error, time::Duration};
+
+use crate::{
+    fb::data::{
+        calendar::{DateAndTime, TimeOfDay},
+        comm::DataBuffer,
+        convert,
+        ty::DataKind,
+    },
+    fb_impl,
+    run_time::id_conn::IdConnRuntime,
+    run_time_impl::check,
+    st::{Program, Variables},
+};
+
+const MAX_CYCLES: usize = 100;
+
+const PROGRAM: &str = "
+    rounded := REAL_TO_INT(x);
+    truncated := TRUNC(x);
+    text := INT_TO_STRING(rounded);
+    ms := TIME_TO_LINT(T#1m) + STRING_TO_LINT('16#FF');
+";
+
+pub fn test_conversions() -> Result<(), Box<dyn Error>> {
+    let dt: DateAndTime = "2024-01-31-08:30:15".parse()?;
+
+    let calls = [
+        ("UINT_TO_DINT", DataBuffer::UInt(5), DataBuffer::DInt(5)),
+        ("REAL_TO_INT", DataBuffer::Real(2.5), DataBuffer::Int(3)),
+        ("REAL_TO_INT", DataBuffer::Real(-2.5), DataBuffer::Int(-3)),
+        (
+            "LREAL_TO_USINT",
+            DataBuffer::LReal(1.49),
+            DataBuffer::USInt(1),
+        ),
+        ("BOOL_TO_WORD", DataBuffer::Bool(true), DataBuffer::Word(1)),
+        ("DINT_TO_BOOL", DataBuffer::DInt(-7), DataBuffer::Bool(true)),
+        (
+            "WORD_TO_BYTE",
+            DataBuffer::Word(0xbeef),
+            DataBuffer::Byte(0xef),
+        ),
+        (
+            "BYTE_TO_SINT",
+            DataBuffer::Byte(0x7f),
+            DataBuffer::SInt(127),
+        ),
+        (
+            "TIME_TO_LINT",
+            DataBuffer::Time(Duration::from_millis(1500)),
+            DataBuffer::LInt(1500),
+        ),
+        (
+            "LTIME_TO_LINT",
+            DataBuffer::LTime(Duration::from_micros(1)),
+            DataBuffer::LInt(1000),
+        ),
+        (
+            "UDINT_TO_TIME",
+            DataBuffer::UDInt(250),
+            DataBuffer::Time(Duration::from_millis(250)),
+        ),
+        (
+            "DT_TO_TOD",
+            DataBuffer::DateTime(dt),
+            DataBuffer::TimeOfDay(TimeOfDay::new(8, 30, 15, 0).ok_or("invalid TOD")?),
+        ),
+        (
+            "LDT_TO_DT",
+            DataBuffer::LDateTime(dt),
+            DataBuffer::DateTime(dt),
+        ),
+        (
+            "INT_TO_STRING",
+            DataBuffer::Int(-5),
+            DataBuffer::String(b"-5".to_vec()),
+        ),
+        (
+            "TIME_TO_STRING",
+            DataBuffer::Time(Duration::from_secs(90)),
+            DataBuffer::String(b"T#1m30s".to_vec()),
+        ),
+        (
+            "STRING_TO_REAL",
+            DataBuffer::String(b" 1.5 ".to_vec()),
+            DataBuffer::Real(1.5),
+        ),
+        (
+            "STRING_TO_WSTRING",
+            DataBuffer::String(b"abc".to_vec()),
+            DataBuffer::WString("abc".encode_utf16().collect()),
+        ),
+    ];
+
+    for (name, value, expected) in calls {
+        let converted = convert::call(name, &value)?;
+
+        check(&format!("{name}({value}) = {expected}"), || {
+            converted == expected
+        })?;
+    }
+
+    check("TRUNC(REAL#-2.7) = INT#-2", || {
+        convert::trunc(&DataBuffer::Real(-2.7), &DataKind::Int) == Ok(DataBuffer::Int(-2))
+    })?;
+
+    let invalid = [
+        ("UINT_TO_SINT", DataBuffer::UInt(300)),
+        ("INT_TO_UINT", DataBuffer::Int(-1)),
+        ("INT_TO_BYTE", DataBuffer::Int(256)),
+        ("REAL_TO_DINT", DataBuffer::Real(1e10)),
+        ("LREAL_TO_REAL", DataBuffer::LReal(1e300)),
+        ("LREAL_TO_INT", DataBuffer::LReal(f64::NAN)),
+        ("STRING_TO_INT", DataBuffer::String(b"abc".to_vec())),
+        ("STRING_TO_INT", DataBuffer::String(b"99#1".to_vec())),
+        ("DATE_TO_REAL", DataBuffer::Date(dt.date())),
+        ("UINT_TO_DINT", DataBuffer::Int(5)),
+    ];
+
+    for (name, value) in invalid {
+        match convert::call(name, &value) {
+            Err(e) => println!("[expected error]: {name}: {e}"),
+            Ok(buf) => return Err(format!("[failed] {name}({value}) = {buf}").into()),
+        }
+    }
+
+    test_conversion_fb()?;
+    test_st_conversions()
+}
+
+fn test_conversion_fb() -> Result<(), Box<dyn Error>> {
+    // SETUP --------------------------------------------------------------------------------------
+    let registry = fb_impl::builtin_registry();
+    let mut rt = IdConnRuntime::default();
+
+    rt.create_fb(&registry, "E_CTU", "ctu0")?;
+    rt.create_fb(&registry, "F_UINT_TO_DINT", "conv0")?;
+
+    rt.connect_event(("ctu0", "cuo"), ("conv0", "req"));
+    rt.connect_data(("ctu0", "cv"), ("conv0", "in"))?;
+
+    // TESTING ------------------------------------------------------------------------------------
+    println!("{}", rt.fbs()["conv0"].interface());
+
+    for _ in 0..3 {
+        rt.fbs_mut().get_mut("ctu0").unwrap().set_event_in("cu")?;
+        rt.run(MAX_CYCLES)?;
+    }
+
+    println!("{rt}");
+    check("F_UINT_TO_DINT: converts the counter value", || {
+        rt.fbs()["conv0"].read_data_out("out").ok() == Some(DataBuffer::DInt(3))
+    })?;
+
+    match rt.create_fb(&registry, "F_DATE_TO_REAL", "conv1") {
+        Err(e) => println!("[expected error]: {e}"),
+        Ok(()) => return Err("[failed] F_DATE_TO_REAL is created".into()),
+    }
+
+    Ok(())
+}
+
+fn test_st_conversions() -> Result<(), Box<dyn Error>> {
+    let program = Program::parse(PROGRAM)?;
+
+    let mut vars: HashMap<String, DataBuffer> = HashMap::from([
+        ("x".to_string(), DataBuffer::Real(-2.5)),
+        ("rounded".to_string(), DataBuffer::Int(0)),
+        ("truncated".to_string(), DataBuffer::Int(0)),
+        ("text".to_string(), DataBuffer::String(vec![])),
+        ("ms".to_string(), DataBuffer::LInt(0)),
+    ]);
+
+    program.execute(&mut vars)?;
+
+    check("ST: conversion functions", || {
+        vars.read("rounded") == Some(DataBuffer::Int(-3))
+            && vars.read("truncated") == Some(DataBuffer::Int(-2))
+            && vars.read("text") == Some(DataBuffer::String(b"-3".to_vec()))
+            && vars.read("ms") == Some(DataBuffer::LInt(60_255))
+    })?;
+
+    for text in [
+        "rounded := UINT_TO_INT(-1);",
+        "rounded := REAL_TO_INT(1e10);",
+    ] {
+        if let Err(e) = Program::parse(text).and_then(|p| p.execute(&mut vars)) {
+            println!("[expected error]: {e}");
+        } else {
+            return Err(format!("[failed] ST: {text} is executed").into());
+        }
+    }
+
+    Ok(())
+}
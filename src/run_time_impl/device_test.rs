@@ -0,0 +1,71 @@
+//! Two resources of a `Device` running on their own threads:
+//! - Res1 counts up to three in a loop of `ctu0` and `switch0`
+//! - Res2 latches the end of the loop in `sr0` and counts along in `ctu1`,
+//!   whose preset is the count of `ctu0`, sent WITH `cuo` across the resources
+
+use std::error::Error;
+
+use crate::{
+    fb::{Bfb, data::comm::DataBuffer},
+    fb_impl::event::{ctu::E_CTU, sr::E_SR, switch::E_SWITCH},
+    run_time::{device::Device, id_conn::IdConnRuntime},
+};
+
+pub fn test_device_resources() -> Result<(), Box<dyn Error>> {
+    // SETUP --------------------------------------------------------------------------------------
+    let mut counter = IdConnRuntime::default();
+
+    let mut ctu0 = E_CTU::new("ctu0");
+    ctu0.write_data_in("pv", &DataBuffer::UInt(3))?;
+
+    counter.add_fb(ctu0);
+    counter.add_fb(E_SWITCH::new("switch0"));
+
+    counter.connect_event(("ctu0", "cuo"), ("switch0", "ei"));
+    counter.connect_data(("ctu0", "q"), ("switch0", "g"))?;
+    counter.connect_event(("switch0", "eo0"), ("ctu0", "cu"));
+
+    let mut follower = IdConnRuntime::default();
+    follower.add_fb(E_SR::new("sr0"));
+    follower.add_fb(E_CTU::new("ctu1"));
+
+    let mut device = Device::new("Dev");
+    device.add_resource("Res1", counter);
+    device.add_resource("Res2", follower);
+
+    device.connect_event(("Res1", "switch0", "eo1"), ("Res2", "sr0", "s"));
+    device.connect_event(("Res1", "ctu0", "cuo"), ("Res2", "ctu1", "cu"));
+    device.connect_data(("Res1", "ctu0", "cv"), ("Res2", "ctu1", "pv"))?;
+
+    println!("{device}");
+
+    // TESTING ------------------------------------------------------------------------------------
+    device.start();
+    device.post("Res1", "ctu0", "cu");
+    device.wait_idle();
+
+    if let Some(snapshot) = device.snapshot("Res2") {
+        println!("[running Res2]:\n{snapshot}");
+    }
+
+    device.stop();
+
+    let res2 = device.runtime("Res2").ok_or("Res2 is not stopped")?;
+    let sr0 = &res2.fbs()["sr0"];
+    let ctu1 = &res2.fbs()["ctu1"];
+
+    println!("[Res2.sr0.q]: {}", sr0.read_data_out("q")?);
+    println!("[Res2.ctu1.cv]: {}", ctu1.read_data_out("cv")?);
+    println!("[Res2.ctu1.q]: {}", ctu1.read_data_out("q")?);
+
+    if sr0.read_data_out("q")? != DataBuffer::Bool(true)
+        || ctu1.read_data_out("cv")? != DataBuffer::UInt(3)
+        || ctu1.read_data_out("q")? != DataBuffer::Bool(true)
+    {
+        return Err("[failed] Res2 did not follow Res1".into());
+    }
+
+    println!("[ok] Res2 followed Res1");
+
+    Ok(())
+}
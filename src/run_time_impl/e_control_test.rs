@@ -2,67 +2,84 @@
 //! - `E_CTU`
 //! - `E_SR`
 //! - `E_SWITCH`
+//!
+//! The remaining event function blocks are checked by firing input events at a single instance
+//! and comparing the emitted output events and data:
+//! - `E_SPLIT`, `E_MERGE`, `E_REND`
+//! - `E_PERMIT`, `E_SELECT`, `E_DEMUX`
+//! - `E_RS`, `E_D_FF`, `E_R_TRIG`, `E_F_TRIG`
+//! - `E_CTD`, `E_CTUD`
+
+use std::error::Error;
 
 use crate::{
-    fb::{Bfb, data::comm::DataBuffer},
+    fb::{Bfb, data::comm::DataBuffer, error::FbError},
     fb_impl::event,
-    run_time,
+    run_time::{self, id_conn::IdConnRuntime},
+    run_time_impl::check,
 };
 
-pub fn test_ctu() {
+/// upper bound of ECC steps after firing a single input event
+const MAX_STEPS: usize = 10;
+
+pub fn test_ctu() -> Result<(), FbError> {
     let mut rt = run_time::id_conn::IdConnRuntime::default();
 
     let pv = DataBuffer::UInt(100);
 
     let mut ctu = event::ctu::E_CTU::new("ctu0");
-    ctu.write_data_in("pv", &pv);
-    ctu.set_event_in("cu");
+    ctu.write_data_in("pv", &pv)?;
+    ctu.set_event_in("cu")?;
 
     rt.add_fb(ctu);
 
     for count_step in 1..=101 {
-        rt.step(); // Start -> cu
-        rt.step(); // cu -> Start
+        rt.step()?; // Start -> cu
+        rt.step()?; // cu -> Start
 
         {
             let fbs = rt.fbs_mut();
             let ctu_mut = fbs.get_mut("ctu0").unwrap();
             println!("[increment {count_step}]: {}", ctu_mut);
-            ctu_mut.set_event_in("cu"); // receive next cu event
+            ctu_mut.set_event_in("cu")?; // receive next cu event
         }
     }
+
+    Ok(())
 }
 
-pub fn test_sr() {
+pub fn test_sr() -> Result<(), FbError> {
     let mut rt = run_time::id_conn::IdConnRuntime::default();
 
     let mut sr = event::sr::E_SR::new("sr0");
-    sr.set_event_in("s"); // Q0 -> SET
+    sr.set_event_in("s")?; // Q0 -> SET
     rt.add_fb(sr);
 
-    rt.step();
+    rt.step()?;
     println!("{rt}");
 
     {
         let fbs = rt.fbs_mut();
         let sr_mut = fbs.get_mut("sr0").unwrap();
-        sr_mut.set_event_in("r");
+        sr_mut.set_event_in("r")?;
     }
 
-    rt.step();
+    rt.step()?;
     println!("{rt}");
 
     {
         let fbs = rt.fbs_mut();
         let sr_mut = fbs.get_mut("sr0").unwrap();
-        sr_mut.set_event_in("s");
+        sr_mut.set_event_in("s")?;
     }
 
-    rt.step();
+    rt.step()?;
     println!("{rt}");
+
+    Ok(())
 }
 
-pub fn test_switch() {
+pub fn test_switch() -> Result<(), FbError> {
     let mut rt = run_time::id_conn::IdConnRuntime::default();
 
     let t = DataBuffer::Bool(true);
@@ -71,8 +88,8 @@ pub fn test_switch() {
     let mut switch = event::switch::E_SWITCH::new("switch0");
 
     // set switch to fire eo0
-    switch.write_data_in("g", &f);
-    switch.set_event_in("ei");
+    switch.write_data_in("g", &f)?;
+    switch.set_event_in("ei")?;
 
     let sr = event::sr::E_SR::new("sr0");
 
@@ -82,16 +99,16 @@ pub fn test_switch() {
     rt.connect_event(("switch0", "eo0"), ("sr0", "s"));
     rt.connect_event(("switch0", "eo1"), ("sr0", "r"));
 
-    rt.step(); // switch Start -> G0 + fires eo0
-    rt.step(); // switch G0 -> Start
+    rt.step()?; // switch Start -> G0 + fires eo0
+    rt.step()?; // switch G0 -> Start
 
     println!("{rt}");
 
     // send event from switch0 to sr0
     println!("switch -> buffer -> sr");
-    rt.send_from();
-    rt.read_in();
-    rt.step(); // sr: Q0 -> SET
+    rt.send_from()?;
+    rt.read_in()?;
+    rt.step()?; // sr: Q0 -> SET
 
     println!("{rt}");
 
@@ -99,19 +116,380 @@ pub fn test_switch() {
         let fbs_mut = rt.fbs_mut();
         let switch_mut = fbs_mut.get_mut("switch0").unwrap();
 
-        switch_mut.write_data_in("g", &t);
-        switch_mut.set_event_in("ei");
+        switch_mut.write_data_in("g", &t)?;
+        switch_mut.set_event_in("ei")?;
     }
 
-    rt.step(); // switch Start -> G1 + fires eo1
-    rt.step(); // switch G1 -> Start
+    rt.step()?; // switch Start -> G1 + fires eo1
+    rt.step()?; // switch G1 -> Start
     println!("{rt}");
 
     // send event from switch0 to sr0
     println!("switch -> buffer -> sr");
-    rt.send_from();
-    rt.read_in();
-    rt.step(); // sr: Q0 -> SET
+    rt.send_from()?;
+    rt.read_in()?;
+    rt.step()?; // sr: Q0 -> SET
 
     println!("{rt}");
+
+    Ok(())
+}
+
+pub fn test_split_merge_rend() -> Result<(), Box<dyn Error>> {
+    let mut rt = IdConnRuntime::default();
+
+    rt.add_fb(event::split::E_SPLIT::new("split0"));
+    rt.add_fb(event::merge::E_MERGE::new("merge0"));
+    rt.add_fb(event::rend::E_REND::new("rend0"));
+
+    check_emitted(
+        "E_SPLIT: ei emits eo1 and eo2",
+        fire(&mut rt, "split0", "ei")?,
+        &["eo1", "eo2"],
+    )?;
+
+    check_emitted(
+        "E_MERGE: ei1 emits eo",
+        fire(&mut rt, "merge0", "ei1")?,
+        &["eo"],
+    )?;
+    check_emitted(
+        "E_MERGE: ei2 emits eo",
+        fire(&mut rt, "merge0", "ei2")?,
+        &["eo"],
+    )?;
+
+    check_emitted(
+        "E_REND: ei1 alone waits",
+        fire(&mut rt, "rend0", "ei1")?,
+        &[],
+    )?;
+    check_emitted(
+        "E_REND: ei1 again still waits",
+        fire(&mut rt, "rend0", "ei1")?,
+        &[],
+    )?;
+    check_emitted(
+        "E_REND: ei2 completes the rendezvous",
+        fire(&mut rt, "rend0", "ei2")?,
+        &["eo"],
+    )?;
+    check_emitted(
+        "E_REND: ei2 alone waits",
+        fire(&mut rt, "rend0", "ei2")?,
+        &[],
+    )?;
+    check_emitted("E_REND: r forgets ei2", fire(&mut rt, "rend0", "r")?, &[])?;
+    check_emitted(
+        "E_REND: ei1 after r waits",
+        fire(&mut rt, "rend0", "ei1")?,
+        &[],
+    )
+}
+
+pub fn test_permit_select_demux() -> Result<(), Box<dyn Error>> {
+    let mut rt = IdConnRuntime::default();
+
+    let t = DataBuffer::Bool(true);
+    let f = DataBuffer::Bool(false);
+
+    rt.add_fb(event::permit::E_PERMIT::new("permit0"));
+    rt.add_fb(event::select::E_SELECT::new("select0"));
+    rt.add_fb(event::demux::E_DEMUX::new("demux0"));
+
+    write(&mut rt, "permit0", "permit", &f)?;
+    check_emitted("E_PERMIT: blocks ei", fire(&mut rt, "permit0", "ei")?, &[])?;
+    write(&mut rt, "permit0", "permit", &t)?;
+    check_emitted(
+        "E_PERMIT: passes ei",
+        fire(&mut rt, "permit0", "ei")?,
+        &["eo"],
+    )?;
+
+    write(&mut rt, "select0", "g", &f)?;
+    check_emitted(
+        "E_SELECT: g = FALSE passes ei0",
+        fire(&mut rt, "select0", "ei0")?,
+        &["eo"],
+    )?;
+    check_emitted(
+        "E_SELECT: g = FALSE blocks ei1",
+        fire(&mut rt, "select0", "ei1")?,
+        &[],
+    )?;
+    write(&mut rt, "select0", "g", &t)?;
+    check_emitted(
+        "E_SELECT: g = TRUE blocks ei0",
+        fire(&mut rt, "select0", "ei0")?,
+        &[],
+    )?;
+    check_emitted(
+        "E_SELECT: g = TRUE passes ei1",
+        fire(&mut rt, "select0", "ei1")?,
+        &["eo"],
+    )?;
+
+    for k in 0..4 {
+        write(&mut rt, "demux0", "k", &DataBuffer::UInt(k))?;
+        check_emitted(
+            &format!("E_DEMUX: k = {k} emits eo{k}"),
+            fire(&mut rt, "demux0", "ei")?,
+            &[&format!("eo{k}")],
+        )?;
+    }
+
+    write(&mut rt, "demux0", "k", &DataBuffer::UInt(4))?;
+    check_emitted(
+        "E_DEMUX: k = 4 is discarded",
+        fire(&mut rt, "demux0", "ei")?,
+        &[],
+    )
+}
+
+pub fn test_rs_d_ff_trig() -> Result<(), Box<dyn Error>> {
+    let mut rt = IdConnRuntime::default();
+
+    let t = DataBuffer::Bool(true);
+    let f = DataBuffer::Bool(false);
+
+    rt.add_fb(event::rs::E_RS::new("rs0"));
+    rt.add_fb(event::d_ff::E_D_FF::new("d_ff0"));
+    rt.add_fb(event::r_trig::E_R_TRIG::new("r_trig0"));
+    rt.add_fb(event::f_trig::E_F_TRIG::new("f_trig0"));
+
+    check_emitted(
+        "E_RS: initial r emits eo",
+        fire(&mut rt, "rs0", "r")?,
+        &["eo"],
+    )?;
+    check_emitted("E_RS: s emits eo", fire(&mut rt, "rs0", "s")?, &["eo"])?;
+    check_data(&rt, "E_RS: s sets q", "rs0", "q", &t)?;
+    check_emitted(
+        "E_RS: repeated s is discarded",
+        fire(&mut rt, "rs0", "s")?,
+        &[],
+    )?;
+    check_emitted("E_RS: r emits eo", fire(&mut rt, "rs0", "r")?, &["eo"])?;
+    check_data(&rt, "E_RS: r resets q", "rs0", "q", &f)?;
+
+    check_emitted(
+        "E_D_FF: clk with d = FALSE keeps q",
+        fire(&mut rt, "d_ff0", "clk")?,
+        &[],
+    )?;
+    write(&mut rt, "d_ff0", "d", &t)?;
+    check_emitted(
+        "E_D_FF: clk with d = TRUE emits eo",
+        fire(&mut rt, "d_ff0", "clk")?,
+        &["eo"],
+    )?;
+    check_data(&rt, "E_D_FF: clk latches d", "d_ff0", "q", &t)?;
+    check_emitted(
+        "E_D_FF: unchanged d is not emitted",
+        fire(&mut rt, "d_ff0", "clk")?,
+        &[],
+    )?;
+    write(&mut rt, "d_ff0", "d", &f)?;
+    check_emitted(
+        "E_D_FF: changed d emits eo",
+        fire(&mut rt, "d_ff0", "clk")?,
+        &["eo"],
+    )?;
+    check_data(&rt, "E_D_FF: clk latches d again", "d_ff0", "q", &f)?;
+
+    for (qi, expected) in [
+        (&f, &[][..]),
+        (&t, &["eo"]),
+        (&t, &[]),
+        (&f, &[]),
+        (&t, &["eo"]),
+    ] {
+        write(&mut rt, "r_trig0", "qi", qi)?;
+        check_emitted(
+            &format!("E_R_TRIG: ei with qi = {qi} emits {expected:?}"),
+            fire(&mut rt, "r_trig0", "ei")?,
+            expected,
+        )?;
+    }
+
+    for (qi, expected) in [
+        (&f, &[][..]),
+        (&t, &[]),
+        (&t, &[]),
+        (&f, &["eo"]),
+        (&f, &[]),
+    ] {
+        write(&mut rt, "f_trig0", "qi", qi)?;
+        check_emitted(
+            &format!("E_F_TRIG: ei with qi = {qi} emits {expected:?}"),
+            fire(&mut rt, "f_trig0", "ei")?,
+            expected,
+        )?;
+    }
+
+    Ok(())
+}
+
+pub fn test_ctd_ctud() -> Result<(), Box<dyn Error>> {
+    let mut rt = IdConnRuntime::default();
+
+    rt.add_fb(event::ctd::E_CTD::new("ctd0"));
+    rt.add_fb(event::ctud::E_CTUD::new("ctud0"));
+
+    write(&mut rt, "ctd0", "pv", &DataBuffer::UInt(2))?;
+    check_emitted(
+        "E_CTD: cd at zero is discarded",
+        fire(&mut rt, "ctd0", "cd")?,
+        &[],
+    )?;
+    check_emitted(
+        "E_CTD: ld emits ldo",
+        fire(&mut rt, "ctd0", "ld")?,
+        &["ldo"],
+    )?;
+    check_data(
+        &rt,
+        "E_CTD: ld loads pv",
+        "ctd0",
+        "cv",
+        &DataBuffer::UInt(2),
+    )?;
+    check_emitted(
+        "E_CTD: cd emits cdo",
+        fire(&mut rt, "ctd0", "cd")?,
+        &["cdo"],
+    )?;
+    check_data(
+        &rt,
+        "E_CTD: cd above zero",
+        "ctd0",
+        "q",
+        &DataBuffer::Bool(false),
+    )?;
+    check_emitted(
+        "E_CTD: cd emits cdo again",
+        fire(&mut rt, "ctd0", "cd")?,
+        &["cdo"],
+    )?;
+    check_data(
+        &rt,
+        "E_CTD: cd reaches zero",
+        "ctd0",
+        "q",
+        &DataBuffer::Bool(true),
+    )?;
+
+    write(&mut rt, "ctud0", "pv", &DataBuffer::UInt(2))?;
+    check_emitted(
+        "E_CTUD: cd at zero is discarded",
+        fire(&mut rt, "ctud0", "cd")?,
+        &[],
+    )?;
+    check_emitted(
+        "E_CTUD: cu emits co",
+        fire(&mut rt, "ctud0", "cu")?,
+        &["co"],
+    )?;
+    check_emitted(
+        "E_CTUD: cu emits co again",
+        fire(&mut rt, "ctud0", "cu")?,
+        &["co"],
+    )?;
+    check_data(
+        &rt,
+        "E_CTUD: cu reaches pv",
+        "ctud0",
+        "qu",
+        &DataBuffer::Bool(true),
+    )?;
+    check_emitted(
+        "E_CTUD: cd emits co",
+        fire(&mut rt, "ctud0", "cd")?,
+        &["co"],
+    )?;
+    check_data(
+        &rt,
+        "E_CTUD: cd below pv",
+        "ctud0",
+        "qu",
+        &DataBuffer::Bool(false),
+    )?;
+    check_emitted("E_CTUD: r emits ro", fire(&mut rt, "ctud0", "r")?, &["ro"])?;
+    check_data(
+        &rt,
+        "E_CTUD: r reaches zero",
+        "ctud0",
+        "qd",
+        &DataBuffer::Bool(true),
+    )?;
+    check_emitted(
+        "E_CTUD: ld emits ldo",
+        fire(&mut rt, "ctud0", "ld")?,
+        &["ldo"],
+    )?;
+    check_data(
+        &rt,
+        "E_CTUD: ld loads pv",
+        "ctud0",
+        "cv",
+        &DataBuffer::UInt(2),
+    )
+}
+
+/// sets the input event and invokes the execution control until it is stable,
+/// returns the emitted output events in order
+fn fire(rt: &mut IdConnRuntime, fb_name: &str, event: &str) -> Result<Vec<String>, FbError> {
+    let fb = rt.fbs_mut().get_mut(fb_name).unwrap();
+    let mut emitted = vec![];
+
+    fb.set_event_in(event)?;
+
+    for _ in 0..MAX_STEPS {
+        let fired = fb.invoke_execution_control()?;
+
+        emitted.extend(fb.active_events_out().into_iter().map(str::to_string));
+        fb.clear_event_out();
+
+        if !fired {
+            break;
+        }
+    }
+
+    println!("[{fb_name}.{event}]: {fb}");
+
+    Ok(emitted)
+}
+
+fn write(
+    rt: &mut IdConnRuntime,
+    fb_name: &str,
+    data: &str,
+    buf: &DataBuffer,
+) -> Result<(), FbError> {
+    rt.fbs_mut()
+        .get_mut(fb_name)
+        .unwrap()
+        .write_data_in(data, buf)
+}
+
+fn check_emitted(
+    label: &str,
+    emitted: Vec<String>,
+    expected: &[&str],
+) -> Result<(), Box<dyn Error>> {
+    check(label, || emitted == expected)
+        .map_err(|e| format!("{e}: emitted {emitted:?}, expected {expected:?}").into())
+}
+
+fn check_data(
+    rt: &IdConnRuntime,
+    label: &str,
+    fb_name: &str,
+    data: &str,
+    expected: &DataBuffer,
+) -> Result<(), Box<dyn Error>> {
+    let value = rt.fbs()[fb_name].read_data_out(data)?;
+
+    check(label, || value == *expected)
+        .map_err(|e| format!("{e}: {data}={value}, expected {expected}").into())
 }
@@ -0,0 +1,125 @@
+//! Parses the `IEC 61499-2` type files in `types/` and checks them against the implemented function blocks:
+//! - `E_CTU`
+//! - `VOTER`
+//!
+//! and splits transition conditions into their event and guard
+
+use crate::{
+    fb::desc::{self, FbBodyDesc, FbTypeDesc},
+    fb_impl::{event::ctu::E_CTU, voter::dynamic_disp::Voter},
+    xml::{XmlError, fbt},
+};
+
+const E_CTU_FBT: &str = include_str!("../../types/E_CTU.fbt");
+const VOTER_FBT: &str = include_str!("../../types/VOTER.fbt");
+
+pub fn test_parse_fbt() -> Result<(), XmlError> {
+    let ctu = fbt::parse_fbt(E_CTU_FBT)?;
+    print_type(&ctu);
+    print_deviations(
+        &ctu,
+        desc::check_conformance(&ctu.interface, &E_CTU::new("ctu0")),
+    );
+
+    let voter = fbt::parse_fbt(VOTER_FBT)?;
+    print_type(&voter);
+    print_deviations(
+        &voter,
+        desc::check_conformance(&voter.interface, &Voter::new("voter0")),
+    );
+
+    // errors report the location inside of the file
+    let broken = E_CTU_FBT.replace("Type=\"UINT\"", "Type=\"UNIT\"");
+
+    if let Err(e) = fbt::parse_fbt(&broken) {
+        println!("[expected error]: {e}");
+    }
+
+    Ok(())
+}
+
+/// a `[` only opens a guard after an input event, otherwise it indexes an array
+pub fn test_parse_conditions() -> Result<(), XmlError> {
+    let fbt = E_CTU_FBT
+        .replace(
+            "<VarDeclaration Name=\"PV\"",
+            "<VarDeclaration Name=\"FLAGS\" Type=\"BOOL\" ArraySize=\"2\"/>\n      <VarDeclaration Name=\"PV\"",
+        )
+        .replace("CU[CV &lt; 65535]", "CU [CV &lt; 5]")
+        .replace("Condition=\"R\"", "Condition=\"FLAGS[1]\"");
+
+    let FbBodyDesc::Basic(basic) = fbt::parse_fbt(&fbt)?.body else {
+        return Ok(());
+    };
+
+    for (destination, expected) in [
+        ("CU", (Some("CU"), Some("CV < 5"))),
+        ("R", (None, Some("FLAGS[1]"))),
+    ] {
+        let Some(transition) = basic
+            .ecc
+            .transitions
+            .iter()
+            .find(|t| t.source == "START" && t.destination == destination)
+        else {
+            println!("START -> {destination} [MISMATCH]");
+            continue;
+        };
+
+        let parsed = (transition.event.as_deref(), transition.guard.as_deref());
+
+        println!(
+            "START -> {destination}: event {:?}, guard {:?} [{}]",
+            parsed.0,
+            parsed.1,
+            if parsed == expected { "ok" } else { "MISMATCH" }
+        );
+    }
+
+    Ok(())
+}
+
+fn print_type(ty: &FbTypeDesc) {
+    println!("{} ({})", ty.name, ty.comment);
+
+    for event in &ty.interface.event_inputs {
+        println!("  EVENT IN  {} WITH {:?}", event.name, event.with);
+    }
+
+    for event in &ty.interface.event_outputs {
+        println!("  EVENT OUT {} WITH {:?}", event.name, event.with);
+    }
+
+    for var in &ty.interface.input_vars {
+        println!("  VAR IN    {}: {}", var.name, var.kind.iec_name());
+    }
+
+    for var in &ty.interface.output_vars {
+        println!("  VAR OUT   {}: {}", var.name, var.kind.iec_name());
+    }
+
+    if let FbBodyDesc::Basic(basic) = &ty.body {
+        println!("{}", basic.ecc);
+
+        for alg in &basic.algorithms {
+            println!(
+                "ALGORITHM {} IN {}:\n{}",
+                alg.name,
+                alg.language,
+                alg.text.trim()
+            );
+        }
+    }
+
+    println!();
+}
+
+fn print_deviations(ty: &FbTypeDesc, deviations: Vec<String>) {
+    if deviations.is_empty() {
+        println!("{} conforms to its type definition\n", ty.name);
+    }
+
+    for deviation in deviations {
+        println!("[deviation {}]: {deviation}", ty.name);
+    }
+}
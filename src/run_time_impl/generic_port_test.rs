@@ -0,0 +1,195 @@
+//! Generic data ports (see `data::ty::GenericKind`):
+//! - `ANY_NUM`, `ANY_INT`, ... accept the kinds of their category
+//! - the ports of `F_ADD` are fixed to the kind of the other end when they are connected
+//! - a fixed port rejects values of other kinds, mismatching connections are rejected by both runtimes
+
+use std::error::Error;
+
+use crate::{
+    fb::{
+        Bfb,
+        data::{
+            comm::DataBuffer,
+            ty::{self, DataKind, GenericKind},
+        },
+    },
+    fb_impl::{self, arith::F_ADD, event::ctu::E_CTU},
+    run_time::{id_conn::IdConnRuntime, rc_conn::RcConnRuntime, validate::Diagnostic},
+    run_time_impl::check,
+};
+
+const MAX_CYCLES: usize = 100;
+
+pub fn test_generic_ports() -> Result<(), Box<dyn Error>> {
+    let contained = [
+        (GenericKind::Any, DataKind::DateTime),
+        (GenericKind::AnyElementary, DataKind::WString),
+        (GenericKind::AnyNum, DataKind::UInt),
+        (GenericKind::AnyNum, DataKind::LReal),
+        (GenericKind::AnyInt, DataKind::SInt),
+        (GenericKind::AnyReal, DataKind::Real),
+        (GenericKind::AnyBit, DataKind::Bool),
+        (GenericKind::AnyString, DataKind::String),
+    ];
+    let rejected = [
+        (GenericKind::AnyNum, DataKind::Word),
+        (GenericKind::AnyInt, DataKind::Real),
+        (GenericKind::AnyReal, DataKind::Int),
+        (GenericKind::AnyBit, DataKind::UInt),
+        (GenericKind::AnyString, DataKind::Time),
+    ];
+
+    for (generic, kind) in &contained {
+        check(
+            &format!("{} contains {}", generic.iec_name(), kind.iec_name()),
+            || generic.contains(kind) && ty::assignable(kind, &DataKind::Generic(*generic)),
+        )?;
+    }
+
+    for (generic, kind) in &rejected {
+        check(
+            &format!(
+                "{} does not contain {}",
+                generic.iec_name(),
+                kind.iec_name()
+            ),
+            || !ty::assignable(kind, &DataKind::Generic(*generic)),
+        )?;
+    }
+
+    check("ANY_INT and ANY_REAL share no kind", || {
+        !ty::assignable(
+            &DataKind::Generic(GenericKind::AnyInt),
+            &DataKind::Generic(GenericKind::AnyReal),
+        )
+    })?;
+
+    test_first_value()?;
+    test_fixing_network()?;
+    test_mismatching_network()
+}
+
+/// unconnected generic ports are fixed by the first value
+fn test_first_value() -> Result<(), Box<dyn Error>> {
+    let mut add = F_ADD::new("add0");
+
+    println!("{}", add.interface());
+    check("F_ADD: unconnected ports are generic", || {
+        add.data_kind("in1").ok() == Some(DataKind::Generic(GenericKind::AnyNum))
+    })?;
+
+    add.write_data_in("in1", &DataBuffer::Real(1.5))?;
+    add.write_data_in("in2", &DataBuffer::Int(2))?;
+    check("F_ADD: ports are fixed by the first value", || {
+        add.data_kind("in1").ok() == Some(DataKind::Real)
+            && add.data_kind("in2").ok() == Some(DataKind::Int)
+    })?;
+
+    for (data, buf) in [
+        ("in1", DataBuffer::LReal(1.0)),
+        ("in2", DataBuffer::Word(1)),
+    ] {
+        match add.write_data_in(data, &buf) {
+            Err(e) => println!("[expected error]: {e}"),
+            Ok(()) => return Err(format!("[failed] F_ADD: {buf} is written to {data}").into()),
+        }
+    }
+
+    add.set_event_in("req")?;
+    while add.invoke_execution_control()? {}
+
+    println!("{add}");
+    check("F_ADD: INT is widened to REAL", || {
+        add.read_data_out("out").ok() == Some(DataBuffer::Real(3.5))
+    })
+}
+
+fn test_fixing_network() -> Result<(), Box<dyn Error>> {
+    // SETUP --------------------------------------------------------------------------------------
+    let registry = fb_impl::builtin_registry();
+    let mut rt = IdConnRuntime::default();
+
+    rt.create_fb(&registry, "E_CTU", "ctu0")?;
+    rt.create_fb(&registry, "F_ADD", "add0")?;
+    rt.create_fb(&registry, "F_UDINT_TO_STRING", "text0")?;
+
+    rt.connect_event(("ctu0", "cuo"), ("add0", "req"));
+    rt.connect_data(("ctu0", "cv"), ("add0", "in1"))?;
+    rt.connect_data(("ctu0", "cv"), ("add0", "in2"))?;
+    rt.connect_event(("add0", "cnf"), ("text0", "req"));
+    rt.connect_data(("add0", "out"), ("text0", "in"))?;
+
+    // TESTING ------------------------------------------------------------------------------------
+    println!("{}", rt.fbs()["add0"].interface());
+    check(
+        "NETWORK: inputs are fixed to the UINT counter value",
+        || {
+            rt.fbs()["add0"].data_kind("in1").ok() == Some(DataKind::UInt)
+                && rt.fbs()["add0"].data_kind("in2").ok() == Some(DataKind::UInt)
+        },
+    )?;
+    check("NETWORK: output is fixed to the UDINT input", || {
+        rt.fbs()["add0"].data_kind("out").ok() == Some(DataKind::UDInt)
+    })?;
+    check("NETWORK: fixed connections are valid", || {
+        rt.validate()
+            .iter()
+            .all(|d| !matches!(d, Diagnostic::KindMismatch { .. }))
+    })?;
+
+    for _ in 0..3 {
+        rt.fbs_mut().get_mut("ctu0").unwrap().set_event_in("cu")?;
+        rt.run(MAX_CYCLES)?;
+    }
+
+    println!("{rt}");
+    check("NETWORK: the UINT sum is widened to UDINT", || {
+        rt.fbs()["add0"].read_data_out("out").ok() == Some(DataBuffer::UDInt(6))
+            && rt.fbs()["text0"].read_data_out("out").ok()
+                == Some(DataBuffer::String(b"6".to_vec()))
+    })?;
+
+    match rt
+        .fbs_mut()
+        .get_mut("add0")
+        .unwrap()
+        .fix_data_kind("in1", &DataKind::LInt)
+    {
+        Err(e) => println!("[expected error]: {e}"),
+        Ok(()) => return Err("[failed] NETWORK: a fixed port is fixed again".into()),
+    }
+
+    Ok(())
+}
+
+fn test_mismatching_network() -> Result<(), Box<dyn Error>> {
+    let registry = fb_impl::builtin_registry();
+    let mut rt = IdConnRuntime::default();
+
+    rt.create_fb(&registry, "F_UINT_TO_STRING", "text0")?;
+    rt.create_fb(&registry, "F_ADD", "add0")?;
+
+    match rt.connect_data(("text0", "out"), ("add0", "in1")) {
+        Err(e) => println!("[expected error]: {e}"),
+        Ok(()) => return Err("[failed] NETWORK: STRING is connected to ANY_NUM".into()),
+    }
+
+    check("NETWORK: STRING is not connected to ANY_NUM", || {
+        rt.data_conns().is_empty()
+            && rt.fbs()["add0"].data_kind("in1").ok()
+                == Some(DataKind::Generic(GenericKind::AnyNum))
+    })?;
+
+    let mut rc_rt = RcConnRuntime::default();
+
+    rc_rt.add_fb(E_CTU::new("ctu0"));
+    rc_rt.add_fb(F_ADD::new("add0"));
+    rc_rt.add_fb(F_ADD::new("add1"));
+    rc_rt.connect_data((0, "cv"), (1, "in1"))?;
+    rc_rt.connect_data((0, "q"), (2, "in1"))?;
+    rc_rt.connect_data((1, "out"), (2, "in2"))?;
+
+    check("RC: only the numeric connections are created", || {
+        rc_rt.data_conns().len() == 2
+    })
+}
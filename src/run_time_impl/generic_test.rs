@@ -0,0 +1,188 @@
+//! Runs function blocks interpreted from the type files in `types/` with `GenericBfb`:
+//! - `E_CTU` and `VOTER` side by side with their Rust implementations
+//! - `systems/COUNTER.sys` with the interpreted `E_CTU` replacing the Rust one
+//! - an input event is only evaluated in the step it was received in
+
+use std::{error::Error, sync::Arc};
+
+use crate::{
+    fb::{Bfb, data::comm::DataBuffer, desc},
+    fb_impl::{self, event::ctu::E_CTU, generic::GenericType, voter::dynamic_disp::Voter},
+    run_time::config::SystemRuntime,
+    xml::fbt,
+};
+
+const E_CTU_FBT: &str = include_str!("../../types/E_CTU.fbt");
+const VOTER_FBT: &str = include_str!("../../types/VOTER.fbt");
+const COUNTER_SYS: &str = include_str!("../../systems/COUNTER.sys");
+
+/// `ARM` moves to `ARMED` on its guard alone, `ARMED` only leaves on `EV`
+const E_LATCH_FBT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<FBType Name="E_LATCH">
+  <InterfaceList>
+    <EventInputs>
+      <Event Name="EV" Type="Event">
+        <With Var="ARM"/>
+      </Event>
+    </EventInputs>
+    <EventOutputs>
+      <Event Name="EO" Type="Event"/>
+    </EventOutputs>
+    <InputVars>
+      <VarDeclaration Name="ARM" Type="BOOL"/>
+    </InputVars>
+  </InterfaceList>
+  <BasicFB>
+    <ECC>
+      <ECState Name="START"/>
+      <ECState Name="ARMED"/>
+      <ECState Name="FIRED">
+        <ECAction Output="EO"/>
+      </ECState>
+      <ECTransition Source="START" Destination="ARMED" Condition="ARM"/>
+      <ECTransition Source="ARMED" Destination="FIRED" Condition="EV"/>
+      <ECTransition Source="FIRED" Destination="START" Condition="1"/>
+    </ECC>
+  </BasicFB>
+</FBType>
+"#;
+
+pub fn test_generic_ctu() -> Result<(), Box<dyn Error>> {
+    let ty = GenericType::new(fbt::parse_fbt(E_CTU_FBT)?)?;
+
+    let mut generic = ty.instantiate("generic_ctu");
+    let mut native = E_CTU::new("native_ctu");
+
+    print_deviations(&ty, &generic);
+
+    for fb in [&mut generic as &mut dyn Bfb, &mut native] {
+        fb.write_data_in("pv", &DataBuffer::UInt(3))?;
+    }
+
+    for event in ["cu", "cu", "cu", "cu", "r", "cu"] {
+        compare(&mut generic, &mut native, event, &["q", "cv"])?;
+    }
+
+    Ok(())
+}
+
+pub fn test_generic_voter() -> Result<(), Box<dyn Error>> {
+    let ty = GenericType::new(fbt::parse_fbt(VOTER_FBT)?)?;
+
+    let mut generic = ty.instantiate("generic_voter");
+    let mut native = Voter::new("native_voter");
+
+    print_deviations(&ty, &generic);
+
+    for inputs in [
+        [true, true, false],
+        [false, true, false],
+        [true, false, true],
+    ] {
+        for fb in [&mut generic as &mut dyn Bfb, &mut native] {
+            for (name, value) in ["a", "b", "c"].iter().zip(inputs) {
+                fb.write_data_in(name, &DataBuffer::Bool(value))?;
+            }
+        }
+
+        compare(&mut generic, &mut native, "vote", &["state"])?;
+        compare(&mut generic, &mut native, "reset", &["state"])?;
+    }
+
+    Ok(())
+}
+
+/// the interpreted `E_CTU` is registered in place of the Rust implementation
+pub fn test_generic_system() -> Result<(), Box<dyn Error>> {
+    let mut registry = fb_impl::builtin_registry();
+    GenericType::new(fbt::parse_fbt(E_CTU_FBT)?)?.register(&mut registry);
+
+    let mut system = SystemRuntime::load_str(COUNTER_SYS, &registry)?;
+    system.run(100)?;
+
+    for resource in &system.resources {
+        println!("{}", resource.runtime);
+    }
+
+    Ok(())
+}
+
+/// the event that armed the latch must not fire it as well
+pub fn test_generic_ecc() -> Result<(), Box<dyn Error>> {
+    let ty = GenericType::new(fbt::parse_fbt(E_LATCH_FBT)?)?;
+    let mut latch = ty.instantiate("latch");
+
+    latch.write_data_in("arm", &DataBuffer::Bool(true))?;
+
+    for expected in [("ARMED", vec![]), ("ARMED", vec!["eo".to_string()])] {
+        let sent = fire(&mut latch, "ev")?;
+        let state = latch.ec_state().unwrap_or_default();
+
+        println!(
+            "   ev -> {sent:?} {state} [{}]",
+            if (state, &sent) == (expected.0, &expected.1) {
+                "ok"
+            } else {
+                "MISMATCH"
+            }
+        );
+    }
+
+    Ok(())
+}
+
+fn print_deviations(ty: &Arc<GenericType>, fb: &dyn Bfb) {
+    let deviations = desc::check_conformance(&ty.desc().interface, fb);
+
+    if deviations.is_empty() {
+        println!("{} conforms to {}", fb.instance_name(), ty.desc().name);
+    }
+
+    for deviation in deviations {
+        println!("[deviation {}]: {deviation}", fb.instance_name());
+    }
+}
+
+/// delivers the event to both function blocks, runs them until they are stable
+/// and compares the sent output events and data outputs
+fn compare(
+    generic: &mut dyn Bfb,
+    native: &mut dyn Bfb,
+    event: &str,
+    outputs: &[&str],
+) -> Result<(), Box<dyn Error>> {
+    let generic_events = fire(generic, event)?;
+    let native_events = fire(native, event)?;
+
+    let mut same = generic_events == native_events;
+    let mut values = vec![];
+
+    for output in outputs {
+        let generic_value = generic.read_data_out(output)?;
+        let native_value = native.read_data_out(output)?;
+
+        same &= generic_value.to_string() == native_value.to_string();
+        values.push(format!("{output}={generic_value}"));
+    }
+
+    println!(
+        "{event:>5} -> {generic_events:?} {} [{}]",
+        values.join(", "),
+        if same { "ok" } else { "MISMATCH" }
+    );
+
+    Ok(())
+}
+
+fn fire(fb: &mut dyn Bfb, event: &str) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut sent = vec![];
+
+    fb.set_event_in(event)?;
+
+    while fb.invoke_execution_control()? {
+        sent.extend(fb.active_event_out().map(str::to_string));
+        fb.clear_event_out();
+    }
+
+    Ok(sent)
+}
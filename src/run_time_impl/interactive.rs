@@ -2,14 +2,16 @@
 
 use std::io::Write;
 
-use crate::fb_impl::voter::typed;
+use crate::{fb::data::comm::DataBuffer, fb_impl::voter::typed};
 
 pub fn simple_typed_runtime() {
     let mut voter = typed::Voter::default();
 
     loop {
         println!("{voter}");
-        println!("commands: quit, run, step, rs <signal_name>, tid <input_data_name>");
+        println!(
+            "commands: quit, run, step, rs <signal_name>, tid <input_data_name>, wid <input_data_name> <literal>"
+        );
         print!("> ");
 
         std::io::stdout().flush().unwrap();
@@ -46,6 +48,14 @@ pub fn simple_typed_runtime() {
                     voter.toggle_input_data(data);
                 }
             }
+            "wid" => {
+                if let (Some(data), Some(literal)) = (cmd.next(), cmd.next()) {
+                    match literal.parse::<DataBuffer>() {
+                        Ok(buf) => voter.write_input_data(data, &buf),
+                        Err(e) => println!("{e}"),
+                    }
+                }
+            }
             "quit" => {
                 break;
             }
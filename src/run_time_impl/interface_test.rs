@@ -0,0 +1,75 @@
+//! Function blocks describing their own interface (see `Bfb::interface`):
+//! - derived, interpreted and composite blocks are rendered without knowing their port names
+//! - every reported interface conforms to the block it was reported by
+//! - the derived `E_CTU` reports the same ports as the interpreted `E_CTU.fbt`
+//! - unknown events are reported as unknown, even while another input event is pending
+
+use std::error::Error;
+
+use crate::{
+    fb::{
+        Bfb,
+        data::ty::DataKind,
+        desc::{self, EventDesc, InterfaceDesc, VarDesc},
+        error::FbError,
+    },
+    fb_impl::{
+        composite::Cfb, event::ctu::E_CTU, generic::GenericType, voter::dynamic_disp::Voter,
+    },
+    run_time_impl::check,
+    xml::fbt,
+};
+
+const E_CTU_FBT: &str = include_str!("../../types/E_CTU.fbt");
+const VOTER_FBT: &str = include_str!("../../types/VOTER.fbt");
+
+pub fn test_interfaces() -> Result<(), Box<dyn Error>> {
+    let generic_ctu = GenericType::new(fbt::parse_fbt(E_CTU_FBT)?)?.instantiate("generic_ctu");
+    let generic_voter = GenericType::new(fbt::parse_fbt(VOTER_FBT)?)?.instantiate("generic_voter");
+
+    let mut fbs: [Box<dyn Bfb>; 5] = [
+        Box::new(E_CTU::new("ctu0")),
+        Box::new(Voter::new("voter0")),
+        Box::new(generic_ctu),
+        Box::new(generic_voter),
+        Box::new(relay("relay0")),
+    ];
+
+    for fb in &fbs {
+        let interface = fb.interface();
+        println!("{interface}");
+
+        check(
+            &format!("{}: reports its own ports", fb.type_name()),
+            || desc::check_conformance(&interface.interface, fb.as_ref()).is_empty(),
+        )?;
+    }
+
+    check("E_CTU: derived and interpreted ports match", || {
+        desc::check_conformance(&fbs[2].interface().interface, fbs[0].as_ref()).is_empty()
+    })?;
+
+    for fb in &mut fbs {
+        let event = fb.event_inputs()[0].to_string();
+        fb.set_event_in(&event)?;
+
+        check(
+            &format!("{}: unknown event while {event} is pending", fb.type_name()),
+            || matches!(fb.set_event_in("unknown"), Err(FbError::UnknownEvent(_))),
+        )?;
+    }
+
+    Ok(())
+}
+
+/// composite without components, its interface is declared explicitly
+fn relay(instance_name: &str) -> Cfb {
+    let interface = InterfaceDesc {
+        event_inputs: vec![EventDesc::new("REQ", &["IN"])],
+        event_outputs: vec![EventDesc::new("CNF", &[])],
+        input_vars: vec![VarDesc::new("IN", DataKind::Int)],
+        output_vars: vec![],
+    };
+
+    Cfb::new(instance_name, "RELAY", interface)
+}
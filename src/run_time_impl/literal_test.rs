@@ -0,0 +1,123 @@
+//! `IEC 61131-3` literals as text representation of `DataBuffer`s:
+//! - literals are parsed with `FromStr`, their kind is taken from the type prefix or the notation
+//! - `Display` writes typed literals, which are parsed back to the same value, arrays included
+//! - malformed literals (invalid dates, out of range values, ...) are rejected
+
+use std::{error::Error, time::Duration};
+
+use crate::{
+    fb::data::{
+        calendar::{Date, DateAndTime, TimeOfDay},
+        comm::DataBuffer,
+        ty::DataKind,
+    },
+    run_time_impl::check,
+};
+
+pub fn test_literals() -> Result<(), Box<dyn Error>> {
+    let date = Date::new(2024, 1, 31).ok_or("invalid date")?;
+    let tod = TimeOfDay::new(8, 30, 15, 250_000_000).ok_or("invalid time of day")?;
+
+    let parsed = [
+        ("UINT#100", DataBuffer::UInt(100)),
+        ("SINT#-128", DataBuffer::SInt(-128)),
+        ("16#FF", DataBuffer::DInt(255)),
+        ("2#1010", DataBuffer::DInt(10)),
+        ("1_000_000", DataBuffer::DInt(1_000_000)),
+        ("5_000_000_000", DataBuffer::LInt(5_000_000_000)),
+        ("BYTE#2#1010_1010", DataBuffer::Byte(0xaa)),
+        ("WORD#16#BEEF", DataBuffer::Word(0xbeef)),
+        ("REAL#1.5", DataBuffer::Real(1.5)),
+        ("-2.5e3", DataBuffer::LReal(-2500.0)),
+        ("TRUE", DataBuffer::Bool(true)),
+        ("bool#0", DataBuffer::Bool(false)),
+        ("T#1h2m3s", DataBuffer::Time(Duration::from_secs(3723))),
+        ("TIME#1.5s", DataBuffer::Time(Duration::from_millis(1500))),
+        ("T#250ms", DataBuffer::Time(Duration::from_millis(250))),
+        ("LT#1d", DataBuffer::LTime(Duration::from_secs(86_400))),
+        ("D#2024-01-31", DataBuffer::Date(date)),
+        ("LDATE#2024-01-31", DataBuffer::LDate(date)),
+        ("TOD#08:30:15.250", DataBuffer::TimeOfDay(tod)),
+        ("LTOD#08:30:15.25", DataBuffer::LTimeOfDay(tod)),
+        (
+            "DT#2024-01-31-08:30:15.250",
+            DataBuffer::DateTime(DateAndTime::new(date, tod).ok_or("invalid DT")?),
+        ),
+        (
+            "LDT#1970-01-01-00:00:00",
+            DataBuffer::LDateTime(DateAndTime::default()),
+        ),
+        ("'it$'s'", DataBuffer::String(b"it's".to_vec())),
+        ("'$41$N'", DataBuffer::String(b"A\n".to_vec())),
+        (
+            "\"wide $\"text$\" \u{00e4}\"",
+            DataBuffer::WString("wide \"text\" \u{00e4}".encode_utf16().collect()),
+        ),
+    ];
+
+    for (literal, expected) in parsed {
+        let buf: DataBuffer = literal.parse()?;
+        check(&format!("{literal} is parsed as {expected}"), || {
+            buf == expected
+        })?;
+
+        let text = buf.to_string();
+        check(&format!("{text} is parsed back to the same value"), || {
+            text.parse::<DataBuffer>().ok() == Some(buf)
+        })?;
+    }
+
+    // arrays without a declared kind are `ARRAY [0..n-1]`, other bounds are read back with their kind
+    for (literal, declaration) in [
+        ("[INT#1, 2(INT#0)]", "ARRAY [0..2] OF INT"),
+        ("[TRUE, 5(FALSE)]", "ARRAY [1..2, 1..3] OF BOOL"),
+    ] {
+        let kind = DataKind::from_iec_name(declaration).ok_or("invalid declaration")?;
+        let buf = DataBuffer::parse_literal(&kind, literal).ok_or("invalid array literal")?;
+        let text = buf.to_string();
+
+        check(&format!("{text} is parsed back as {declaration}"), || {
+            DataBuffer::parse_literal(&kind, &text) == Some(buf.clone())
+        })?;
+
+        if declaration.starts_with("ARRAY [0..") {
+            check(&format!("{literal} is parsed as {declaration}"), || {
+                literal.parse::<DataBuffer>().ok() == Some(buf.clone())
+                    && text.parse::<DataBuffer>().ok() == Some(buf.clone())
+            })?;
+        }
+    }
+
+    let invalid = [
+        "USINT#256",
+        "UINT#-1",
+        "D#2023-02-29",
+        "TOD#24:00:00",
+        "DT#2024-01-31",
+        "LDT#2024-13-01-00:00:00",
+        "T#5x",
+        "'unterminated",
+        "'it's'",
+        "BOOL#2",
+        "E_CTU#3",
+        "99#1",
+        "1#0",
+        "16#-1",
+        "BYTE#16#+1",
+        "REAL#1e40",
+        "LREAL#1e400",
+        "REAL#NaN",
+        "inf",
+        "[INT#1, UINT#2]",
+        "[]",
+    ];
+
+    for literal in invalid {
+        match literal.parse::<DataBuffer>() {
+            Err(e) => println!("[expected error]: {e}"),
+            Ok(buf) => return Err(format!("[failed] {literal} is parsed as {buf}").into()),
+        }
+    }
+
+    Ok(())
+}
@@ -1,3 +1,33 @@
+pub mod adapter_test;
+pub mod array_test;
+pub mod calendar_test;
+pub mod cfb_test;
 pub mod conn_test;
+pub mod convert_test;
+pub mod device_test;
 pub mod e_control_test;
+pub mod fbt_test;
+pub mod generic_port_test;
+pub mod generic_test;
 pub mod interactive;
+pub mod interface_test;
+pub mod literal_test;
+pub mod registry_test;
+pub mod sched_test;
+pub mod st_test;
+pub mod sys_test;
+pub mod timer_test;
+pub mod validate_test;
+pub mod widening_test;
+pub mod with_test;
+
+/// prints the label of a passed check, a failed check ends the test mode with an error
+pub fn check(label: &str, ok: impl FnOnce() -> bool) -> Result<(), Box<dyn std::error::Error>> {
+    if !ok() {
+        return Err(format!("[failed] {label}").into());
+    }
+
+    println!("[ok] {label}");
+
+    Ok(())
+}
@@ -0,0 +1,80 @@
+//! Creating function blocks by type name (see `fb::registry`):
+//! - the builtin registry lists every type implemented in this crate
+//! - `COUNTER` (see `cfb_test`) is registered as a user type next to the builtin types
+//! - the network is created and connected from strings only
+
+use std::error::Error;
+
+use crate::{
+    fb::data::comm::DataBuffer,
+    fb_impl,
+    run_time::id_conn::IdConnRuntime,
+    run_time_impl::{cfb_test, check},
+};
+
+const MAX_CYCLES: usize = 100;
+
+pub fn test_registry() -> Result<(), Box<dyn Error>> {
+    // SETUP --------------------------------------------------------------------------------------
+    let mut registry = fb_impl::builtin_registry();
+
+    registry.register("COUNTER", |name| {
+        Box::new(cfb_test::counter(name).expect("COUNTER is valid"))
+    });
+
+    // conversion function blocks (`F_*_TO_*`) are left out, there is one for every pair of types
+    let type_names: Vec<_> = registry
+        .type_names()
+        .into_iter()
+        .filter(|name| !name.starts_with("F_"))
+        .collect();
+
+    println!("types: {}", type_names.join(", "));
+
+    if let Some(interface) = registry.interface("counter") {
+        println!("{interface}");
+    }
+
+    let instances = [("COUNTER", "cnt0"), ("e_sr", "sr0")];
+    let events = [("cnt0", "done", "sr0", "s")];
+
+    let mut rt = IdConnRuntime::default();
+
+    for (type_name, instance_name) in instances {
+        rt.create_fb(&registry, type_name, instance_name)?;
+    }
+
+    for (from_fb, from_event, to_fb, to_event) in events {
+        rt.connect_event((from_fb, from_event), (to_fb, to_event));
+    }
+
+    // TESTING ------------------------------------------------------------------------------------
+    check("REGISTRY: type names ignore the case", || {
+        registry.contains("e_ctu") && registry.contains("Counter")
+    })?;
+    check("REGISTRY: instances report their type", || {
+        rt.fbs()["cnt0"].type_name() == "COUNTER" && rt.fbs()["sr0"].type_name() == "E_SR"
+    })?;
+
+    rt.fbs_mut()
+        .get_mut("cnt0")
+        .unwrap()
+        .write_data_in("pv", &DataBuffer::UInt(2))?;
+
+    for _ in 0..2 {
+        rt.fbs_mut().get_mut("cnt0").unwrap().set_event_in("cu")?;
+        rt.run(MAX_CYCLES)?;
+    }
+
+    println!("{rt}");
+    check("REGISTRY: created blocks run like constructed ones", || {
+        rt.fbs()["sr0"].read_data_out("q").ok() == Some(DataBuffer::Bool(true))
+    })?;
+
+    match rt.create_fb(&registry, "E_LATCH", "latch0") {
+        Err(e) => println!("[expected error]: {e}"),
+        Ok(()) => return Err("[failed] REGISTRY: unknown type is created".into()),
+    }
+
+    Ok(())
+}
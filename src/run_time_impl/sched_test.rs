@@ -0,0 +1,94 @@
+//! Event driven execution of the voter networks of `conn_test` through the `Scheduler`:
+//! - a sequential chain, where the voted events are forwarded without manual stepping
+//! - a fan-in, where three voters send their voted event to the same voter
+
+use crate::{
+    fb::{Bfb, data::comm::DataBuffer, error::FbError},
+    fb_impl::voter::dynamic_disp::Voter,
+    run_time::{id_conn::IdConnRuntime, scheduler::Scheduler},
+};
+
+const MAX_DELIVERIES: usize = 100;
+
+pub fn test_scheduler_seq_voter() -> Result<(), FbError> {
+    // SETUP --------------------------------------------------------------------------------------
+    let mut rt = IdConnRuntime::default();
+
+    add_voters(&mut rt)?;
+
+    rt.connect_event(("voter0", "voted"), ("voter1", "vote"));
+    rt.connect_event(("voter1", "voted"), ("voter2", "vote"));
+    rt.connect_event(("voter2", "voted"), ("voter3", "vote"));
+
+    rt.connect_data(("voter0", "state"), ("voter3", "a"))?;
+    rt.connect_data(("voter1", "state"), ("voter3", "b"))?;
+    rt.connect_data(("voter2", "state"), ("voter3", "c"))?;
+
+    rt.connect_event(("voter3", "voted"), ("voter0", "reset"));
+    rt.connect_event(("voter3", "voted"), ("voter1", "reset"));
+    rt.connect_event(("voter3", "voted"), ("voter2", "reset"));
+
+    // TESTING ------------------------------------------------------------------------------------
+    let mut scheduler = Scheduler::default();
+    scheduler.post("voter0", "vote");
+
+    let stable = scheduler.run(&mut rt, MAX_DELIVERIES)?;
+
+    println!("{rt}");
+    println!("[stable]: {stable}, [delivered]: {}", scheduler.delivered());
+
+    Ok(())
+}
+
+pub fn test_scheduler_par_voter() -> Result<(), FbError> {
+    // SETUP --------------------------------------------------------------------------------------
+    let mut rt = IdConnRuntime::default();
+
+    add_voters(&mut rt)?;
+
+    rt.connect_event(("voter0", "voted"), ("voter3", "vote"));
+    rt.connect_event(("voter1", "voted"), ("voter3", "vote"));
+    rt.connect_event(("voter2", "voted"), ("voter3", "vote"));
+
+    rt.connect_data(("voter0", "state"), ("voter3", "a"))?;
+    rt.connect_data(("voter1", "state"), ("voter3", "b"))?;
+    rt.connect_data(("voter2", "state"), ("voter3", "c"))?;
+
+    // TESTING ------------------------------------------------------------------------------------
+    let mut scheduler = Scheduler::default();
+    scheduler.post("voter0", "vote");
+    scheduler.post("voter1", "vote");
+    scheduler.post("voter2", "vote");
+
+    // voter0-2 vote first, afterwards all three voted events are delivered to voter3 one after another
+    // > the first vote of voter3 already samples the states of all three voters,
+    //   the later votes are discarded by its ECC, since voter3 waits for a reset
+    while scheduler.deliver_next(&mut rt)? {
+        if let Some(next) = scheduler.pending().front() {
+            println!("[next delivery]: {next}");
+        }
+    }
+
+    println!("{rt}");
+    println!("[delivered]: {}", scheduler.delivered());
+
+    Ok(())
+}
+
+/// voter0-2 vote positive once all states are sampled, voter3 starts with all inputs false
+fn add_voters(rt: &mut IdConnRuntime) -> Result<(), FbError> {
+    let t = DataBuffer::Bool(true);
+    let f = DataBuffer::Bool(false);
+
+    let inputs = [[&t, &t, &f], [&t, &f, &t], [&f, &t, &t], [&f, &f, &f]];
+
+    for (i, [a, b, c]) in inputs.into_iter().enumerate() {
+        let mut voter = Voter::new(&format!("voter{i}"));
+        voter.write_data_in("a", a)?;
+        voter.write_data_in("b", b)?;
+        voter.write_data_in("c", c)?;
+        rt.add_fb(voter);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,143 @@
+//! Executes Structured Text algorithms with the `st` interpreter:
+//! - `VoteAlg`/`ResetAlg` of `types/VOTER.fbt` on the `Voter` function block
+//! - a program using the supported control structures and standard functions on plain variables
+
+use std::{collections::HashMap, error::Error};
+
+use crate::{
+    fb::{Bfb, data::comm::DataBuffer, desc::FbBodyDesc},
+    fb_impl::voter::dynamic_disp::Voter,
+    st::{Program, Variables},
+    xml::fbt,
+};
+
+const VOTER_FBT: &str = include_str!("../../types/VOTER.fbt");
+
+const PROGRAM: &str = "
+    (* sum of all odd numbers below n *)
+    sum := 0;
+    FOR i := 1 TO n BY 2 DO
+        sum := sum + i;
+    END_FOR;
+
+    WHILE steps < 10 AND sum > 1 DO
+        sum := sum / 2;
+        steps := steps + 1;
+    END_WHILE;
+
+    CASE steps OF
+        0: level := 'none';
+        1..3: level := 'low';
+        4, 5: level := 'medium';
+    ELSE
+        level := 'high';
+    END_CASE;
+
+    IF MAX(n, 50) > 50 THEN
+        clamped := LIMIT(0, n, 50);
+    ELSIF n = 0 THEN
+        clamped := -1;
+    ELSE
+        clamped := SEL(n MOD 2 = 0, n - 1, n);
+    END_IF;
+";
+
+pub fn test_st_voter() -> Result<(), Box<dyn Error>> {
+    let ty = fbt::parse_fbt(VOTER_FBT)?;
+
+    let FbBodyDesc::Basic(basic) = &ty.body else {
+        return Err("VOTER is not a basic function block".into());
+    };
+
+    let algorithm = |name: &str| -> Result<Program, Box<dyn Error>> {
+        let alg = basic
+            .algorithms
+            .iter()
+            .find(|a| a.name == name)
+            .ok_or(format!("missing algorithm {name}"))?;
+
+        Ok(Program::parse(&alg.text)?)
+    };
+
+    let vote = algorithm("VoteAlg")?;
+    let reset = algorithm("ResetAlg")?;
+
+    let mut voter = Voter::new("voter0");
+
+    for inputs in 0..8u8 {
+        let [a, b, c] = [inputs & 1 != 0, inputs & 2 != 0, inputs & 4 != 0];
+
+        voter.write_data_in("a", &DataBuffer::Bool(a))?;
+        voter.write_data_in("b", &DataBuffer::Bool(b))?;
+        voter.write_data_in("c", &DataBuffer::Bool(c))?;
+
+        vote.execute(&mut voter)?;
+        let voted = voter.read_data_out("state")?;
+
+        reset.execute(&mut voter)?;
+        let after_reset = voter.read_data_out("state")?;
+
+        let expected = [a, b, c].iter().filter(|v| **v).count() >= 2;
+        let verdict = match voted {
+            DataBuffer::Bool(v) if v == expected => "ok",
+            _ => "MISMATCH",
+        };
+
+        println!(
+            "a={a:<5} b={b:<5} c={c:<5} -> VoteAlg: {voted}, ResetAlg: {after_reset} [{verdict}]"
+        );
+    }
+
+    Ok(())
+}
+
+pub fn test_st_program() -> Result<(), Box<dyn Error>> {
+    let program = Program::parse(PROGRAM)?;
+
+    for n in [0u16, 7, 20, 100] {
+        let mut vars: HashMap<String, DataBuffer> = HashMap::from([
+            ("n".to_string(), DataBuffer::UInt(n)),
+            ("i".to_string(), DataBuffer::UInt(0)),
+            ("sum".to_string(), DataBuffer::UDInt(0)),
+            ("steps".to_string(), DataBuffer::USInt(0)),
+            ("level".to_string(), DataBuffer::String(vec![])),
+            ("clamped".to_string(), DataBuffer::Int(0)),
+        ]);
+
+        program.execute(&mut vars)?;
+
+        println!(
+            "n={n}: sum={}, steps={}, level={}, clamped={}",
+            read(&vars, "sum"),
+            read(&vars, "steps"),
+            read(&vars, "level"),
+            read(&vars, "clamped"),
+        );
+    }
+
+    // errors report the line of the failing statement
+    let mut vars = HashMap::from([("x".to_string(), DataBuffer::USInt(200))]);
+
+    for text in [
+        "x := x + 1;\nx := x * 2;",
+        "x := x +;",
+        "x := TRUE;",
+        "x := 1#0;",
+        "x := 2 ** 200;",
+        "x := TRUE MOD 2;",
+    ] {
+        if let Err(e) = Program::parse(text).and_then(|p| p.execute(&mut vars)) {
+            println!("[expected error]: {e}");
+        }
+    }
+
+    Ok(())
+}
+
+fn read(vars: &dyn Variables, name: &str) -> String {
+    match vars.read(name) {
+        Some(DataBuffer::String(s)) => String::from_utf8_lossy(&s).into_owned(),
+        Some(buf) => buf.to_string(),
+        None => "-".to_string(),
+    }
+}
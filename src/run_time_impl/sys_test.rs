@@ -0,0 +1,55 @@
+//! Loads the system configuration in `systems/` (or a given `.sys` file) into the runtime and runs it:
+//! - `COUNTER`: `E_CTU` counting up to `PV` through an `E_SWITCH` loop, latching the result in an `E_SR`
+
+use std::path::Path;
+
+use crate::{fb_impl, run_time::config::SystemRuntime};
+
+const COUNTER_SYS: &str = include_str!("../../systems/COUNTER.sys");
+
+const MAX_DELIVERIES: usize = 100;
+
+pub fn test_load_system(path: Option<&Path>) -> Result<(), Box<dyn std::error::Error>> {
+    let registry = fb_impl::builtin_registry();
+
+    let mut system = match path {
+        Some(path) => SystemRuntime::load_file(path, &registry)?,
+        None => SystemRuntime::load_str(COUNTER_SYS, &registry)?,
+    };
+
+    let stable = system.run(MAX_DELIVERIES)?;
+
+    for resource in &system.resources {
+        println!("{}.{}.{}:", system.name, resource.device, resource.name);
+        println!("{}", resource.runtime);
+    }
+
+    if !stable {
+        println!("[warning]: system did not become stable within {MAX_DELIVERIES} deliveries");
+    }
+
+    if path.is_none() {
+        print_expected_errors();
+    }
+
+    Ok(())
+}
+
+/// errors are reported with their location inside of the configuration
+fn print_expected_errors() {
+    let registry = fb_impl::builtin_registry();
+
+    let broken = [
+        COUNTER_SYS.replace("Type=\"E_SR\"", "Type=\"E_LATCH\""),
+        COUNTER_SYS.replace("switch0.EO1", "switch0.EO2"),
+        COUNTER_SYS.replace("Destination=\"sr0.S\"", "Destination=\"sr0.EO\""),
+        COUNTER_SYS.replace("ctu0.Q", "ctu0.CV"),
+        COUNTER_SYS.replace("UINT#3", "-3"),
+    ];
+
+    for text in broken {
+        if let Err(e) = SystemRuntime::load_str(&text, &registry) {
+            println!("[expected error]: {e}");
+        }
+    }
+}
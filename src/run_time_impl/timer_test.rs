@@ -0,0 +1,167 @@
+//! Deterministic tests of the timed event function blocks on a `SimClock`:
+//! - `E_RESTART` starts an `E_DELAY`, whose `eo` sets an `E_SR`
+//! - `E_CYCLE` drives an `E_CTU` until it is stopped, and never becomes stable without a period
+//! - `E_TRAIN` emits a fixed number of events
+
+use std::{error::Error, sync::Arc, time::Duration};
+
+use crate::{
+    fb::{Bfb, data::comm::DataBuffer, error::FbError},
+    fb_impl::event::{
+        ctu::E_CTU, cycle::E_CYCLE, delay::E_DELAY, restart::E_RESTART, sr::E_SR, train::E_TRAIN,
+    },
+    run_time::{
+        clock::{Clock, SimClock},
+        id_conn::IdConnRuntime,
+        scheduler::Scheduler,
+    },
+    run_time_impl::check,
+};
+
+const MAX_DELIVERIES: usize = 100;
+
+pub fn test_timer_delay() -> Result<(), Box<dyn Error>> {
+    // SETUP --------------------------------------------------------------------------------------
+    let clock = Arc::new(SimClock::default());
+    let mut rt = IdConnRuntime::default();
+
+    let mut delay = E_DELAY::new("delay0");
+    delay.write_data_in("dt", &DataBuffer::Time(Duration::from_millis(500)))?;
+
+    rt.add_fb(E_RESTART::new("restart0"));
+    rt.add_fb(delay);
+    rt.add_fb(E_SR::new("sr0"));
+
+    rt.connect_event(("restart0", "cold"), ("delay0", "start"));
+    rt.connect_event(("delay0", "eo"), ("sr0", "s"));
+
+    rt.set_clock(clock.clone());
+
+    // TESTING ------------------------------------------------------------------------------------
+    let mut scheduler = Scheduler::default();
+
+    scheduler.advance(&mut rt, &clock, Duration::from_millis(499), MAX_DELIVERIES)?;
+    check_at(&clock, "E_DELAY: eo is not emitted before dt", || {
+        output(&rt, "sr0", "q") == DataBuffer::Bool(false)
+    })?;
+
+    scheduler.advance(&mut rt, &clock, Duration::from_millis(1), MAX_DELIVERIES)?;
+    check_at(&clock, "E_DELAY: eo is emitted after dt", || {
+        output(&rt, "sr0", "q") == DataBuffer::Bool(true)
+    })?;
+
+    scheduler.post("sr0", "r");
+    scheduler.post("delay0", "start");
+    scheduler.advance(&mut rt, &clock, Duration::from_millis(100), MAX_DELIVERIES)?;
+    scheduler.post("delay0", "stop");
+    scheduler.advance(&mut rt, &clock, Duration::from_secs(1), MAX_DELIVERIES)?;
+    check_at(&clock, "E_DELAY: stop cancels the delay", || {
+        output(&rt, "sr0", "q") == DataBuffer::Bool(false)
+            && rt.fbs()["delay0"].deadline().is_none()
+    })
+}
+
+pub fn test_timer_cycle() -> Result<(), Box<dyn Error>> {
+    // SETUP --------------------------------------------------------------------------------------
+    let clock = Arc::new(SimClock::default());
+    let mut rt = IdConnRuntime::default();
+
+    let mut cycle = E_CYCLE::new("cycle0");
+    cycle.write_data_in("dt", &DataBuffer::Time(Duration::from_millis(100)))?;
+
+    rt.add_fb(cycle);
+    rt.add_fb(E_CTU::new("ctu0"));
+
+    rt.connect_event(("cycle0", "eo"), ("ctu0", "cu"));
+
+    rt.set_clock(clock.clone());
+
+    // TESTING ------------------------------------------------------------------------------------
+    let mut scheduler = Scheduler::default();
+    scheduler.post("cycle0", "start");
+
+    scheduler.advance(&mut rt, &clock, Duration::from_millis(1050), MAX_DELIVERIES)?;
+    check_at(&clock, "E_CYCLE: eo is emitted every dt", || {
+        output(&rt, "ctu0", "cv") == DataBuffer::UInt(10)
+    })?;
+
+    scheduler.post("cycle0", "start");
+    scheduler.advance(&mut rt, &clock, Duration::from_millis(50), MAX_DELIVERIES)?;
+    check_at(
+        &clock,
+        "E_CYCLE: start does not restart a running cycle",
+        || output(&rt, "ctu0", "cv") == DataBuffer::UInt(11),
+    )?;
+
+    scheduler.post("cycle0", "stop");
+    scheduler.advance(&mut rt, &clock, Duration::from_secs(1), MAX_DELIVERIES)?;
+    check_at(&clock, "E_CYCLE: stop ends the cycle", || {
+        output(&rt, "ctu0", "cv") == DataBuffer::UInt(11)
+    })?;
+
+    // a cycle without a period expires forever without the time advancing
+    rt.fbs_mut()
+        .get_mut("cycle0")
+        .unwrap()
+        .write_data_in("dt", &DataBuffer::Time(Duration::ZERO))?;
+
+    scheduler.post("cycle0", "start");
+    let result = scheduler.advance(&mut rt, &clock, Duration::from_secs(1), MAX_DELIVERIES);
+    check_at(
+        &clock,
+        "E_CYCLE: a cycle with dt=T#0s is not stable",
+        || matches!(result, Err(FbError::NotStable { .. })),
+    )
+}
+
+pub fn test_timer_train() -> Result<(), Box<dyn Error>> {
+    // SETUP --------------------------------------------------------------------------------------
+    let clock = Arc::new(SimClock::default());
+    let mut rt = IdConnRuntime::default();
+
+    let mut train = E_TRAIN::new("train0");
+    train.write_data_in("dt", &DataBuffer::Time(Duration::from_millis(50)))?;
+    train.write_data_in("n", &DataBuffer::UInt(3))?;
+
+    let mut ctu = E_CTU::new("ctu0");
+    ctu.write_data_in("pv", &DataBuffer::UInt(100))?;
+
+    rt.add_fb(train);
+    rt.add_fb(ctu);
+
+    rt.connect_event(("train0", "eo"), ("ctu0", "cu"));
+    rt.connect_data(("train0", "cv"), ("ctu0", "pv"))?;
+
+    rt.set_clock(clock.clone());
+
+    // TESTING ------------------------------------------------------------------------------------
+    let mut scheduler = Scheduler::default();
+    scheduler.post("train0", "start");
+
+    scheduler.advance(&mut rt, &clock, Duration::from_millis(100), MAX_DELIVERIES)?;
+    check_at(&clock, "E_TRAIN: eo is emitted every dt", || {
+        output(&rt, "ctu0", "cv") == DataBuffer::UInt(2)
+    })?;
+
+    scheduler.advance(&mut rt, &clock, Duration::from_secs(1), MAX_DELIVERIES)?;
+    check_at(&clock, "E_TRAIN: eo is emitted n times", || {
+        output(&rt, "ctu0", "cv") == DataBuffer::UInt(3)
+    })?;
+    check_at(&clock, "E_TRAIN: cv is sent WITH eo", || {
+        output(&rt, "ctu0", "q") == DataBuffer::Bool(true)
+    })
+}
+
+fn check_at(
+    clock: &SimClock,
+    label: &str,
+    ok: impl FnOnce() -> bool,
+) -> Result<(), Box<dyn Error>> {
+    check(&format!("{:?}: {label}", clock.now()), ok)
+}
+
+fn output(rt: &IdConnRuntime, fb_name: &str, data: &str) -> DataBuffer {
+    rt.fbs()[fb_name]
+        .read_data_out(data)
+        .unwrap_or(DataBuffer::Unassigned)
+}
@@ -0,0 +1,117 @@
+//! Static validation of function block networks (see `run_time::validate`):
+//! - the `COUNTER` system only raises warnings (`PV` is a parameter, the counting loop is a cycle)
+//! - a network with one mistake of every kind reports each of them
+
+use std::error::Error;
+
+use crate::{
+    fb::error::FbError,
+    fb_impl::{
+        self,
+        event::{ctu::E_CTU, sr::E_SR, switch::E_SWITCH},
+    },
+    run_time::{
+        config::SystemRuntime,
+        id_conn::IdConnRuntime,
+        validate::{Diagnostic, Severity},
+    },
+    run_time_impl::check,
+};
+
+const COUNTER_SYS: &str = include_str!("../../systems/COUNTER.sys");
+
+pub fn test_validate_system() -> Result<(), Box<dyn Error>> {
+    let system = SystemRuntime::load_str(COUNTER_SYS, &fb_impl::builtin_registry())?;
+
+    for resource in &system.resources {
+        let diagnostics = resource.runtime.validate();
+        print_diagnostics(&diagnostics);
+
+        check("COUNTER: has no errors", || {
+            diagnostics
+                .iter()
+                .all(|d| d.severity() == Severity::Warning)
+        })?;
+        check("COUNTER: the counting loop is a cycle", || {
+            diagnostics
+                .iter()
+                .any(|d| matches!(d, Diagnostic::EventCycle(fbs) if fbs == &["ctu0", "switch0"]))
+        })?;
+    }
+
+    Ok(())
+}
+
+pub fn test_validate_network() -> Result<(), Box<dyn Error>> {
+    // SETUP --------------------------------------------------------------------------------------
+    let mut rt = IdConnRuntime::default();
+
+    rt.add_fb(E_CTU::new("ctu0"));
+    rt.add_fb(E_CTU::new("ctu1"));
+    rt.add_fb(E_SWITCH::new("switch0"));
+    rt.add_fb(E_SR::new("sr0"));
+
+    // event input used as source
+    rt.connect_event(("sr0", "s"), ("ctu1", "r"));
+    // unknown event
+    rt.connect_event(("ctu0", "cuo"), ("sr0", "set"));
+    // loop without a data connection for ctu0.pv
+    rt.connect_event(("ctu0", "cuo"), ("switch0", "ei"));
+    rt.connect_event(("switch0", "eo0"), ("ctu0", "cu"));
+    // two drivers of switch0.g
+    rt.connect_data(("ctu0", "q"), ("switch0", "g"))?;
+    rt.connect_data(("sr0", "q"), ("switch0", "g"))?;
+    // BOOL to UINT is rejected when connecting
+    let mismatch = rt.connect_data(("sr0", "q"), ("ctu1", "pv"));
+    // so are data inputs as sources and unknown instances
+    let reversed = rt.connect_data(("switch0", "g"), ("ctu1", "pv"));
+    let unknown = rt.connect_data(("ctu9", "q"), ("switch0", "g"));
+
+    // TESTING ------------------------------------------------------------------------------------
+    let diagnostics = rt.validate();
+    print_diagnostics(&diagnostics);
+
+    check("NETWORK: event input used as source", || {
+        diagnostics.iter().any(|d| {
+            matches!(d, Diagnostic::WrongDirection { port, input: false } if port.to_string() == "sr0.s")
+        })
+    })?;
+    check("NETWORK: unknown event", || {
+        diagnostics.iter().any(
+            |d| matches!(d, Diagnostic::UnknownPort { port, .. } if port.to_string() == "sr0.set"),
+        )
+    })?;
+    check("NETWORK: data kind mismatch", || {
+        matches!(mismatch, Err(FbError::KindMismatch { .. }))
+            && diagnostics
+                .iter()
+                .all(|d| !matches!(d, Diagnostic::KindMismatch { .. }))
+    })?;
+    check("NETWORK: data input used as source", || {
+        matches!(reversed, Err(FbError::WrongDirection(_)))
+    })?;
+    check("NETWORK: unknown instance", || {
+        matches!(unknown, Err(FbError::UnknownInstance(_)))
+    })?;
+    check("NETWORK: multiple drivers", || {
+        diagnostics.iter().any(|d| {
+            matches!(d, Diagnostic::MultipleDrivers { port, sources } if port.to_string() == "switch0.g" && sources.len() == 2)
+        })
+    })?;
+    check("NETWORK: unconnected input", || {
+        diagnostics.iter().any(|d| {
+            matches!(d, Diagnostic::UnconnectedInput { port, .. } if port.to_string() == "ctu0.pv")
+        })
+    })?;
+    check("NETWORK: event cycle", || {
+        diagnostics
+            .iter()
+            .any(|d| matches!(d, Diagnostic::EventCycle(_)))
+    })
+}
+
+fn print_diagnostics(diagnostics: &[Diagnostic]) {
+    for d in diagnostics {
+        println!("{d}");
+    }
+}
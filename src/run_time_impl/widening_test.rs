@@ -0,0 +1,132 @@
+//! Implicit widening on data connections (see `data::ty::widens_to`):
+//! - the lattice widens within `ANY_INT`, `ANY_REAL` and `ANY_BIT` and never narrows
+//! - the `UINT` counter value of `E_CTU` is connected to a `DINT` and an `LREAL` input and widened on transfer
+//! - connecting it to a `SINT` input is rejected by both runtimes
+
+use std::error::Error;
+
+use crate::{
+    fb::data::{
+        comm::DataBuffer,
+        ty::{self, DataKind},
+    },
+    fb_impl::{self, convert::Conversion, event::ctu::E_CTU},
+    run_time::{id_conn::IdConnRuntime, rc_conn::RcConnRuntime, validate::Diagnostic},
+    run_time_impl::check,
+};
+
+const MAX_CYCLES: usize = 100;
+
+pub fn test_widening() -> Result<(), Box<dyn Error>> {
+    let widening = [
+        (DataKind::SInt, DataKind::LInt),
+        (DataKind::USInt, DataKind::Int),
+        (DataKind::UInt, DataKind::DInt),
+        (DataKind::Int, DataKind::Real),
+        (DataKind::DInt, DataKind::LReal),
+        (DataKind::Real, DataKind::LReal),
+        (DataKind::Byte, DataKind::LWord),
+        (DataKind::Time, DataKind::LTime),
+    ];
+    let rejected = [
+        (DataKind::DInt, DataKind::Int),
+        (DataKind::UInt, DataKind::SInt),
+        (DataKind::Int, DataKind::UDInt),
+        (DataKind::DInt, DataKind::Real),
+        (DataKind::LReal, DataKind::Real),
+        (DataKind::Word, DataKind::Int),
+        (DataKind::LTime, DataKind::Time),
+    ];
+
+    for (from, to) in &widening {
+        check(
+            &format!("{} is widened to {}", from.iec_name(), to.iec_name()),
+            || ty::widens_to(from, to),
+        )?;
+    }
+
+    for (from, to) in &rejected {
+        check(
+            &format!("{} is not widened to {}", from.iec_name(), to.iec_name()),
+            || !ty::assignable(from, to),
+        )?;
+    }
+
+    test_widening_network()?;
+    test_narrowing_network()
+}
+
+fn test_widening_network() -> Result<(), Box<dyn Error>> {
+    // SETUP --------------------------------------------------------------------------------------
+    let registry = fb_impl::builtin_registry();
+    let mut rt = IdConnRuntime::default();
+
+    rt.create_fb(&registry, "E_CTU", "ctu0")?;
+    rt.create_fb(&registry, "F_DINT_TO_STRING", "dint0")?;
+    rt.create_fb(&registry, "F_LREAL_TO_STRING", "lreal0")?;
+
+    for to in ["dint0", "lreal0"] {
+        rt.connect_event(("ctu0", "cuo"), (to, "req"));
+        rt.connect_data(("ctu0", "cv"), (to, "in"))?;
+    }
+
+    // TESTING ------------------------------------------------------------------------------------
+    check("NETWORK: widening connections are valid", || {
+        rt.validate()
+            .iter()
+            .all(|d| !matches!(d, Diagnostic::KindMismatch { .. }))
+    })?;
+
+    for _ in 0..2 {
+        rt.fbs_mut().get_mut("ctu0").unwrap().set_event_in("cu")?;
+        rt.run(MAX_CYCLES)?;
+    }
+
+    println!("{rt}");
+    check("NETWORK: UINT is widened to DINT", || {
+        rt.fbs()["dint0"].read_data_out("out").ok() == Some(DataBuffer::String(b"2".to_vec()))
+    })?;
+    check("NETWORK: UINT is widened to LREAL", || {
+        rt.fbs()["lreal0"].read_data_out("out").ok() == Some(DataBuffer::String(b"2.0".to_vec()))
+    })
+}
+
+fn test_narrowing_network() -> Result<(), Box<dyn Error>> {
+    let mut rt = IdConnRuntime::default();
+
+    rt.add_fb(E_CTU::new("ctu0"));
+    rt.add_fb(Conversion::new(
+        "sint0",
+        &DataKind::SInt,
+        &DataKind::String,
+    )?);
+
+    match rt.connect_data(("ctu0", "cv"), ("sint0", "in")) {
+        Err(e) => println!("[expected error]: {e}"),
+        Ok(()) => return Err("[failed] NETWORK: UINT is connected to SINT".into()),
+    }
+
+    check("NETWORK: UINT is not narrowed to SINT", || {
+        rt.data_conns().is_empty()
+    })?;
+
+    let mut rc_rt = RcConnRuntime::default();
+
+    rc_rt.add_fb(E_CTU::new("ctu0"));
+    rc_rt.add_fb(Conversion::new(
+        "dint0",
+        &DataKind::DInt,
+        &DataKind::String,
+    )?);
+    rc_rt.add_fb(Conversion::new(
+        "sint0",
+        &DataKind::SInt,
+        &DataKind::String,
+    )?);
+    rc_rt.connect_data((0, "cv"), (1, "in"))?;
+    rc_rt.connect_data((0, "cv"), (2, "in"))?;
+
+    check("RC: only the widening connection is created", || {
+        rc_rt.data_conns().len() == 1
+    })
+}
@@ -0,0 +1,319 @@
+//! Conformance of the WITH semantics (see `run_time::sampling`) in all runtimes:
+//! - only the data inputs associated WITH the delivered input event are sampled
+//! - data outputs are latched into their connections when an associated output event is emitted,
+//!   even if the output event itself is not connected
+//!
+//! Every check is run on the `IdConnRuntime`, the `RcConnRuntime` and the `Scheduler`,
+//! a failing check aborts the test with an error.
+
+use std::error::Error;
+
+use crate::{
+    fb::{Bfb, data::comm::DataBuffer, error::FbError},
+    fb_impl::{
+        event::{ctu::E_CTU, sr::E_SR, switch::E_SWITCH},
+        voter::dynamic_disp::Voter,
+    },
+    run_time::{id_conn::IdConnRuntime, rc_conn::RcConnRuntime, scheduler::Scheduler},
+    st::Variables,
+};
+
+const MAX_CYCLES: usize = 100;
+
+pub fn test_with_ctu() -> Result<(), Box<dyn Error>> {
+    ctu_suite(IdConnRuntime::default())?;
+    ctu_suite(RcConnRuntime::default())?;
+    ctu_suite(Scheduled::default())
+}
+
+pub fn test_with_switch() -> Result<(), Box<dyn Error>> {
+    switch_suite(IdConnRuntime::default())?;
+    switch_suite(RcConnRuntime::default())?;
+    switch_suite(Scheduled::default())
+}
+
+pub fn test_with_voter() -> Result<(), Box<dyn Error>> {
+    voter_suite(IdConnRuntime::default())?;
+    voter_suite(RcConnRuntime::default())?;
+    voter_suite(Scheduled::default())
+}
+
+/// `cu` is associated WITH `pv`, `r` is not associated with any data input
+fn ctu_suite(mut net: impl Network) -> Result<(), Box<dyn Error>> {
+    let mut src = E_CTU::new("src");
+    src.write_data_in("pv", &DataBuffer::UInt(10))?;
+
+    net.add(src);
+    net.add(E_CTU::new("dst"));
+
+    net.connect_data(("src", "cv"), ("dst", "pv"))?;
+    net.connect_event(("src", "cuo"), ("dst", "r"));
+
+    net.fire("src", "cu")?;
+    check(&net, "E_CTU: r does not sample pv", || {
+        input(&net, "dst", "pv") == DataBuffer::UInt(0)
+    })?;
+
+    net.connect_event(("src", "cuo"), ("dst", "cu"));
+
+    net.fire("src", "cu")?;
+    check(&net, "E_CTU: cu samples pv", || {
+        input(&net, "dst", "pv") == DataBuffer::UInt(2)
+    })?;
+    check(&net, "E_CTU: cu counts", || {
+        output(&net, "dst", "cv") == DataBuffer::UInt(1)
+    })?;
+
+    // the unconnected ro still latches cv into the connection
+    net.fire("src", "r")?;
+    net.fire("dst", "cu")?;
+    check(&net, "E_CTU: ro latches cv", || {
+        input(&net, "dst", "pv") == DataBuffer::UInt(0)
+            && output(&net, "dst", "q") == DataBuffer::Bool(true)
+    })
+}
+
+/// `ei` is associated WITH `g`, the voter latches `state` with `voted` and `ready`
+fn switch_suite(mut net: impl Network) -> Result<(), Box<dyn Error>> {
+    let mut voter = Voter::new("voter");
+    voter.write_data_in("a", &DataBuffer::Bool(true))?;
+    voter.write_data_in("b", &DataBuffer::Bool(true))?;
+
+    net.add(voter);
+    net.add(E_SWITCH::new("switch"));
+    net.add(E_SR::new("sr"));
+
+    net.connect_data(("voter", "state"), ("switch", "g"))?;
+    net.connect_event(("voter", "voted"), ("switch", "ei"));
+    net.connect_event(("switch", "eo0"), ("sr", "r"));
+    net.connect_event(("switch", "eo1"), ("sr", "s"));
+
+    net.fire("voter", "vote")?;
+    check(&net, "E_SWITCH: ei samples g", || {
+        input(&net, "switch", "g") == DataBuffer::Bool(true)
+    })?;
+    check(&net, "E_SWITCH: g = TRUE sends eo1", || {
+        output(&net, "sr", "q") == DataBuffer::Bool(true)
+    })?;
+
+    net.fire("voter", "reset")?;
+    check(&net, "E_SWITCH: g is only sampled with ei", || {
+        input(&net, "switch", "g") == DataBuffer::Bool(true)
+    })?;
+
+    net.fire("switch", "ei")?;
+    check(&net, "E_SWITCH: ready latches state", || {
+        input(&net, "switch", "g") == DataBuffer::Bool(false)
+    })?;
+    check(&net, "E_SWITCH: g = FALSE sends eo0", || {
+        output(&net, "sr", "q") == DataBuffer::Bool(false)
+    })
+}
+
+/// `vote` is associated WITH `a`, `b` and `c`, which are connected to three different voters
+fn voter_suite(mut net: impl Network) -> Result<(), Box<dyn Error>> {
+    let t = DataBuffer::Bool(true);
+    let f = DataBuffer::Bool(false);
+
+    let inputs = [[&t, &t, &f], [&t, &f, &t], [&f, &f, &f], [&f, &f, &f]];
+
+    for (i, [a, b, c]) in inputs.into_iter().enumerate() {
+        let mut voter = Voter::new(&format!("voter{i}"));
+        voter.write_data_in("a", a)?;
+        voter.write_data_in("b", b)?;
+        voter.write_data_in("c", c)?;
+        net.add(voter);
+    }
+
+    net.connect_data(("voter0", "state"), ("voter3", "a"))?;
+    net.connect_data(("voter1", "state"), ("voter3", "b"))?;
+    net.connect_data(("voter2", "state"), ("voter3", "c"))?;
+    net.connect_event(("voter0", "voted"), ("voter3", "vote"));
+
+    net.fire("voter1", "vote")?;
+    check(
+        &net,
+        "VOTER: inputs are not sampled without an event",
+        || input(&net, "voter3", "b") == f,
+    )?;
+
+    net.fire("voter0", "vote")?;
+    check(&net, "VOTER: vote samples a, b and c", || {
+        input(&net, "voter3", "a") == t
+            && input(&net, "voter3", "b") == t
+            && input(&net, "voter3", "c") == f
+    })?;
+    check(&net, "VOTER: majority of sampled inputs", || {
+        output(&net, "voter3", "state") == t
+    })?;
+
+    net.fire("voter1", "reset")?;
+    check(&net, "VOTER: reset of a source is not sampled", || {
+        input(&net, "voter3", "b") == t
+    })
+}
+
+fn check<N: Network>(
+    net: &N,
+    label: &str,
+    ok: impl FnOnce() -> bool,
+) -> Result<(), Box<dyn Error>> {
+    if !ok() {
+        return Err(format!("[failed] {}: {label}", net.label()).into());
+    }
+
+    println!("[ok] {}: {label}", net.label());
+
+    Ok(())
+}
+
+fn input(net: &impl Network, fb_name: &str, data: &str) -> DataBuffer {
+    net.inspect(fb_name, |fb| {
+        let any = fb.as_any();
+
+        let vars: &dyn Variables = if let Some(fb) = any.downcast_ref::<E_CTU>() {
+            fb
+        } else if let Some(fb) = any.downcast_ref::<E_SWITCH>() {
+            fb
+        } else if let Some(fb) = any.downcast_ref::<Voter>() {
+            fb
+        } else {
+            return DataBuffer::Unassigned;
+        };
+
+        vars.read(data).unwrap_or(DataBuffer::Unassigned)
+    })
+}
+
+fn output(net: &impl Network, fb_name: &str, data: &str) -> DataBuffer {
+    net.inspect(fb_name, |fb| {
+        fb.read_data_out(data).unwrap_or(DataBuffer::Unassigned)
+    })
+}
+
+/// the parts of a runtime the checks rely on
+trait Network {
+    fn label(&self) -> &'static str;
+
+    fn add<T: Bfb + 'static>(&mut self, fb: T);
+
+    fn connect_event(&mut self, from: (&str, &str), to: (&str, &str));
+
+    fn connect_data(&mut self, from: (&str, &str), to: (&str, &str)) -> Result<(), FbError>;
+
+    /// delivers an event from outside of the network and runs the network until it is stable
+    fn fire(&mut self, fb_name: &str, event: &str) -> Result<(), FbError>;
+
+    fn inspect<R>(&self, fb_name: &str, f: impl FnOnce(&dyn Bfb) -> R) -> R;
+}
+
+impl Network for IdConnRuntime {
+    fn label(&self) -> &'static str {
+        "id_conn"
+    }
+
+    fn add<T: Bfb + 'static>(&mut self, fb: T) {
+        self.add_fb(fb);
+    }
+
+    fn connect_event(&mut self, from: (&str, &str), to: (&str, &str)) {
+        IdConnRuntime::connect_event(self, from, to);
+    }
+
+    fn connect_data(&mut self, from: (&str, &str), to: (&str, &str)) -> Result<(), FbError> {
+        IdConnRuntime::connect_data(self, from, to)
+    }
+
+    fn fire(&mut self, fb_name: &str, event: &str) -> Result<(), FbError> {
+        self.fbs_mut()
+            .get_mut(fb_name)
+            .unwrap()
+            .set_event_in(event)?;
+        self.run(MAX_CYCLES)?;
+
+        Ok(())
+    }
+
+    fn inspect<R>(&self, fb_name: &str, f: impl FnOnce(&dyn Bfb) -> R) -> R {
+        f(self.fbs()[fb_name].as_ref())
+    }
+}
+
+impl Network for RcConnRuntime {
+    fn label(&self) -> &'static str {
+        "rc_conn"
+    }
+
+    fn add<T: Bfb + 'static>(&mut self, fb: T) {
+        self.add_fb(fb);
+    }
+
+    fn connect_event(&mut self, from: (&str, &str), to: (&str, &str)) {
+        let from = (index(self, from.0), from.1);
+        let to = (index(self, to.0), to.1);
+
+        RcConnRuntime::connect_event(self, from, to);
+    }
+
+    fn connect_data(&mut self, from: (&str, &str), to: (&str, &str)) -> Result<(), FbError> {
+        let from = (index(self, from.0), from.1);
+        let to = (index(self, to.0), to.1);
+
+        RcConnRuntime::connect_data(self, from, to)
+    }
+
+    fn fire(&mut self, fb_name: &str, event: &str) -> Result<(), FbError> {
+        self.fbs()[index(self, fb_name)]
+            .borrow_mut()
+            .set_event_in(event)?;
+        self.run(MAX_CYCLES)?;
+
+        Ok(())
+    }
+
+    fn inspect<R>(&self, fb_name: &str, f: impl FnOnce(&dyn Bfb) -> R) -> R {
+        f(&*self.fbs()[index(self, fb_name)].borrow())
+    }
+}
+
+fn index(rt: &RcConnRuntime, fb_name: &str) -> usize {
+    rt.fbs()
+        .iter()
+        .position(|fb| fb.borrow().instance_name() == fb_name)
+        .unwrap()
+}
+
+#[derive(Default)]
+struct Scheduled {
+    rt: IdConnRuntime,
+    scheduler: Scheduler,
+}
+
+impl Network for Scheduled {
+    fn label(&self) -> &'static str {
+        "scheduler"
+    }
+
+    fn add<T: Bfb + 'static>(&mut self, fb: T) {
+        self.rt.add_fb(fb);
+    }
+
+    fn connect_event(&mut self, from: (&str, &str), to: (&str, &str)) {
+        self.rt.connect_event(from, to);
+    }
+
+    fn connect_data(&mut self, from: (&str, &str), to: (&str, &str)) -> Result<(), FbError> {
+        self.rt.connect_data(from, to)
+    }
+
+    fn fire(&mut self, fb_name: &str, event: &str) -> Result<(), FbError> {
+        self.scheduler.post(fb_name, event);
+        self.scheduler.run(&mut self.rt, MAX_CYCLES)?;
+
+        Ok(())
+    }
+
+    fn inspect<R>(&self, fb_name: &str, f: impl FnOnce(&dyn Bfb) -> R) -> R {
+        self.rt.inspect(fb_name, f)
+    }
+}
@@ -0,0 +1,603 @@
+//! Execution of parsed statements and evaluation of expressions against `Variables`.
+
+use std::time::Duration;
+
+use crate::fb::data::{comm::DataBuffer, convert};
+
+use super::{
+    StError, Variables,
+    parser::{BinaryOp, CaseLabel, Expr, Statement, StatementKind, UnaryOp},
+    value::{Value, width},
+};
+
+/// upper bound of iterations per loop, protects the runtime from endless algorithms
+const MAX_ITERATIONS: usize = 100_000;
+
+/// how execution continues after a statement
+#[derive(PartialEq)]
+enum Flow {
+    Next,
+    Exit,
+    Return,
+}
+
+pub(crate) fn execute(statements: &[Statement], vars: &mut dyn Variables) -> Result<(), StError> {
+    block(statements, vars).map(|_| ())
+}
+
+fn block(statements: &[Statement], vars: &mut dyn Variables) -> Result<Flow, StError> {
+    for statement in statements {
+        let flow = self::statement(statement, vars)?;
+
+        if flow != Flow::Next {
+            return Ok(flow);
+        }
+    }
+
+    Ok(Flow::Next)
+}
+
+fn statement(statement: &Statement, vars: &mut dyn Variables) -> Result<Flow, StError> {
+    let at_line = |message: String| StError::new(statement.line, message);
+
+    match &statement.kind {
+        StatementKind::Assign {
+            target,
+            index,
+            value,
+        } if !index.is_empty() => {
+            let index = indices(index, vars).map_err(at_line)?;
+            let value = evaluate(value, vars).map_err(at_line)?;
+            assign_element(vars, target, &index, &value).map_err(at_line)?;
+        }
+        StatementKind::Assign { target, value, .. } => {
+            let value = evaluate(value, vars).map_err(at_line)?;
+            assign(vars, target, &value).map_err(at_line)?;
+        }
+        StatementKind::If {
+            branches,
+            otherwise,
+        } => {
+            for (condition, body) in branches {
+                if condition_value(condition, vars).map_err(at_line)? {
+                    return block(body, vars);
+                }
+            }
+
+            return block(otherwise, vars);
+        }
+        StatementKind::Case {
+            selector,
+            cases,
+            otherwise,
+        } => {
+            let selector = match evaluate(selector, vars).map_err(at_line)? {
+                Value::Int(v) => v,
+                Value::Bits(v, _) => v.into(),
+                other => {
+                    return Err(at_line(format!(
+                        "CASE selector needs to be an integer, found {}",
+                        other.type_name()
+                    )));
+                }
+            };
+
+            for (labels, body) in cases {
+                let matches = labels.iter().any(|label| match label {
+                    CaseLabel::Value(v) => *v == selector,
+                    CaseLabel::Range(from, to) => (*from..=*to).contains(&selector),
+                });
+
+                if matches {
+                    return block(body, vars);
+                }
+            }
+
+            return block(otherwise, vars);
+        }
+        StatementKind::For {
+            variable,
+            from,
+            to,
+            by,
+            body,
+        } => {
+            let integer = |expr: &Expr, vars: &dyn Variables| match evaluate(expr, vars)? {
+                Value::Int(v) => Ok(v),
+                other => Err(format!(
+                    "FOR bounds need to be integers, found {}",
+                    other.type_name()
+                )),
+            };
+
+            let from = integer(from, vars).map_err(at_line)?;
+            let to = integer(to, vars).map_err(at_line)?;
+            let by = match by {
+                Some(by) => integer(by, vars).map_err(at_line)?,
+                None => 1,
+            };
+
+            if by == 0 {
+                return Err(at_line("FOR increment must not be 0".to_string()));
+            }
+
+            let mut counter = from;
+            let mut iterations = 0;
+
+            while (by > 0 && counter <= to) || (by < 0 && counter >= to) {
+                check_iterations(&mut iterations).map_err(at_line)?;
+                assign(vars, variable, &Value::Int(counter)).map_err(at_line)?;
+
+                match block(body, vars)? {
+                    Flow::Next => {}
+                    Flow::Exit => break,
+                    Flow::Return => return Ok(Flow::Return),
+                }
+
+                counter += by;
+            }
+        }
+        StatementKind::While { condition, body } => {
+            let mut iterations = 0;
+
+            while condition_value(condition, vars).map_err(at_line)? {
+                check_iterations(&mut iterations).map_err(at_line)?;
+
+                match block(body, vars)? {
+                    Flow::Next => {}
+                    Flow::Exit => break,
+                    Flow::Return => return Ok(Flow::Return),
+                }
+            }
+        }
+        StatementKind::Repeat { body, until } => {
+            let mut iterations = 0;
+
+            loop {
+                check_iterations(&mut iterations).map_err(at_line)?;
+
+                match block(body, vars)? {
+                    Flow::Next => {}
+                    Flow::Exit => break,
+                    Flow::Return => return Ok(Flow::Return),
+                }
+
+                if condition_value(until, vars).map_err(at_line)? {
+                    break;
+                }
+            }
+        }
+        StatementKind::Exit => return Ok(Flow::Exit),
+        StatementKind::Return => return Ok(Flow::Return),
+    }
+
+    Ok(Flow::Next)
+}
+
+fn check_iterations(iterations: &mut usize) -> Result<(), String> {
+    *iterations += 1;
+
+    if *iterations > MAX_ITERATIONS {
+        return Err(format!("loop exceeded {MAX_ITERATIONS} iterations"));
+    }
+
+    Ok(())
+}
+
+/// assigns a value to a variable, converted into the kind of the variable
+fn assign(vars: &mut dyn Variables, name: &str, value: &Value) -> Result<(), String> {
+    let Some(current) = vars.read(name) else {
+        return Err(format!("unknown variable \"{name}\""));
+    };
+
+    let Some(kind) = current.kind() else {
+        return Err(format!("variable \"{name}\" has no data type"));
+    };
+
+    let buf = value
+        .to_buf(&kind)
+        .map_err(|e| format!("can not assign to \"{name}\": {e}"))?;
+
+    vars.write(name, &buf).map_err(|e| e.to_string())
+}
+
+/// assigns a value to an element of an array variable, converted into the element kind
+fn assign_element(
+    vars: &mut dyn Variables,
+    name: &str,
+    index: &[i64],
+    value: &Value,
+) -> Result<(), String> {
+    let mut array = match vars.read(name) {
+        Some(DataBuffer::Array(array)) => array,
+        Some(_) => return Err(format!("variable \"{name}\" is no array")),
+        None => return Err(format!("unknown variable \"{name}\"")),
+    };
+
+    let buf = value
+        .to_buf(array.kind().element())
+        .map_err(|e| format!("can not assign to \"{name}\": {e}"))?;
+
+    array
+        .set(index, buf)
+        .map_err(|e| format!("can not assign to \"{name}\": {e}"))?;
+
+    vars.write(name, &DataBuffer::Array(array))
+        .map_err(|e| e.to_string())
+}
+
+/// evaluates the indices of an array element
+fn indices(index: &[Expr], vars: &dyn Variables) -> Result<Vec<i64>, String> {
+    index
+        .iter()
+        .map(|expr| match evaluate(expr, vars)? {
+            Value::Int(v) => i64::try_from(v).map_err(|_| format!("index {v} is out of range")),
+            other => Err(format!(
+                "array index needs to be an integer, found {}",
+                other.type_name()
+            )),
+        })
+        .collect()
+}
+
+fn condition_value(expr: &Expr, vars: &dyn Variables) -> Result<bool, String> {
+    match evaluate(expr, vars)? {
+        Value::Bool(v) => Ok(v),
+        other => Err(format!(
+            "condition needs to be BOOL, found {}",
+            other.type_name()
+        )),
+    }
+}
+
+pub(crate) fn evaluate(expr: &Expr, vars: &dyn Variables) -> Result<Value, String> {
+    match expr {
+        Expr::Literal(value) => Ok(value.clone()),
+        Expr::Variable(name) => match vars.read(name) {
+            Some(buf) => Value::from_buf(&buf).map_err(|e| format!("\"{name}\": {e}")),
+            None => Err(format!("unknown variable \"{name}\"")),
+        },
+        Expr::Index(name, index) => {
+            let index = indices(index, vars)?;
+
+            match vars.read(name) {
+                Some(DataBuffer::Array(array)) => match array.get(&index) {
+                    Some(buf) => Value::from_buf(buf).map_err(|e| format!("\"{name}\": {e}")),
+                    None => Err(format!(
+                        "index {index:?} of \"{name}\" is out of the bounds of {}",
+                        array.kind()
+                    )),
+                },
+                Some(_) => Err(format!("variable \"{name}\" is no array")),
+                None => Err(format!("unknown variable \"{name}\"")),
+            }
+        }
+        Expr::Unary(op, operand) => unary(*op, evaluate(operand, vars)?),
+        Expr::Binary(op, left, right) => binary(*op, evaluate(left, vars)?, evaluate(right, vars)?),
+        Expr::Call(name, args) => {
+            let args = args
+                .iter()
+                .map(|arg| evaluate(arg, vars))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            call(name, args)
+        }
+    }
+}
+
+fn unary(op: UnaryOp, value: Value) -> Result<Value, String> {
+    match (op, value) {
+        (UnaryOp::Neg, Value::Int(v)) => Ok(Value::Int(-v)),
+        (UnaryOp::Neg, Value::Real(v)) => Ok(Value::Real(-v)),
+        (UnaryOp::Not, Value::Bool(v)) => Ok(Value::Bool(!v)),
+        (UnaryOp::Not, Value::Bits(v, kind)) => {
+            let mask = u64::MAX >> (64 - width(&kind));
+            Ok(Value::Bits(!v & mask, kind))
+        }
+        (op, value) => Err(format!(
+            "{} is not defined for {}",
+            match op {
+                UnaryOp::Neg => "negation",
+                UnaryOp::Not => "NOT",
+            },
+            value.type_name()
+        )),
+    }
+}
+
+fn binary(op: BinaryOp, left: Value, right: Value) -> Result<Value, String> {
+    use BinaryOp::*;
+
+    let undefined = |left: &Value, right: &Value| {
+        format!(
+            "\"{op}\" is not defined for {} and {}",
+            left.type_name(),
+            right.type_name()
+        )
+    };
+    let overflow = || format!("\"{op}\" overflowed");
+
+    let value = match (op, &left, &right) {
+        // boolean and bitwise operators
+        (And | Or | Xor, Value::Bool(a), Value::Bool(b)) => Value::Bool(match op {
+            And => *a && *b,
+            Or => *a || *b,
+            _ => a ^ b,
+        }),
+        (And | Or | Xor, Value::Bits(..) | Value::Int(_), Value::Bits(..) | Value::Int(_))
+            if matches!(left, Value::Bits(..)) || matches!(right, Value::Bits(..)) =>
+        {
+            let (a, b, kind) =
+                bit_operands(&left, &right).ok_or_else(|| undefined(&left, &right))?;
+
+            Value::Bits(
+                match op {
+                    And => a & b,
+                    Or => a | b,
+                    _ => a ^ b,
+                },
+                kind,
+            )
+        }
+
+        // comparisons
+        (Eq | Ne | Lt | Le | Gt | Ge, _, _) => {
+            let ordering = compare(&left, &right).ok_or_else(|| undefined(&left, &right))?;
+
+            Value::Bool(match op {
+                Eq => ordering.is_eq(),
+                Ne => ordering.is_ne(),
+                Lt => ordering.is_lt(),
+                Le => ordering.is_le(),
+                Gt => ordering.is_gt(),
+                _ => ordering.is_ge(),
+            })
+        }
+
+        // integer arithmetic
+        (Add | Sub | Mul | Div | Mod | Pow, Value::Int(a), Value::Int(b)) => {
+            let (a, b) = (*a, *b);
+
+            Value::Int(match op {
+                Add => a.checked_add(b).ok_or_else(overflow)?,
+                Sub => a.checked_sub(b).ok_or_else(overflow)?,
+                Mul => a.checked_mul(b).ok_or_else(overflow)?,
+                Div | Mod if b == 0 => return Err("division by zero".to_string()),
+                Div => a / b,
+                Mod => a % b,
+                _ => {
+                    let exponent = u32::try_from(b).map_err(|_| overflow())?;
+                    a.checked_pow(exponent).ok_or_else(overflow)?
+                }
+            })
+        }
+
+        // real arithmetic, integers are promoted
+        (
+            Add | Sub | Mul | Div | Pow,
+            Value::Real(_) | Value::Int(_),
+            Value::Real(_) | Value::Int(_),
+        ) => {
+            let (a, b) = (real(&left).unwrap(), real(&right).unwrap());
+
+            Value::Real(match op {
+                Add => a + b,
+                Sub => a - b,
+                Mul => a * b,
+                Div => a / b,
+                _ => a.powf(b),
+            })
+        }
+
+        // durations
+        (Add, Value::Time(a), Value::Time(b)) => {
+            Value::Time(a.checked_add(*b).ok_or_else(overflow)?)
+        }
+        (Sub, Value::Time(a), Value::Time(b)) => {
+            Value::Time(a.checked_sub(*b).ok_or_else(overflow)?)
+        }
+        (Mul, Value::Time(t), Value::Int(n)) | (Mul, Value::Int(n), Value::Time(t)) => Value::Time(
+            t.checked_mul(u32::try_from(*n).map_err(|_| overflow())?)
+                .ok_or_else(overflow)?,
+        ),
+        (Div, Value::Time(t), Value::Int(n)) => {
+            let n = u32::try_from(*n).map_err(|_| overflow())?;
+
+            if n == 0 {
+                return Err("division by zero".to_string());
+            }
+
+            Value::Time(*t / n)
+        }
+        (Mul | Div, Value::Time(t), Value::Real(f)) => {
+            let f = if op == Div { 1.0 / f } else { *f };
+            Value::Time(Duration::try_from_secs_f64(t.as_secs_f64() * f).map_err(|_| overflow())?)
+        }
+
+        // points in time, a negative difference is an overflow of `TIME`
+        (Add, Value::DateTime(dt), Value::Time(t)) => {
+            Value::DateTime(dt.checked_add(*t).ok_or_else(overflow)?)
+        }
+        (Sub, Value::DateTime(dt), Value::Time(t)) => {
+            Value::DateTime(dt.checked_sub(*t).ok_or_else(overflow)?)
+        }
+        (Sub, Value::DateTime(a), Value::DateTime(b)) => {
+            Value::Time(a.duration_since(b).ok_or_else(overflow)?)
+        }
+        (Add, Value::TimeOfDay(tod), Value::Time(t)) => Value::TimeOfDay(*tod + *t),
+        (Sub, Value::TimeOfDay(tod), Value::Time(t)) => Value::TimeOfDay(*tod - *t),
+        (Sub, Value::TimeOfDay(a), Value::TimeOfDay(b)) => {
+            Value::Time(a.duration_since(b).ok_or_else(overflow)?)
+        }
+        (Sub, Value::Date(a), Value::Date(b)) => {
+            let days = u64::try_from(a.days_since(b)).map_err(|_| overflow())?;
+            Value::Time(Duration::from_secs(days * 86_400))
+        }
+
+        _ => return Err(undefined(&left, &right)),
+    };
+
+    Ok(value)
+}
+
+/// operands of a bitwise operation, untyped integers take the kind of the other operand
+fn bit_operands(left: &Value, right: &Value) -> Option<(u64, u64, crate::fb::data::ty::DataKind)> {
+    match (left, right) {
+        (Value::Bits(a, kind), Value::Bits(b, other)) if kind == other => {
+            Some((*a, *b, kind.clone()))
+        }
+        (Value::Bits(a, kind), Value::Int(b)) => Some((*a, u64::try_from(*b).ok()?, kind.clone())),
+        (Value::Int(a), Value::Bits(b, kind)) => Some((u64::try_from(*a).ok()?, *b, kind.clone())),
+        _ => None,
+    }
+}
+
+fn real(value: &Value) -> Option<f64> {
+    match value {
+        Value::Real(v) => Some(*v),
+        Value::Int(v) => Some(*v as f64),
+        _ => None,
+    }
+}
+
+fn compare(left: &Value, right: &Value) -> Option<std::cmp::Ordering> {
+    match (left, right) {
+        (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+        (Value::Int(a), Value::Int(b)) => Some(a.cmp(b)),
+        (Value::Bits(a, _), Value::Bits(b, _)) => Some(a.cmp(b)),
+        (Value::Bits(a, _), Value::Int(b)) => Some(i128::from(*a).cmp(b)),
+        (Value::Int(a), Value::Bits(b, _)) => Some(a.cmp(&i128::from(*b))),
+        (Value::Time(a), Value::Time(b)) => Some(a.cmp(b)),
+        (Value::Date(a), Value::Date(b)) => Some(a.cmp(b)),
+        (Value::TimeOfDay(a), Value::TimeOfDay(b)) => Some(a.cmp(b)),
+        (Value::DateTime(a), Value::DateTime(b)) => Some(a.cmp(b)),
+        (Value::String(a), Value::String(b)) | (Value::WString(a), Value::WString(b)) => {
+            Some(a.cmp(b))
+        }
+        _ => real(left)?.partial_cmp(&real(right)?),
+    }
+}
+
+/// standard functions of `IEC 61131-3`
+fn call(name: &str, args: Vec<Value>) -> Result<Value, String> {
+    let arity = |expected: usize| {
+        if args.len() == expected {
+            Ok(())
+        } else {
+            Err(format!(
+                "{} expects {expected} arguments, found {}",
+                name.to_uppercase(),
+                args.len()
+            ))
+        }
+    };
+
+    let ordering = |a: &Value, b: &Value| {
+        compare(a, b).ok_or_else(|| {
+            format!(
+                "{} is not defined for {} and {}",
+                name.to_uppercase(),
+                a.type_name(),
+                b.type_name()
+            )
+        })
+    };
+
+    match name {
+        "max" | "min" => {
+            let mut args = args.into_iter();
+            let Some(mut result) = args.next() else {
+                return Err(format!(
+                    "{} expects at least one argument",
+                    name.to_uppercase()
+                ));
+            };
+
+            for arg in args {
+                let ordering = ordering(&arg, &result)?;
+
+                if (name == "max" && ordering.is_gt()) || (name == "min" && ordering.is_lt()) {
+                    result = arg;
+                }
+            }
+
+            Ok(result)
+        }
+        "limit" => {
+            arity(3)?;
+            let [min, value, max] = <[Value; 3]>::try_from(args).unwrap();
+
+            if ordering(&value, &min)?.is_lt() {
+                Ok(min)
+            } else if ordering(&value, &max)?.is_gt() {
+                Ok(max)
+            } else {
+                Ok(value)
+            }
+        }
+        "sel" => {
+            arity(3)?;
+            let [g, in0, in1] = <[Value; 3]>::try_from(args).unwrap();
+
+            match g {
+                Value::Bool(true) => Ok(in1),
+                Value::Bool(false) => Ok(in0),
+                other => Err(format!(
+                    "SEL expects a BOOL selector, found {}",
+                    other.type_name()
+                )),
+            }
+        }
+        "mux" => {
+            let mut args = args.into_iter();
+
+            let k = match args.next() {
+                Some(Value::Int(k)) => k,
+                _ => return Err("MUX expects an integer selector".to_string()),
+            };
+
+            usize::try_from(k)
+                .ok()
+                .and_then(|k| args.nth(k))
+                .ok_or_else(|| format!("MUX selector {k} is out of range"))
+        }
+        "abs" => {
+            arity(1)?;
+
+            match &args[0] {
+                Value::Int(v) => Ok(Value::Int(v.abs())),
+                Value::Real(v) => Ok(Value::Real(v.abs())),
+                other => Err(format!("ABS is not defined for {}", other.type_name())),
+            }
+        }
+        "sqrt" => {
+            arity(1)?;
+
+            match real(&args[0]) {
+                Some(v) => Ok(Value::Real(v.sqrt())),
+                None => Err(format!("SQRT is not defined for {}", args[0].type_name())),
+            }
+        }
+        "trunc" => {
+            arity(1)?;
+
+            match &args[0] {
+                Value::Real(v) if v.is_finite() => Ok(Value::Int(v.trunc() as i128)),
+                Value::Int(v) => Ok(Value::Int(*v)),
+                other => Err(format!("TRUNC is not defined for {other}")),
+            }
+        }
+        _ => match convert::parse_function_name(name) {
+            // the argument gets the source type, so e.g. `UINT_TO_DINT(-1)` is rejected
+            Some((from, to)) => {
+                arity(1)?;
+
+                let buf = args[0].to_buf(&from)?;
+                let converted = convert::convert(&buf, &to).map_err(|e| e.to_string())?;
+
+                Value::from_buf(&converted)
+            }
+            None => Err(format!("unknown function \"{}\"", name.to_uppercase())),
+        },
+    }
+}
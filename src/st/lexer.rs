@@ -0,0 +1,291 @@
+//! Splits Structured Text into tokens, identifiers are lowercased since `IEC 61131-3` is case-insensitive.
+
+use crate::fb::data::{comm::DataBuffer, ty::DataKind};
+
+use super::{StError, value::Value};
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Token {
+    /// identifier or keyword, lowercased
+    Ident(String),
+    Literal(Value),
+    Assign,
+    Colon,
+    Semicolon,
+    Comma,
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Range,
+    Plus,
+    Minus,
+    Star,
+    Power,
+    Slash,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Ampersand,
+}
+
+/// a token and the line it starts in
+pub(crate) type Spanned = (Token, usize);
+
+pub(crate) fn tokenize(text: &str) -> Result<Vec<Spanned>, StError> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = vec![];
+    let mut line = 1;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+
+        match (c, next) {
+            ('\n', _) => {
+                line += 1;
+                i += 1;
+            }
+            (c, _) if c.is_whitespace() => i += 1,
+            ('(', Some('*')) | ('/', Some('*')) => {
+                let close = if c == '(' { ')' } else { '/' };
+                let start = line;
+                i += 2;
+
+                loop {
+                    match (chars.get(i), chars.get(i + 1)) {
+                        (Some('*'), Some(c)) if *c == close => break,
+                        (Some(c), _) => {
+                            if *c == '\n' {
+                                line += 1;
+                            }
+                            i += 1;
+                        }
+                        (None, _) => return Err(StError::new(start, "unterminated comment")),
+                    }
+                }
+
+                i += 2;
+            }
+            ('/', Some('/')) => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            ('\'', _) | ('"', _) => {
+                let (value, end) = string(&chars, i, line)?;
+                tokens.push((Token::Literal(value), line));
+                i = end;
+            }
+            (c, _) if c.is_ascii_digit() => {
+                let (value, end) = number(&chars, i, line)?;
+                tokens.push((Token::Literal(value), line));
+                i = end;
+            }
+            (c, _) if c.is_ascii_alphabetic() || c == '_' => {
+                let start = i;
+
+                while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+
+                let ident = chars[start..i].iter().collect::<String>().to_lowercase();
+
+                if chars.get(i) == Some(&'#') {
+                    let (value, end) = typed_literal(&chars, &ident, i + 1, line)?;
+                    tokens.push((Token::Literal(value), line));
+                    i = end;
+                    continue;
+                }
+
+                let token = match ident.as_str() {
+                    "true" => Token::Literal(Value::Bool(true)),
+                    "false" => Token::Literal(Value::Bool(false)),
+                    _ => Token::Ident(ident),
+                };
+
+                tokens.push((token, line));
+            }
+            _ => {
+                let (token, len) = match (c, next) {
+                    (':', Some('=')) => (Token::Assign, 2),
+                    ('.', Some('.')) => (Token::Range, 2),
+                    ('*', Some('*')) => (Token::Power, 2),
+                    ('<', Some('>')) => (Token::Ne, 2),
+                    ('<', Some('=')) => (Token::Le, 2),
+                    ('>', Some('=')) => (Token::Ge, 2),
+                    (':', _) => (Token::Colon, 1),
+                    (';', _) => (Token::Semicolon, 1),
+                    (',', _) => (Token::Comma, 1),
+                    ('(', _) => (Token::LParen, 1),
+                    (')', _) => (Token::RParen, 1),
+                    ('[', _) => (Token::LBracket, 1),
+                    (']', _) => (Token::RBracket, 1),
+                    ('+', _) => (Token::Plus, 1),
+                    ('-', _) => (Token::Minus, 1),
+                    ('*', _) => (Token::Star, 1),
+                    ('/', _) => (Token::Slash, 1),
+                    ('=', _) => (Token::Eq, 1),
+                    ('<', _) => (Token::Lt, 1),
+                    ('>', _) => (Token::Gt, 1),
+                    ('&', _) => (Token::Ampersand, 1),
+                    _ => return Err(StError::new(line, format!("unexpected character '{c}'"))),
+                };
+
+                tokens.push((token, line));
+                i += len;
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// `'text'` (STRING) or `"text"` (WSTRING) including `$` escapes
+fn string(chars: &[char], start: usize, line: usize) -> Result<(Value, usize), StError> {
+    let quote = chars[start];
+    let mut text = String::new();
+    let mut i = start + 1;
+
+    loop {
+        match chars.get(i) {
+            Some(c) if *c == quote => break,
+            Some('$') => {
+                let escaped = match chars.get(i + 1).map(char::to_ascii_uppercase) {
+                    Some('$') => '$',
+                    Some('\'') => '\'',
+                    Some('"') => '"',
+                    Some('N') | Some('L') => '\n',
+                    Some('R') => '\r',
+                    Some('T') => '\t',
+                    _ => return Err(StError::new(line, "invalid escape sequence in string")),
+                };
+
+                text.push(escaped);
+                i += 2;
+            }
+            Some(c) => {
+                text.push(*c);
+                i += 1;
+            }
+            None => return Err(StError::new(line, "unterminated string literal")),
+        }
+    }
+
+    let value = match quote {
+        '\'' => Value::String(text),
+        _ => Value::WString(text),
+    };
+
+    Ok((value, i + 1))
+}
+
+/// integer (`42`, `16#FF`) or real (`1.5`, `1e3`) literal
+fn number(chars: &[char], start: usize, line: usize) -> Result<(Value, usize), StError> {
+    let mut i = start;
+    let mut real = false;
+
+    let digits = |i: &mut usize, radix: u32| {
+        while *i < chars.len() && (chars[*i].is_digit(radix) || chars[*i] == '_') {
+            *i += 1;
+        }
+    };
+
+    digits(&mut i, 10);
+
+    if chars.get(i) == Some(&'#') {
+        i += 1;
+        digits(&mut i, 16);
+    } else {
+        // `1..5` is a range, not a real
+        if chars.get(i) == Some(&'.') && chars.get(i + 1).is_some_and(char::is_ascii_digit) {
+            real = true;
+            i += 1;
+            digits(&mut i, 10);
+        }
+
+        if matches!(chars.get(i), Some('e') | Some('E')) {
+            let sign = usize::from(matches!(chars.get(i + 1), Some('+') | Some('-')));
+
+            if chars.get(i + 1 + sign).is_some_and(char::is_ascii_digit) {
+                real = true;
+                i += 1 + sign;
+                digits(&mut i, 10);
+            }
+        }
+    }
+
+    let text: String = chars[start..i].iter().collect();
+    let kind = if real {
+        DataKind::LReal
+    } else {
+        DataKind::LInt
+    };
+
+    match DataBuffer::parse_literal(&kind, &text) {
+        Some(DataBuffer::LReal(v)) => Ok((Value::Real(v), i)),
+        Some(DataBuffer::LInt(v)) => Ok((Value::Int(v.into()), i)),
+        // integers beyond LINT are only representable as ULINT
+        _ => match DataBuffer::parse_literal(&DataKind::ULInt, &text) {
+            Some(DataBuffer::ULInt(v)) if !real => Ok((Value::Int(v.into()), i)),
+            _ => Err(StError::new(line, format!("invalid number \"{text}\""))),
+        },
+    }
+}
+
+/// literals with type prefix like `T#1s`, `UINT#5` or `BYTE#16#FF`
+fn typed_literal(
+    chars: &[char],
+    prefix: &str,
+    start: usize,
+    line: usize,
+) -> Result<(Value, usize), StError> {
+    let mut i = start;
+
+    if matches!(chars.get(i), Some('+') | Some('-')) {
+        i += 1;
+    }
+
+    let kind = match prefix {
+        "t" | "time" => Some(DataKind::Time),
+        "lt" => Some(DataKind::LTime),
+        "d" => Some(DataKind::Date),
+        "ld" => Some(DataKind::LDate),
+        _ => DataKind::from_iec_name(prefix),
+    };
+
+    // dates and times of day are separated by `-` and `:`
+    let separators = match kind {
+        Some(
+            DataKind::Date
+            | DataKind::LDate
+            | DataKind::TimeOfDay
+            | DataKind::LTimeOfDay
+            | DataKind::DateTime
+            | DataKind::LDateTime,
+        ) => "_.#-:",
+        _ => "_.#",
+    };
+
+    while i < chars.len() && (chars[i].is_ascii_alphanumeric() || separators.contains(chars[i])) {
+        i += 1;
+    }
+
+    let text: String = chars[start..i].iter().collect();
+
+    let value = kind
+        .and_then(|kind| DataBuffer::parse_literal(&kind, &text))
+        .and_then(|buf| Value::from_buf(&buf).ok());
+
+    match value {
+        Some(value) => Ok((value, i)),
+        None => Err(StError::new(
+            line,
+            format!("invalid literal \"{prefix}#{text}\""),
+        )),
+    }
+}
@@ -0,0 +1,120 @@
+//! Interpreter for a subset of `IEC 61131-3` Structured Text, used to execute algorithm bodies
+//! (e.g. loaded from `.fbt` files) without translating them into Rust.
+//!
+//! Supported are:
+//! - assignments (including array elements like `x[i] := 0;`), `IF`/`ELSIF`/`ELSE`, `CASE` (integer labels and ranges), `FOR`, `WHILE`, `REPEAT`, `EXIT`, `RETURN`
+//! - arithmetic (`+ - * / MOD **`), boolean/bitwise (`AND & OR XOR NOT`) and comparison operators
+//! - the standard functions `MAX`, `MIN`, `LIMIT`, `SEL`, `MUX`, `ABS`, `SQRT`, `TRUNC` and the type conversions (`*_TO_*`)
+//!
+//! Variables are accessed through the `Variables` trait as `DataBuffer`s,
+//! which is implemented for every struct deriving `Bfb` (all `Data` fields) and for plain maps.
+//!
+//! ```text
+//! let vote = Program::parse("State := (A AND B) OR (A AND C) OR (B AND C);")?;
+//! vote.execute(&mut voter)?;
+//! ```
+
+use std::{collections::HashMap, fmt::Display};
+
+use crate::fb::{data::comm::DataBuffer, error::FbError};
+
+mod interp;
+mod lexer;
+mod parser;
+mod value;
+
+/// read and write access to the variables of an algorithm,
+/// names are passed in lowercase since identifiers are case-insensitive
+pub trait Variables {
+    fn read(&self, name: &str) -> Option<DataBuffer>;
+
+    /// the buffer always has the kind of the value returned by `read`
+    fn write(&mut self, name: &str, value: &DataBuffer) -> Result<(), FbError>;
+}
+
+impl Variables for HashMap<String, DataBuffer> {
+    fn read(&self, name: &str) -> Option<DataBuffer> {
+        self.get(name).cloned()
+    }
+
+    fn write(&mut self, name: &str, value: &DataBuffer) -> Result<(), FbError> {
+        match self.get_mut(name) {
+            Some(var) => {
+                *var = value.clone();
+                Ok(())
+            }
+            None => Err(FbError::unknown_data(name)),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct StError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl StError {
+    pub(crate) fn new(line: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for StError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for StError {}
+
+/// a parsed algorithm body, which can be executed repeatedly
+#[derive(Clone, Debug)]
+pub struct Program {
+    statements: Vec<parser::Statement>,
+}
+
+impl Program {
+    pub fn parse(text: &str) -> Result<Self, StError> {
+        let tokens = lexer::tokenize(text)?;
+
+        Ok(Self {
+            statements: parser::Parser::new(tokens).statements()?,
+        })
+    }
+
+    pub fn execute(&self, vars: &mut dyn Variables) -> Result<(), StError> {
+        interp::execute(&self.statements, vars)
+    }
+}
+
+/// a parsed expression, e.g. the guard of an EC transition
+#[derive(Clone, Debug)]
+pub struct Expression {
+    expr: parser::Expr,
+}
+
+impl Expression {
+    pub fn parse(text: &str) -> Result<Self, StError> {
+        let tokens = lexer::tokenize(text)?;
+
+        Ok(Self {
+            expr: parser::Parser::new(tokens).expression()?,
+        })
+    }
+
+    /// evaluates the expression, fails if the result is not a `BOOL`
+    pub fn evaluate_bool(&self, vars: &dyn Variables) -> Result<bool, StError> {
+        match interp::evaluate(&self.expr, vars) {
+            Ok(value::Value::Bool(v)) => Ok(v),
+            Ok(other) => Err(StError::new(
+                1,
+                format!("expected a BOOL expression, found {}", other.type_name()),
+            )),
+            Err(e) => Err(StError::new(1, e)),
+        }
+    }
+}
@@ -0,0 +1,615 @@
+//! Recursive descent parser building the statement and expression trees.
+//!
+//! Operator precedence (highest first): `()`, function calls and array elements, `**`, unary `-`/`NOT`,
+//! `*`/`/`/`MOD`, `+`/`-`, comparisons, `=`/`<>`, `AND`/`&`, `XOR`, `OR`.
+
+use super::{
+    StError,
+    lexer::{Spanned, Token},
+    value::Value,
+};
+
+const KEYWORDS: &[&str] = &[
+    "if",
+    "then",
+    "elsif",
+    "else",
+    "end_if",
+    "case",
+    "of",
+    "end_case",
+    "for",
+    "to",
+    "by",
+    "do",
+    "end_for",
+    "while",
+    "end_while",
+    "repeat",
+    "until",
+    "end_repeat",
+    "exit",
+    "return",
+    "and",
+    "or",
+    "xor",
+    "not",
+    "mod",
+];
+
+#[derive(Clone, Debug)]
+pub(crate) struct Statement {
+    pub kind: StatementKind,
+    pub line: usize,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum StatementKind {
+    Assign {
+        target: String,
+        /// indices of an array element, empty for whole variables
+        index: Vec<Expr>,
+        value: Expr,
+    },
+    If {
+        branches: Vec<(Expr, Vec<Statement>)>,
+        otherwise: Vec<Statement>,
+    },
+    Case {
+        selector: Expr,
+        cases: Vec<(Vec<CaseLabel>, Vec<Statement>)>,
+        otherwise: Vec<Statement>,
+    },
+    For {
+        variable: String,
+        from: Expr,
+        to: Expr,
+        by: Option<Expr>,
+        body: Vec<Statement>,
+    },
+    While {
+        condition: Expr,
+        body: Vec<Statement>,
+    },
+    Repeat {
+        body: Vec<Statement>,
+        until: Expr,
+    },
+    Exit,
+    Return,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum CaseLabel {
+    Value(i128),
+    Range(i128, i128),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum UnaryOp {
+    Neg,
+    Not,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum BinaryOp {
+    Or,
+    Xor,
+    And,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Pow,
+}
+
+impl std::fmt::Display for BinaryOp {
+    /// writes the operator as it is written in ST
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            BinaryOp::Or => "OR",
+            BinaryOp::Xor => "XOR",
+            BinaryOp::And => "AND",
+            BinaryOp::Eq => "=",
+            BinaryOp::Ne => "<>",
+            BinaryOp::Lt => "<",
+            BinaryOp::Le => "<=",
+            BinaryOp::Gt => ">",
+            BinaryOp::Ge => ">=",
+            BinaryOp::Add => "+",
+            BinaryOp::Sub => "-",
+            BinaryOp::Mul => "*",
+            BinaryOp::Div => "/",
+            BinaryOp::Mod => "MOD",
+            BinaryOp::Pow => "**",
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum Expr {
+    Literal(Value),
+    Variable(String),
+    /// element of an array variable, one index per dimension
+    Index(String, Vec<Expr>),
+    Unary(UnaryOp, Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    Call(String, Vec<Expr>),
+}
+
+pub(crate) struct Parser {
+    tokens: Vec<Spanned>,
+    pos: usize,
+}
+
+impl Parser {
+    pub(crate) fn new(tokens: Vec<Spanned>) -> Self {
+        Self { tokens, pos: 0 }
+    }
+
+    /// parses statements until the end of the input
+    pub(crate) fn statements(&mut self) -> Result<Vec<Statement>, StError> {
+        let body = self.block(&[])?;
+
+        match self.peek() {
+            None => Ok(body),
+            Some(token) => Err(self.error(format!("unexpected {}", describe(token)))),
+        }
+    }
+
+    /// parses a single expression spanning the whole input
+    pub(crate) fn expression(&mut self) -> Result<Expr, StError> {
+        let expr = self.expr()?;
+
+        match self.peek() {
+            None => Ok(expr),
+            Some(token) => Err(self.error(format!("unexpected {}", describe(token)))),
+        }
+    }
+
+    /// parses statements until one of the given keywords (or the end of the input) follows
+    fn block(&mut self, end: &[&str]) -> Result<Vec<Statement>, StError> {
+        let mut statements = vec![];
+
+        loop {
+            match self.peek() {
+                None => break,
+                Some(Token::Ident(ident)) if end.contains(&ident.as_str()) => break,
+                Some(Token::Semicolon) => self.pos += 1,
+                Some(_) => statements.push(self.statement()?),
+            }
+        }
+
+        Ok(statements)
+    }
+
+    fn statement(&mut self) -> Result<Statement, StError> {
+        let line = self.line();
+
+        let kind = match self.peek().cloned() {
+            Some(Token::Ident(keyword)) if keyword == "if" => self.if_statement()?,
+            Some(Token::Ident(keyword)) if keyword == "case" => self.case_statement()?,
+            Some(Token::Ident(keyword)) if keyword == "for" => self.for_statement()?,
+            Some(Token::Ident(keyword)) if keyword == "while" => {
+                self.pos += 1;
+                let condition = self.expr()?;
+                self.keyword("do")?;
+                let body = self.block(&["end_while"])?;
+                self.keyword("end_while")?;
+
+                StatementKind::While { condition, body }
+            }
+            Some(Token::Ident(keyword)) if keyword == "repeat" => {
+                self.pos += 1;
+                let body = self.block(&["until"])?;
+                self.keyword("until")?;
+                let until = self.expr()?;
+                self.keyword("end_repeat")?;
+
+                StatementKind::Repeat { body, until }
+            }
+            Some(Token::Ident(keyword)) if keyword == "exit" => {
+                self.pos += 1;
+                StatementKind::Exit
+            }
+            Some(Token::Ident(keyword)) if keyword == "return" => {
+                self.pos += 1;
+                StatementKind::Return
+            }
+            Some(Token::Ident(_)) => {
+                let target = self.variable()?;
+                let index = match self.eat(&Token::LBracket) {
+                    true => self.index()?,
+                    false => vec![],
+                };
+                self.expect(Token::Assign)?;
+                let value = self.expr()?;
+
+                StatementKind::Assign {
+                    target,
+                    index,
+                    value,
+                }
+            }
+            Some(token) => {
+                return Err(self.error(format!("expected a statement, found {}", describe(&token))));
+            }
+            None => return Err(self.error("expected a statement")),
+        };
+
+        self.expect(Token::Semicolon)?;
+
+        Ok(Statement { kind, line })
+    }
+
+    fn if_statement(&mut self) -> Result<StatementKind, StError> {
+        let mut branches = vec![];
+        let mut otherwise = vec![];
+
+        self.keyword("if")?;
+
+        loop {
+            let condition = self.expr()?;
+            self.keyword("then")?;
+            let body = self.block(&["elsif", "else", "end_if"])?;
+            branches.push((condition, body));
+
+            if self.eat_keyword("elsif") {
+                continue;
+            }
+
+            if self.eat_keyword("else") {
+                otherwise = self.block(&["end_if"])?;
+            }
+
+            self.keyword("end_if")?;
+            break;
+        }
+
+        Ok(StatementKind::If {
+            branches,
+            otherwise,
+        })
+    }
+
+    fn case_statement(&mut self) -> Result<StatementKind, StError> {
+        self.keyword("case")?;
+        let selector = self.expr()?;
+        self.keyword("of")?;
+
+        let mut cases = vec![];
+        let mut otherwise = vec![];
+
+        loop {
+            if self.eat_keyword("end_case") {
+                break;
+            }
+
+            if self.eat_keyword("else") {
+                otherwise = self.block(&["end_case"])?;
+                self.keyword("end_case")?;
+                break;
+            }
+
+            let mut labels = vec![];
+
+            loop {
+                let from = self.case_value()?;
+
+                if self.eat(&Token::Range) {
+                    labels.push(CaseLabel::Range(from, self.case_value()?));
+                } else {
+                    labels.push(CaseLabel::Value(from));
+                }
+
+                if !self.eat(&Token::Comma) {
+                    break;
+                }
+            }
+
+            self.expect(Token::Colon)?;
+
+            let body = self.case_body()?;
+            cases.push((labels, body));
+        }
+
+        Ok(StatementKind::Case {
+            selector,
+            cases,
+            otherwise,
+        })
+    }
+
+    /// statements of a case run until the next label, `ELSE` or `END_CASE`
+    fn case_body(&mut self) -> Result<Vec<Statement>, StError> {
+        let mut statements = vec![];
+
+        loop {
+            match self.peek() {
+                None => break,
+                Some(Token::Ident(ident)) if ident == "else" || ident == "end_case" => break,
+                Some(Token::Literal(_)) | Some(Token::Minus) => break,
+                Some(Token::Semicolon) => self.pos += 1,
+                Some(_) => statements.push(self.statement()?),
+            }
+        }
+
+        Ok(statements)
+    }
+
+    fn case_value(&mut self) -> Result<i128, StError> {
+        let negative = self.eat(&Token::Minus);
+
+        match self.next() {
+            Some(Token::Literal(Value::Int(v))) => Ok(if negative { -v } else { v }),
+            Some(Token::Literal(Value::Bits(v, _))) if !negative => Ok(v.into()),
+            _ => Err(self.error("expected an integer case label")),
+        }
+    }
+
+    fn for_statement(&mut self) -> Result<StatementKind, StError> {
+        self.keyword("for")?;
+        let variable = self.variable()?;
+        self.expect(Token::Assign)?;
+        let from = self.expr()?;
+        self.keyword("to")?;
+        let to = self.expr()?;
+        let by = if self.eat_keyword("by") {
+            Some(self.expr()?)
+        } else {
+            None
+        };
+        self.keyword("do")?;
+        let body = self.block(&["end_for"])?;
+        self.keyword("end_for")?;
+
+        Ok(StatementKind::For {
+            variable,
+            from,
+            to,
+            by,
+            body,
+        })
+    }
+
+    fn expr(&mut self) -> Result<Expr, StError> {
+        self.binary(0)
+    }
+
+    /// parses binary operators of the given precedence level and above
+    fn binary(&mut self, level: usize) -> Result<Expr, StError> {
+        const LEVELS: usize = 7;
+
+        if level == LEVELS {
+            return self.unary();
+        }
+
+        let mut left = self.binary(level + 1)?;
+
+        while let Some(op) = self.peek().and_then(|t| binary_op(t, level)) {
+            self.pos += 1;
+            let right = self.binary(level + 1)?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr, StError> {
+        if self.eat(&Token::Minus) {
+            return Ok(Expr::Unary(UnaryOp::Neg, Box::new(self.unary()?)));
+        }
+
+        if self.eat(&Token::Plus) {
+            return self.unary();
+        }
+
+        if self.eat_keyword("not") {
+            return Ok(Expr::Unary(UnaryOp::Not, Box::new(self.unary()?)));
+        }
+
+        self.power()
+    }
+
+    fn power(&mut self) -> Result<Expr, StError> {
+        let mut left = self.primary()?;
+
+        while self.eat(&Token::Power) {
+            let right = self.primary()?;
+            left = Expr::Binary(BinaryOp::Pow, Box::new(left), Box::new(right));
+        }
+
+        Ok(left)
+    }
+
+    fn primary(&mut self) -> Result<Expr, StError> {
+        match self.next() {
+            Some(Token::Literal(value)) => Ok(Expr::Literal(value)),
+            Some(Token::LParen) => {
+                let expr = self.expr()?;
+                self.expect(Token::RParen)?;
+                Ok(expr)
+            }
+            Some(Token::Ident(name)) if !KEYWORDS.contains(&name.as_str()) => {
+                if self.eat(&Token::LBracket) {
+                    return Ok(Expr::Index(name, self.index()?));
+                }
+
+                if !self.eat(&Token::LParen) {
+                    return Ok(Expr::Variable(name));
+                }
+
+                let mut args = vec![];
+
+                if !self.eat(&Token::RParen) {
+                    loop {
+                        args.push(self.expr()?);
+
+                        if self.eat(&Token::RParen) {
+                            break;
+                        }
+
+                        self.expect(Token::Comma)?;
+                    }
+                }
+
+                Ok(Expr::Call(name, args))
+            }
+            Some(token) => {
+                self.pos -= 1;
+                Err(self.error(format!(
+                    "expected an expression, found {}",
+                    describe(&token)
+                )))
+            }
+            None => Err(self.error("expected an expression")),
+        }
+    }
+
+    /// parses the comma separated indices of an array element after `[`
+    fn index(&mut self) -> Result<Vec<Expr>, StError> {
+        let mut index = vec![self.expr()?];
+
+        while self.eat(&Token::Comma) {
+            index.push(self.expr()?);
+        }
+
+        self.expect(Token::RBracket)?;
+
+        Ok(index)
+    }
+
+    fn variable(&mut self) -> Result<String, StError> {
+        match self.next() {
+            Some(Token::Ident(name)) if !KEYWORDS.contains(&name.as_str()) => Ok(name),
+            _ => {
+                self.pos -= 1;
+                Err(self.error("expected a variable"))
+            }
+        }
+    }
+}
+
+// token helpers
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(t, _)| t)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).map(|(t, _)| t.clone());
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.pos += 1;
+            return true;
+        }
+
+        false
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Ident(ident)) if ident == keyword) {
+            self.pos += 1;
+            return true;
+        }
+
+        false
+    }
+
+    fn expect(&mut self, token: Token) -> Result<(), StError> {
+        if self.eat(&token) {
+            return Ok(());
+        }
+
+        Err(self.unexpected(&describe(&token)))
+    }
+
+    fn keyword(&mut self, keyword: &str) -> Result<(), StError> {
+        if self.eat_keyword(keyword) {
+            return Ok(());
+        }
+
+        Err(self.unexpected(&keyword.to_uppercase()))
+    }
+
+    fn unexpected(&self, expected: &str) -> StError {
+        match self.peek() {
+            Some(token) => self.error(format!("expected {expected}, found {}", describe(token))),
+            None => self.error(format!("expected {expected}, found the end of the input")),
+        }
+    }
+
+    /// line of the current token (or the last one at the end of the input)
+    fn line(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .or(self.tokens.last())
+            .map_or(1, |(_, line)| *line)
+    }
+
+    fn error(&self, message: impl Into<String>) -> StError {
+        StError::new(self.line(), message)
+    }
+}
+
+fn binary_op(token: &Token, level: usize) -> Option<BinaryOp> {
+    let op = match (level, token) {
+        (0, Token::Ident(i)) if i == "or" => BinaryOp::Or,
+        (1, Token::Ident(i)) if i == "xor" => BinaryOp::Xor,
+        (2, Token::Ident(i)) if i == "and" => BinaryOp::And,
+        (2, Token::Ampersand) => BinaryOp::And,
+        (3, Token::Eq) => BinaryOp::Eq,
+        (3, Token::Ne) => BinaryOp::Ne,
+        (4, Token::Lt) => BinaryOp::Lt,
+        (4, Token::Le) => BinaryOp::Le,
+        (4, Token::Gt) => BinaryOp::Gt,
+        (4, Token::Ge) => BinaryOp::Ge,
+        (5, Token::Plus) => BinaryOp::Add,
+        (5, Token::Minus) => BinaryOp::Sub,
+        (6, Token::Star) => BinaryOp::Mul,
+        (6, Token::Slash) => BinaryOp::Div,
+        (6, Token::Ident(i)) if i == "mod" => BinaryOp::Mod,
+        _ => return None,
+    };
+
+    Some(op)
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Ident(ident) => format!("\"{ident}\""),
+        Token::Literal(value) => format!("literal {value}"),
+        Token::Assign => "\":=\"".to_string(),
+        Token::Colon => "\":\"".to_string(),
+        Token::Semicolon => "\";\"".to_string(),
+        Token::Comma => "\",\"".to_string(),
+        Token::LParen => "\"(\"".to_string(),
+        Token::RParen => "\")\"".to_string(),
+        Token::LBracket => "\"[\"".to_string(),
+        Token::RBracket => "\"]\"".to_string(),
+        Token::Range => "\"..\"".to_string(),
+        Token::Plus => "\"+\"".to_string(),
+        Token::Minus => "\"-\"".to_string(),
+        Token::Star => "\"*\"".to_string(),
+        Token::Power => "\"**\"".to_string(),
+        Token::Slash => "\"/\"".to_string(),
+        Token::Eq => "\"=\"".to_string(),
+        Token::Ne => "\"<>\"".to_string(),
+        Token::Lt => "\"<\"".to_string(),
+        Token::Le => "\"<=\"".to_string(),
+        Token::Gt => "\">\"".to_string(),
+        Token::Ge => "\">=\"".to_string(),
+        Token::Ampersand => "\"&\"".to_string(),
+    }
+}
@@ -0,0 +1,187 @@
+//! Intermediate values of the interpreter.
+//!
+//! Integers are evaluated as `i128` and reals as `f64`, the result is range-checked
+//! when it is assigned to a variable of a concrete `DataKind`.
+
+use std::{borrow::Cow, fmt::Display, time::Duration};
+
+use crate::fb::data::{
+    calendar::{Date, DateAndTime, TimeOfDay},
+    comm::DataBuffer,
+    ty::DataKind,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Value {
+    Bool(bool),
+    Int(i128),
+    /// bit strings (`BYTE`, `WORD`, `DWORD`, `LWORD`) keep their kind for bitwise operations
+    Bits(u64, DataKind),
+    Real(f64),
+    Time(Duration),
+    /// the long date and time types share the values of the short ones
+    Date(Date),
+    TimeOfDay(TimeOfDay),
+    DateTime(DateAndTime),
+    String(String),
+    WString(String),
+}
+
+impl Value {
+    pub(crate) fn from_buf(buf: &DataBuffer) -> Result<Self, String> {
+        let value = match buf {
+            DataBuffer::SInt(v) => Value::Int((*v).into()),
+            DataBuffer::Int(v) => Value::Int((*v).into()),
+            DataBuffer::DInt(v) => Value::Int((*v).into()),
+            DataBuffer::LInt(v) => Value::Int((*v).into()),
+            DataBuffer::USInt(v) => Value::Int((*v).into()),
+            DataBuffer::UInt(v) => Value::Int((*v).into()),
+            DataBuffer::UDInt(v) => Value::Int((*v).into()),
+            DataBuffer::ULInt(v) => Value::Int((*v).into()),
+            DataBuffer::Real(v) => Value::Real((*v).into()),
+            DataBuffer::LReal(v) => Value::Real(*v),
+            DataBuffer::Time(v) | DataBuffer::LTime(v) => Value::Time(*v),
+            DataBuffer::Date(v) | DataBuffer::LDate(v) => Value::Date(*v),
+            DataBuffer::TimeOfDay(v) | DataBuffer::LTimeOfDay(v) => Value::TimeOfDay(*v),
+            DataBuffer::DateTime(v) | DataBuffer::LDateTime(v) => Value::DateTime(*v),
+            DataBuffer::Bool(v) => Value::Bool(*v),
+            DataBuffer::Byte(v) => Value::Bits((*v).into(), DataKind::Byte),
+            DataBuffer::Word(v) => Value::Bits((*v).into(), DataKind::Word),
+            DataBuffer::DWord(v) => Value::Bits((*v).into(), DataKind::DWord),
+            DataBuffer::LWord(v) => Value::Bits(*v, DataKind::LWord),
+            DataBuffer::String(v) => Value::String(String::from_utf8_lossy(v).into_owned()),
+            DataBuffer::WString(v) => Value::WString(String::from_utf16_lossy(v)),
+            DataBuffer::Array(v) => {
+                return Err(format!("{} can only be accessed by its elements", v.kind()));
+            }
+            DataBuffer::Unassigned => return Err("unassigned value".to_string()),
+        };
+
+        Ok(value)
+    }
+
+    /// converts the value into a buffer of the given kind,
+    /// integers are implicitly converted into reals and range-checked
+    pub(crate) fn to_buf(&self, kind: &DataKind) -> Result<DataBuffer, String> {
+        let out_of_range = || format!("{self} is out of range for {}", kind.iec_name());
+
+        let buf = match (self, kind) {
+            (Value::Bool(v), DataKind::Bool) => DataBuffer::Bool(*v),
+            (Value::Int(v), DataKind::SInt) => {
+                DataBuffer::SInt((*v).try_into().map_err(|_| out_of_range())?)
+            }
+            (Value::Int(v), DataKind::Int) => {
+                DataBuffer::Int((*v).try_into().map_err(|_| out_of_range())?)
+            }
+            (Value::Int(v), DataKind::DInt) => {
+                DataBuffer::DInt((*v).try_into().map_err(|_| out_of_range())?)
+            }
+            (Value::Int(v), DataKind::LInt) => {
+                DataBuffer::LInt((*v).try_into().map_err(|_| out_of_range())?)
+            }
+            (Value::Int(v), DataKind::USInt) => {
+                DataBuffer::USInt((*v).try_into().map_err(|_| out_of_range())?)
+            }
+            (Value::Int(v), DataKind::UInt) => {
+                DataBuffer::UInt((*v).try_into().map_err(|_| out_of_range())?)
+            }
+            (Value::Int(v), DataKind::UDInt) => {
+                DataBuffer::UDInt((*v).try_into().map_err(|_| out_of_range())?)
+            }
+            (Value::Int(v), DataKind::ULInt) => {
+                DataBuffer::ULInt((*v).try_into().map_err(|_| out_of_range())?)
+            }
+            (Value::Int(v), DataKind::Real) => DataBuffer::Real(*v as f32),
+            (Value::Int(v), DataKind::LReal) => DataBuffer::LReal(*v as f64),
+            (Value::Real(v), DataKind::Real) => DataBuffer::Real(*v as f32),
+            (Value::Real(v), DataKind::LReal) => DataBuffer::LReal(*v),
+            // untyped integer literals may be assigned to bit strings
+            (
+                Value::Int(v),
+                DataKind::Byte | DataKind::Word | DataKind::DWord | DataKind::LWord,
+            ) => bits(u64::try_from(*v).map_err(|_| out_of_range())?, kind)
+                .ok_or_else(out_of_range)?,
+            (
+                Value::Bits(v, _),
+                DataKind::Byte | DataKind::Word | DataKind::DWord | DataKind::LWord,
+            ) => bits(*v, kind).ok_or_else(out_of_range)?,
+            (Value::Time(v), DataKind::Time) => DataBuffer::Time(*v),
+            (Value::Time(v), DataKind::LTime) => DataBuffer::LTime(*v),
+            (Value::Date(v), DataKind::Date) => DataBuffer::Date(*v),
+            (Value::Date(v), DataKind::LDate) => DataBuffer::LDate(*v),
+            (Value::TimeOfDay(v), DataKind::TimeOfDay) => DataBuffer::TimeOfDay(*v),
+            (Value::TimeOfDay(v), DataKind::LTimeOfDay) => DataBuffer::LTimeOfDay(*v),
+            (Value::DateTime(v), DataKind::DateTime) => DataBuffer::DateTime(*v),
+            (Value::DateTime(v), DataKind::LDateTime) => DataBuffer::LDateTime(*v),
+            (Value::String(v), DataKind::String) => DataBuffer::String(v.as_bytes().to_vec()),
+            (Value::WString(v), DataKind::WString) => {
+                DataBuffer::WString(v.encode_utf16().collect())
+            }
+            _ => {
+                return Err(format!(
+                    "{self} ({}) can not be assigned to {}",
+                    self.type_name(),
+                    kind.iec_name()
+                ));
+            }
+        };
+
+        Ok(buf)
+    }
+
+    pub(crate) fn type_name(&self) -> Cow<'static, str> {
+        let name = match self {
+            Value::Bool(_) => "BOOL",
+            Value::Int(_) => "ANY_INT",
+            Value::Bits(_, kind) => return kind.iec_name(),
+            Value::Real(_) => "ANY_REAL",
+            Value::Time(_) => "TIME",
+            Value::Date(_) => "DATE",
+            Value::TimeOfDay(_) => "TIME_OF_DAY",
+            Value::DateTime(_) => "DATE_AND_TIME",
+            Value::String(_) => "STRING",
+            Value::WString(_) => "WSTRING",
+        };
+
+        Cow::Borrowed(name)
+    }
+}
+
+/// bit string of the given kind, `None` if the value does not fit
+fn bits(value: u64, kind: &DataKind) -> Option<DataBuffer> {
+    let buf = match kind {
+        DataKind::Byte => DataBuffer::Byte(value.try_into().ok()?),
+        DataKind::Word => DataBuffer::Word(value.try_into().ok()?),
+        DataKind::DWord => DataBuffer::DWord(value.try_into().ok()?),
+        _ => DataBuffer::LWord(value),
+    };
+
+    Some(buf)
+}
+
+/// number of bits of a bit string kind
+pub(crate) fn width(kind: &DataKind) -> u32 {
+    match kind {
+        DataKind::Byte => 8,
+        DataKind::Word => 16,
+        DataKind::DWord => 32,
+        _ => 64,
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Bool(v) => write!(f, "{}", if *v { "TRUE" } else { "FALSE" }),
+            Value::Int(v) => write!(f, "{v}"),
+            Value::Bits(v, kind) => write!(f, "{}#16#{v:X}", kind.iec_name()),
+            Value::Real(v) => write!(f, "{v:?}"),
+            Value::Time(v) => write!(f, "T#{}ms", v.as_millis()),
+            Value::Date(v) => write!(f, "D#{v}"),
+            Value::TimeOfDay(v) => write!(f, "TOD#{v}"),
+            Value::DateTime(v) => write!(f, "DT#{v}"),
+            Value::String(v) => write!(f, "'{v}'"),
+            Value::WString(v) => write!(f, "\"{v}\""),
+        }
+    }
+}
@@ -0,0 +1,434 @@
+//! Parser for function block type files (`.fbt`).
+//!
+//! ```text
+//! <FBType Name="E_CTU" Comment="...">
+//!   <InterfaceList>
+//!     <EventInputs>
+//!       <Event Name="CU" Type="Event"><With Var="PV"/></Event>
+//!     </EventInputs>
+//!     <EventOutputs>...</EventOutputs>
+//!     <InputVars>
+//!       <VarDeclaration Name="PV" Type="UINT" InitialValue="0"/>
+//!       <VarDeclaration Name="LIMITS" Type="INT" ArraySize="0..3" InitialValue="[4(100)]"/>
+//!     </InputVars>
+//!     <OutputVars>...</OutputVars>
+//!   </InterfaceList>
+//!   <BasicFB>
+//!     <InternalVars>...</InternalVars>
+//!     <ECC>
+//!       <ECState Name="START"/>
+//!       <ECState Name="CU"><ECAction Algorithm="CU" Output="CUO"/></ECState>
+//!       <ECTransition Source="START" Destination="CU" Condition="CU[CV &lt; 65535]"/>
+//!     </ECC>
+//!     <Algorithm Name="CU"><ST><![CDATA[CV := CV + 1;]]></ST></Algorithm>
+//!   </BasicFB>
+//! </FBType>
+//! ```
+
+use std::path::Path;
+
+use roxmltree::{Document, Node};
+
+use crate::fb::{
+    data::{
+        array,
+        ty::{ArrayKind, DataKind},
+    },
+    desc::{AlgorithmDesc, BasicFbDesc, EventDesc, FbBodyDesc, FbTypeDesc, InterfaceDesc, VarDesc},
+    ecc::{EcActionDesc, EcStateDesc, EcTransitionDesc, EccDesc},
+};
+
+use super::{XmlError, attr, attr_or_empty, child, children, read_file, required_child};
+
+/// parses the content of a `.fbt` file
+pub fn parse_fbt(text: &str) -> Result<FbTypeDesc, XmlError> {
+    let doc = Document::parse(text)?;
+    let root = doc.root_element();
+
+    if !root.has_tag_name("FBType") {
+        return Err(XmlError::at(
+            root,
+            format!("expected <FBType>, found <{}>", root.tag_name().name()),
+        ));
+    }
+
+    let interface = parse_interface(required_child(root, "InterfaceList")?)?;
+
+    let body = if let Some(basic) = child(root, "BasicFB") {
+        FbBodyDesc::Basic(parse_basic(basic, &interface)?)
+    } else if child(root, "FBNetwork").is_some() {
+        FbBodyDesc::Other("composite".to_string())
+    } else if child(root, "SimpleFB").is_some() {
+        FbBodyDesc::Other("simple".to_string())
+    } else {
+        FbBodyDesc::Other("service interface".to_string())
+    };
+
+    Ok(FbTypeDesc {
+        name: attr(root, "Name")?.to_string(),
+        comment: attr_or_empty(root, "Comment").to_string(),
+        interface,
+        body,
+    })
+}
+
+/// reads and parses a `.fbt` file
+pub fn parse_fbt_file(path: impl AsRef<Path>) -> Result<FbTypeDesc, XmlError> {
+    parse_fbt(&read_file(path.as_ref())?)
+}
+
+fn parse_interface(node: Node) -> Result<InterfaceDesc, XmlError> {
+    let input_vars = parse_vars(child(node, "InputVars"))?;
+    let output_vars = parse_vars(child(node, "OutputVars"))?;
+    let event_inputs = parse_events(child(node, "EventInputs"), &input_vars)?;
+    let event_outputs = parse_events(child(node, "EventOutputs"), &output_vars)?;
+
+    let interface = InterfaceDesc {
+        event_inputs,
+        event_outputs,
+        input_vars,
+        output_vars,
+    };
+
+    // port names need to be unique within the interface
+    let mut names: Vec<String> = vec![];
+
+    for port in children(node, "EventInputs")
+        .chain(children(node, "EventOutputs"))
+        .flat_map(|list| children(list, "Event"))
+        .chain(
+            children(node, "InputVars")
+                .chain(children(node, "OutputVars"))
+                .flat_map(|list| children(list, "VarDeclaration")),
+        )
+    {
+        let name = attr(port, "Name")?.to_uppercase();
+
+        if names.contains(&name) {
+            return Err(XmlError::at_attr(
+                port,
+                "Name",
+                format!("duplicate port name \"{name}\""),
+            ));
+        }
+
+        names.push(name);
+    }
+
+    Ok(interface)
+}
+
+/// parses events and validates their WITH associations against the data of the same direction
+fn parse_events(list: Option<Node>, vars: &[VarDesc]) -> Result<Vec<EventDesc>, XmlError> {
+    let Some(list) = list else {
+        return Ok(vec![]);
+    };
+
+    let mut events = vec![];
+
+    for event in children(list, "Event") {
+        let mut with = vec![];
+
+        for w in children(event, "With") {
+            let var = attr(w, "Var")?;
+
+            if !vars.iter().any(|v| v.name.eq_ignore_ascii_case(var)) {
+                return Err(XmlError::at_attr(
+                    w,
+                    "Var",
+                    format!("WITH references unknown data \"{var}\""),
+                ));
+            }
+
+            with.push(var.to_string());
+        }
+
+        events.push(EventDesc {
+            name: attr(event, "Name")?.to_string(),
+            with,
+            comment: attr_or_empty(event, "Comment").to_string(),
+        });
+    }
+
+    Ok(events)
+}
+
+fn parse_vars(list: Option<Node>) -> Result<Vec<VarDesc>, XmlError> {
+    let Some(list) = list else {
+        return Ok(vec![]);
+    };
+
+    children(list, "VarDeclaration").map(parse_var).collect()
+}
+
+fn parse_var(node: Node) -> Result<VarDesc, XmlError> {
+    let ty = attr(node, "Type")?;
+
+    let Some(mut kind) = DataKind::from_iec_name(ty) else {
+        return Err(XmlError::at_attr(
+            node,
+            "Type",
+            format!("unknown data type \"{ty}\""),
+        ));
+    };
+
+    if let Some(size) = node.attribute("ArraySize") {
+        let Some(array) = array_kind(size, kind) else {
+            return Err(XmlError::at_attr(
+                node,
+                "ArraySize",
+                format!("invalid array size \"{size}\""),
+            ));
+        };
+
+        kind = DataKind::Array(Box::new(array));
+    }
+
+    Ok(VarDesc {
+        name: attr(node, "Name")?.to_string(),
+        kind,
+        initial_value: node
+            .attribute("InitialValue")
+            .filter(|v| !v.trim().is_empty())
+            .map(str::to_string),
+        comment: attr_or_empty(node, "Comment").to_string(),
+    })
+}
+
+/// `ArraySize` is either the number of elements (`16` for `[0..15]`) or the bounds (`1..16`, `0..3, 0..1`)
+fn array_kind(size: &str, element: DataKind) -> Option<ArrayKind> {
+    let size = size.trim();
+    let size = size
+        .strip_prefix('[')
+        .and_then(|s| s.strip_suffix(']'))
+        .unwrap_or(size);
+
+    let bounds = match size.parse::<i64>() {
+        Ok(len) => vec![0..=len.checked_sub(1)?],
+        Err(_) => array::parse_bounds(size)?,
+    };
+
+    ArrayKind::new(bounds, element)
+}
+
+fn parse_basic(node: Node, interface: &InterfaceDesc) -> Result<BasicFbDesc, XmlError> {
+    let internal_vars = parse_vars(child(node, "InternalVars"))?;
+
+    for var in children(node, "InternalVars").flat_map(|l| children(l, "VarDeclaration")) {
+        let name = attr(var, "Name")?;
+
+        if interface.input_var(name).is_some()
+            || interface.output_var(name).is_some()
+            || interface.event_input(name).is_some()
+            || interface.event_output(name).is_some()
+        {
+            return Err(XmlError::at_attr(
+                var,
+                "Name",
+                format!("internal variable \"{name}\" shadows a port"),
+            ));
+        }
+    }
+
+    let mut algorithms = vec![];
+
+    for alg in children(node, "Algorithm") {
+        algorithms.push(parse_algorithm(alg)?);
+    }
+
+    let ecc = parse_ecc(required_child(node, "ECC")?, interface, &algorithms)?;
+
+    Ok(BasicFbDesc {
+        internal_vars,
+        ecc,
+        algorithms,
+    })
+}
+
+fn parse_algorithm(node: Node) -> Result<AlgorithmDesc, XmlError> {
+    let name = attr(node, "Name")?.to_string();
+    let comment = attr_or_empty(node, "Comment").to_string();
+
+    let Some(body) = node.children().find(|c| c.is_element()) else {
+        return Err(XmlError::at(
+            node,
+            format!("algorithm \"{name}\" has no body"),
+        ));
+    };
+
+    let language = match body.tag_name().name() {
+        "Other" => attr(body, "Language")?.to_string(),
+        language => language.to_string(),
+    };
+
+    // older files store the body in a `Text` attribute, newer ones as (CDATA) content
+    let text = match body.attribute("Text") {
+        Some(text) => text.to_string(),
+        None => body.children().filter_map(|c| c.text()).collect::<String>(),
+    };
+
+    Ok(AlgorithmDesc {
+        name,
+        comment,
+        language,
+        text,
+    })
+}
+
+fn parse_ecc(
+    node: Node,
+    interface: &InterfaceDesc,
+    algorithms: &[AlgorithmDesc],
+) -> Result<EccDesc, XmlError> {
+    let mut states: Vec<EcStateDesc> = vec![];
+
+    for state in children(node, "ECState") {
+        let name = attr(state, "Name")?;
+
+        if states.iter().any(|s| s.name == name) {
+            return Err(XmlError::at_attr(
+                state,
+                "Name",
+                format!("duplicate EC state \"{name}\""),
+            ));
+        }
+
+        let mut actions = vec![];
+
+        for action in children(state, "ECAction") {
+            let algorithm = action.attribute("Algorithm").filter(|a| !a.is_empty());
+            let output = action.attribute("Output").filter(|o| !o.is_empty());
+
+            if let Some(algorithm) = algorithm
+                && !algorithms.iter().any(|a| a.name == algorithm)
+            {
+                return Err(XmlError::at_attr(
+                    action,
+                    "Algorithm",
+                    format!("unknown algorithm \"{algorithm}\""),
+                ));
+            }
+
+            if let Some(output) = output
+                && interface.event_output(output).is_none()
+            {
+                return Err(XmlError::at_attr(
+                    action,
+                    "Output",
+                    format!("unknown output event \"{output}\""),
+                ));
+            }
+
+            actions.push(EcActionDesc {
+                algorithm: algorithm.map(str::to_string),
+                output: output.map(str::to_string),
+            });
+        }
+
+        states.push(EcStateDesc {
+            name: name.to_string(),
+            actions,
+        });
+    }
+
+    if states.is_empty() {
+        return Err(XmlError::at(node, "the ECC has no states"));
+    }
+
+    let mut transitions = vec![];
+
+    for transition in children(node, "ECTransition") {
+        let source = attr(transition, "Source")?;
+        let destination = attr(transition, "Destination")?;
+
+        for (attribute, state) in [("Source", source), ("Destination", destination)] {
+            if !states.iter().any(|s| s.name == state) {
+                return Err(XmlError::at_attr(
+                    transition,
+                    attribute,
+                    format!("unknown EC state \"{state}\""),
+                ));
+            }
+        }
+
+        let (event, guard) = parse_condition(transition, interface)?;
+
+        transitions.push(EcTransitionDesc {
+            source: source.to_string(),
+            destination: destination.to_string(),
+            event,
+            guard,
+        });
+    }
+
+    Ok(EccDesc {
+        states,
+        transitions,
+    })
+}
+
+/// splits a transition condition like `CU[CV < 65535]` into event and guard,
+/// conditions which do not start with `EVENT[` are guards only (e.g. `X[1] > 0`)
+fn parse_condition(
+    node: Node,
+    interface: &InterfaceDesc,
+) -> Result<(Option<String>, Option<String>), XmlError> {
+    let condition = attr(node, "Condition")?.trim();
+
+    if condition == "1" || condition.eq_ignore_ascii_case("TRUE") {
+        return Ok((None, None));
+    }
+
+    // `EV[guard]`, the prefix is only an event if the interface declares it,
+    // otherwise it indexes an array in a guard like `FLAGS[1]`
+    let open = condition
+        .find('[')
+        .filter(|&open| interface.event_input(condition[..open].trim()).is_some());
+
+    let (event, guard) = match open {
+        Some(open) => match closing_bracket(condition, open) {
+            // the guard spans the rest of the condition
+            Some(close) if close == condition.len() - 1 => {
+                (condition[..open].trim(), condition[open + 1..close].trim())
+            }
+            Some(_) => ("", condition),
+            None => {
+                return Err(XmlError::at_attr(
+                    node,
+                    "Condition",
+                    format!("unterminated guard in condition \"{condition}\""),
+                ));
+            }
+        },
+        None if interface.event_input(condition).is_some() => (condition, ""),
+        None => ("", condition),
+    };
+
+    let event = match event {
+        "" => None,
+        event => interface.event_input(event).map(|e| e.name.clone()),
+    };
+
+    let guard = (!guard.is_empty()).then(|| guard.to_string());
+
+    Ok((event, guard))
+}
+
+/// returns the index of the `]` closing the `[` at `open`
+fn closing_bracket(text: &str, open: usize) -> Option<usize> {
+    let mut depth = 0;
+
+    for (i, c) in text.char_indices().skip_while(|(i, _)| *i < open) {
+        match c {
+            '[' => depth += 1,
+            ']' => depth -= 1,
+            _ => {}
+        }
+
+        if depth == 0 {
+            return Some(i);
+        }
+    }
+
+    None
+}
@@ -0,0 +1,138 @@
+//! Parsers for `IEC 61499-2` XML files (e.g. as written by 4diac IDE):
+//! - `fbt`: function block type files (`.fbt`)
+//! - `sys`: system configuration files (`.sys`)
+
+use std::fmt::Display;
+
+use roxmltree::{Document, Node};
+
+pub mod fbt;
+pub mod sys;
+
+/// line and column inside of the parsed document (both starting at 1)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Location {
+    pub line: u32,
+    pub column: u32,
+}
+
+#[derive(Clone, Debug)]
+pub struct XmlError {
+    pub location: Option<Location>,
+    pub message: String,
+}
+
+impl XmlError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            location: None,
+            message: message.into(),
+        }
+    }
+
+    /// error located at the start of the given element
+    pub fn at(node: Node, message: impl Into<String>) -> Self {
+        Self {
+            location: Some(text_location(node.document(), node.range().start)),
+            message: message.into(),
+        }
+    }
+
+    /// error located at the value of the given attribute,
+    /// falls back to the element if the attribute does not exist
+    pub fn at_attr(node: Node, attr: &str, message: impl Into<String>) -> Self {
+        Self {
+            location: Some(location(node, attr)),
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for XmlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.location {
+            Some(Location { line, column }) => write!(f, "{line}:{column}: {}", self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for XmlError {}
+
+impl From<roxmltree::Error> for XmlError {
+    fn from(e: roxmltree::Error) -> Self {
+        let pos = e.pos();
+
+        Self {
+            location: Some(Location {
+                line: pos.row,
+                column: pos.col,
+            }),
+            message: e.to_string(),
+        }
+    }
+}
+
+fn text_location(doc: &Document, pos: usize) -> Location {
+    let pos = doc.text_pos_at(pos);
+
+    Location {
+        line: pos.row,
+        column: pos.col,
+    }
+}
+
+/// location of the value of the given attribute, or of the element if the attribute does not exist
+fn location(node: Node, attr: &str) -> Location {
+    match node.attribute_node(attr) {
+        Some(a) => text_location(node.document(), a.range_value().start),
+        None => text_location(node.document(), node.range().start),
+    }
+}
+
+/// returns the value of a required attribute
+fn attr<'a>(node: Node<'a, '_>, name: &str) -> Result<&'a str, XmlError> {
+    node.attribute(name).ok_or_else(|| {
+        XmlError::at(
+            node,
+            format!(
+                "<{}> is missing the attribute \"{name}\"",
+                node.tag_name().name()
+            ),
+        )
+    })
+}
+
+/// returns the value of an optional attribute, defaults to an empty string
+fn attr_or_empty<'a>(node: Node<'a, '_>, name: &str) -> &'a str {
+    node.attribute(name).unwrap_or_default()
+}
+
+/// iterates over all child elements with the given tag name
+fn children<'a, 'i>(node: Node<'a, 'i>, tag: &'static str) -> impl Iterator<Item = Node<'a, 'i>> {
+    node.children()
+        .filter(move |c| c.is_element() && c.has_tag_name(tag))
+}
+
+fn child<'a, 'i>(node: Node<'a, 'i>, tag: &'static str) -> Option<Node<'a, 'i>> {
+    children(node, tag).next()
+}
+
+/// returns the first child element with the given tag name, fails if it does not exist
+fn required_child<'a, 'i>(node: Node<'a, 'i>, tag: &'static str) -> Result<Node<'a, 'i>, XmlError> {
+    child(node, tag).ok_or_else(|| {
+        XmlError::at(
+            node,
+            format!(
+                "<{}> is missing the element <{tag}>",
+                node.tag_name().name()
+            ),
+        )
+    })
+}
+
+/// reads a file into a string, io errors are reported without location
+fn read_file(path: &std::path::Path) -> Result<String, XmlError> {
+    std::fs::read_to_string(path)
+        .map_err(|e| XmlError::new(format!("can not read \"{}\": {e}", path.display())))
+}
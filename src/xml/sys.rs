@@ -0,0 +1,155 @@
+//! Parser for system configuration files (`.sys`).
+//!
+//! Only the function block networks of the resources are read,
+//! applications and their mapping onto resources are ignored.
+//!
+//! ```text
+//! <System Name="Counter">
+//!   <Device Name="Dev" Type="FORTE_PC">
+//!     <Resource Name="Res" Type="EMB_RES">
+//!       <FBNetwork>
+//!         <FB Name="ctu0" Type="E_CTU"><Parameter Name="PV" Value="3"/></FB>
+//!         <EventConnections>
+//!           <Connection Source="START.COLD" Destination="ctu0.CU"/>
+//!         </EventConnections>
+//!         <DataConnections>...</DataConnections>
+//!       </FBNetwork>
+//!     </Resource>
+//!   </Device>
+//! </System>
+//! ```
+
+use std::path::Path;
+
+use roxmltree::{Document, Node};
+
+use crate::run_time::config::{
+    ConnectionDesc, DeviceDesc, FbInstanceDesc, FbNetworkDesc, ParameterDesc, PortRef,
+    ResourceDesc, SystemDesc,
+};
+
+use super::{XmlError, attr, attr_or_empty, child, children, location, read_file};
+
+/// parses the content of a `.sys` file
+pub fn parse_sys(text: &str) -> Result<SystemDesc, XmlError> {
+    let doc = Document::parse(text)?;
+    let root = doc.root_element();
+
+    if !root.has_tag_name("System") {
+        return Err(XmlError::at(
+            root,
+            format!("expected <System>, found <{}>", root.tag_name().name()),
+        ));
+    }
+
+    let mut devices = vec![];
+
+    for device in children(root, "Device") {
+        let mut resources = vec![];
+
+        for resource in children(device, "Resource") {
+            let network = match child(resource, "FBNetwork") {
+                Some(network) => parse_network(network)?,
+                None => FbNetworkDesc::default(),
+            };
+
+            resources.push(ResourceDesc {
+                name: attr(resource, "Name")?.to_string(),
+                type_name: attr_or_empty(resource, "Type").to_string(),
+                network,
+            });
+        }
+
+        devices.push(DeviceDesc {
+            name: attr(device, "Name")?.to_string(),
+            type_name: attr_or_empty(device, "Type").to_string(),
+            resources,
+        });
+    }
+
+    Ok(SystemDesc {
+        name: attr(root, "Name")?.to_string(),
+        comment: attr_or_empty(root, "Comment").to_string(),
+        devices,
+    })
+}
+
+/// reads and parses a `.sys` file
+pub fn parse_sys_file(path: impl AsRef<Path>) -> Result<SystemDesc, XmlError> {
+    parse_sys(&read_file(path.as_ref())?)
+}
+
+/// parses an `<FBNetwork>`, instance names need to be unique within the network
+pub fn parse_network(node: Node) -> Result<FbNetworkDesc, XmlError> {
+    let mut fbs: Vec<FbInstanceDesc> = vec![];
+
+    for fb in children(node, "FB") {
+        let name = attr(fb, "Name")?;
+
+        if fbs.iter().any(|f| f.name.eq_ignore_ascii_case(name)) {
+            return Err(XmlError::at_attr(
+                fb,
+                "Name",
+                format!("duplicate instance name \"{name}\""),
+            ));
+        }
+
+        let mut parameters = vec![];
+
+        for parameter in children(fb, "Parameter") {
+            parameters.push(ParameterDesc {
+                name: attr(parameter, "Name")?.to_string(),
+                value: attr(parameter, "Value")?.to_string(),
+                location: Some(location(parameter, "Value")),
+            });
+        }
+
+        fbs.push(FbInstanceDesc {
+            name: name.to_string(),
+            type_name: attr(fb, "Type")?.to_string(),
+            parameters,
+            location: Some(location(fb, "Type")),
+        });
+    }
+
+    Ok(FbNetworkDesc {
+        fbs,
+        event_connections: parse_connections(child(node, "EventConnections"))?,
+        data_connections: parse_connections(child(node, "DataConnections"))?,
+    })
+}
+
+fn parse_connections(list: Option<Node>) -> Result<Vec<ConnectionDesc>, XmlError> {
+    let Some(list) = list else {
+        return Ok(vec![]);
+    };
+
+    let mut connections = vec![];
+
+    for conn in children(list, "Connection") {
+        connections.push(ConnectionDesc {
+            source: parse_port_ref(conn, "Source")?,
+            destination: parse_port_ref(conn, "Destination")?,
+            location: Some(location(conn, "Source")),
+        });
+    }
+
+    Ok(connections)
+}
+
+/// splits `instance.port` into its parts
+fn parse_port_ref(node: Node, attribute: &str) -> Result<PortRef, XmlError> {
+    let value = attr(node, attribute)?;
+
+    match value.rsplit_once('.') {
+        Some((instance, port)) if !instance.is_empty() && !port.is_empty() => Ok(PortRef {
+            instance: instance.to_string(),
+            port: port.to_string(),
+        }),
+        _ => Err(XmlError::at_attr(
+            node,
+            attribute,
+            format!("expected \"instance.port\", found \"{value}\""),
+        )),
+    }
+}